@@ -0,0 +1,228 @@
+/*
+Project: Optic
+GitHub: https://github.com/MillieFD/optic
+
+BSD 3-Clause License, Copyright (c) 2026, Amelia Fraser-Dale
+
+Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the conditions of the LICENSE are met.
+*/
+
+/* ------------------------------------------------------------------------------ Public Exports */
+
+/// Dense symmetric matrix stored row-major.
+pub(super) struct Symmetric {
+    pub size: usize,
+    pub values: Vec<f64>,
+}
+
+impl Symmetric {
+    pub(super) fn zeros(size: usize) -> Self {
+        Self {
+            size,
+            values: vec![0.0; size * size],
+        }
+    }
+
+    /// Add the outer product `v·vᵀ` to the upper triangle. Call [`Symmetric::mirror`] once all
+    /// updates are complete.
+    pub(super) fn add_outer(&mut self, v: &[f64]) {
+        for (i, vi) in v.iter().enumerate() {
+            let row = &mut self.values[i * self.size..(i + 1) * self.size];
+            row[i..]
+                .iter_mut()
+                .zip(&v[i..])
+                .for_each(|(value, vj)| *value += vi * vj);
+        }
+    }
+
+    /// Copy the upper triangle into the lower triangle.
+    pub(super) fn mirror(&mut self) {
+        for i in 0..self.size {
+            for j in 0..i {
+                self.values[i * self.size + j] = self.values[j * self.size + i];
+            }
+        }
+    }
+
+    fn multiply(&self, v: &[f64]) -> Vec<f64> {
+        self.values
+            .chunks_exact(self.size)
+            .map(|row| dot(row, v))
+            .collect()
+    }
+
+    /// The `k` largest eigenvalues and their unit eigenvectors by orthogonal iteration. Each
+    /// eigenvector is signed so that its largest-magnitude element is positive.
+    pub(super) fn eigen(&self, k: usize) -> (Vec<f64>, Vec<Vec<f64>>) {
+        const ITERATIONS: usize = 1000;
+        const TOLERANCE: f64 = 1E-12;
+        let mut state: u64 = 0x9E37_79B9_7F4A_7C15; // Deterministic pseudo-random start
+        let mut vectors: Vec<Vec<f64>> = (0..k)
+            .map(|_| {
+                (0..self.size)
+                    .map(|_| {
+                        state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+                        (state >> 11) as f64 / (1u64 << 53) as f64 - 0.5
+                    })
+                    .collect()
+            })
+            .collect();
+        orthonormalise(&mut vectors);
+        for _ in 0..ITERATIONS {
+            let mut next: Vec<Vec<f64>> = vectors.iter().map(|v| self.multiply(v)).collect();
+            orthonormalise(&mut next);
+            let change = next
+                .iter()
+                .zip(&vectors)
+                .map(|(a, b)| 1.0 - dot(a, b).abs())
+                .fold(0.0, f64::max);
+            vectors = next;
+            if change < TOLERANCE {
+                break;
+            }
+        }
+        for vector in &mut vectors {
+            let largest = vector
+                .iter()
+                .copied()
+                .fold(0.0, |a: f64, b| match b.abs() > a.abs() {
+                    true => b,
+                    false => a,
+                });
+            if largest < 0.0 {
+                vector.iter_mut().for_each(|v| *v = -*v);
+            }
+        }
+        let values = vectors.iter().map(|v| dot(v, &self.multiply(v))).collect();
+        (values, vectors)
+    }
+}
+
+/// Non-negative least squares `min ‖Ax − b‖` subject to `x ≥ 0` by the Lawson–Hanson active set
+/// method, expressed in terms of the normal equations `AᵀA` and `Aᵀb`.
+pub(super) fn nnls(ata: &[Vec<f64>], atb: &[f64]) -> Vec<f64> {
+    const TOLERANCE: f64 = 1E-12;
+    let n = atb.len();
+    let mut x = vec![0.0; n];
+    let mut passive = vec![false; n];
+    for _ in 0..3 * n.max(1) {
+        let gradient: Vec<f64> = (0..n).map(|i| atb[i] - dot(&ata[i], &x)).collect();
+        let candidate = (0..n)
+            .filter(|&i| !passive[i] && gradient[i] > TOLERANCE)
+            .max_by(|&a, &b| gradient[a].total_cmp(&gradient[b]));
+        let Some(j) = candidate else { break };
+        passive[j] = true;
+        loop {
+            let indices: Vec<usize> = (0..n).filter(|&i| passive[i]).collect();
+            let matrix = indices
+                .iter()
+                .map(|&r| indices.iter().map(|&c| ata[r][c]).collect())
+                .collect();
+            let rhs = indices.iter().map(|&r| vec![atb[r]]).collect();
+            let Some(solution) = solve(matrix, rhs) else {
+                passive[j] = false; // Dependent on the passive set
+                break;
+            };
+            let mut s = vec![0.0; n];
+            indices
+                .iter()
+                .zip(&solution)
+                .for_each(|(&i, v)| s[i] = v[0]);
+            if indices.iter().all(|&i| s[i] > TOLERANCE) {
+                x = s;
+                break;
+            }
+            let alpha = indices
+                .iter()
+                .filter(|&&i| s[i] <= TOLERANCE)
+                .map(|&i| x[i] / (x[i] - s[i]))
+                .fold(f64::INFINITY, f64::min);
+            for i in 0..n {
+                x[i] += alpha * (s[i] - x[i]);
+                if passive[i] && x[i] <= TOLERANCE {
+                    passive[i] = false;
+                    x[i] = 0.0;
+                }
+            }
+            if !passive.iter().any(|p| *p) {
+                break;
+            }
+        }
+    }
+    x
+}
+
+pub(crate) fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
+
+/* ----------------------------------------------------------------------------- Private Helpers */
+
+/// Modified Gram–Schmidt orthonormalisation in place.
+fn orthonormalise(vectors: &mut [Vec<f64>]) {
+    for i in 0..vectors.len() {
+        let (done, rest) = vectors.split_at_mut(i);
+        let vector = &mut rest[0];
+        for basis in done.iter() {
+            let projection = dot(vector, basis);
+            vector
+                .iter_mut()
+                .zip(basis)
+                .for_each(|(v, b)| *v -= projection * b);
+        }
+        let norm = dot(vector, vector).sqrt();
+        if norm > 0.0 {
+            vector.iter_mut().for_each(|v| *v /= norm);
+        }
+    }
+}
+
+/// Solve `matrix · X = rhs` for every column of `rhs` at once by Gaussian elimination with
+/// partial pivoting, where `rhs` has one row per row of `matrix`. Returns `None` if `matrix` is
+/// singular to working precision.
+pub(crate) fn solve(mut matrix: Vec<Vec<f64>>, mut rhs: Vec<Vec<f64>>) -> Option<Vec<Vec<f64>>> {
+    let n = matrix.len();
+    let scale = matrix
+        .iter()
+        .flatten()
+        .fold(0.0, |max: f64, value| max.max(value.abs()));
+    for column in 0..n {
+        let pivot = (column..n)
+            .max_by(|&a, &b| matrix[a][column].abs().total_cmp(&matrix[b][column].abs()))?;
+        let magnitude = matrix[pivot][column].abs();
+        if magnitude.is_nan() || magnitude <= f64::EPSILON * scale {
+            return None;
+        }
+        matrix.swap(column, pivot);
+        rhs.swap(column, pivot);
+        let (upper, lower) = matrix.split_at_mut(column + 1);
+        let (known, unknown) = rhs.split_at_mut(column + 1);
+        let (pivot, reference) = (&upper[column], &known[column]);
+        for (row, values) in lower.iter_mut().zip(unknown) {
+            let factor = row[column] / pivot[column];
+            row.iter_mut()
+                .zip(pivot)
+                .skip(column)
+                .for_each(|(value, p)| *value -= factor * p);
+            values
+                .iter_mut()
+                .zip(reference)
+                .for_each(|(value, r)| *value -= factor * r);
+        }
+    }
+    for row in (0..n).rev() {
+        let (upper, solved) = rhs.split_at_mut(row + 1);
+        let values = &mut upper[row];
+        for (coefficient, solution) in matrix[row][row + 1..].iter().zip(solved.iter()) {
+            values
+                .iter_mut()
+                .zip(solution)
+                .for_each(|(value, x)| *value -= coefficient * x);
+        }
+        values
+            .iter_mut()
+            .for_each(|value| *value /= matrix[row][row]);
+    }
+    Some(rhs)
+}
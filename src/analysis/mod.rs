@@ -0,0 +1,343 @@
+/*
+Project: Optic
+GitHub: https://github.com/MillieFD/optic
+
+BSD 3-Clause License, Copyright (c) 2026, Amelia Fraser-Dale
+
+Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the conditions of the LICENSE are met.
+*/
+
+/* ----------------------------------------------------------------------------- Private Modules */
+
+pub(crate) mod linalg;
+
+/* ----------------------------------------------------------------------------- Private Imports */
+
+use std::collections::{HashMap, HashSet};
+
+use self::linalg::{Symmetric, dot, nnls};
+use crate::intensities::{Spectrum, group};
+use crate::{Database, Error, Provenance, Source};
+
+/* ------------------------------------------------------------------------------ Public Exports */
+
+/// Principal components of a set of spectra over a fixed list of wavelength IDs.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Pca {
+    pub wavelengths: Vec<u32>,
+    pub mean: Vec<f64>,
+    /// One unit loading vector per component, in order of decreasing variance.
+    pub loadings: Vec<Vec<f64>>,
+    /// Variance explained by each component.
+    pub variances: Vec<f64>,
+    /// Number of spectra the components were computed from.
+    pub count: usize,
+}
+
+impl Pca {
+    /// Project a spectrum ordered as [`Pca::wavelengths`] onto each component.
+    pub fn scores(&self, row: &[f64]) -> Vec<f64> {
+        let centred: Vec<f64> = row.iter().zip(&self.mean).map(|(v, m)| v - m).collect();
+        self.loadings
+            .iter()
+            .map(|loading| dot(loading, &centred))
+            .collect()
+    }
+}
+
+impl Database {
+    /// Compute the first `components` principal components of the spectra of `measurements` from
+    /// `source` over `wavelengths`. Spectra are streamed one batch at a time so only the
+    /// covariance matrix, and any spectrum still split across batches, is held in memory.
+    /// Measurements missing any wavelength are skipped.
+    pub fn pca(
+        &self,
+        source: Source,
+        measurements: &[u32],
+        wavelengths: &[u32],
+        components: usize,
+    ) -> Result<Pca, Error> {
+        let p = wavelengths.len();
+        if components == 0 || components > p {
+            let e = format!("{components} components requested from {p} wavelengths");
+            return Err(Error::InvalidParameter(e));
+        }
+        let mut shift: Option<Vec<f64>> = None; // First spectrum, subtracted for stability
+        let mut sum = vec![0.0; p];
+        let mut products = Symmetric::zeros(p);
+        let mut count = 0;
+        self.scan(source, measurements, wavelengths, |_, row| {
+            let shift = shift.get_or_insert_with(|| row.to_vec());
+            let shifted: Vec<f64> = row.iter().zip(shift.iter()).map(|(v, s)| v - s).collect();
+            sum.iter_mut().zip(&shifted).for_each(|(s, v)| *s += v);
+            products.add_outer(&shifted);
+            count += 1;
+            Ok(())
+        })?;
+        if count < 2 {
+            let e = format!("PCA needs at least 2 complete spectra, found {count}");
+            return Err(Error::InvalidParameter(e));
+        }
+        let n = count as f64;
+        products.mirror();
+        for i in 0..p {
+            for j in 0..p {
+                let value = &mut products.values[i * p + j];
+                *value = (*value - sum[i] * sum[j] / n) / (n - 1.0);
+            }
+        }
+        let (variances, loadings) = products.eigen(components);
+        let shift = shift.unwrap_or_default();
+        let mean = sum.iter().zip(&shift).map(|(s, v)| s / n + v).collect();
+        Ok(Pca {
+            wavelengths: wavelengths.to_vec(),
+            mean,
+            loadings,
+            variances,
+            count,
+        })
+    }
+
+    /// Project the spectra of `measurements` onto `pca` and write the scores to the scalar table
+    /// called `name` with columns `pc1`, `pc2`, …. Returns the IDs of the projected measurements.
+    pub fn pca_scores(
+        &self,
+        name: &str,
+        source: Source,
+        measurements: &[u32],
+        pca: &Pca,
+    ) -> Result<Vec<u32>, Error> {
+        let columns: Vec<String> = (1..=pca.loadings.len()).map(|k| format!("pc{k}")).collect();
+        let columns: Vec<&str> = columns.iter().map(String::as_str).collect();
+        let variances: Vec<String> = pca.variances.iter().map(f64::to_string).collect();
+        let provenance = Provenance::new("pca", &[source.name(), "measurements"])
+            .parameter("components", pca.loadings.len())
+            .parameter("count", pca.count)
+            .parameter("variances", variances.join(","));
+        let mut table = self.create_scalars(name, &columns, &provenance)?;
+        let mut ids = Vec::new();
+        self.scan(source, measurements, &pca.wavelengths, |id, row| {
+            table.push(id, &pca.scores(row))?;
+            ids.push(id);
+            Ok(())
+        })?;
+        table.commit()?;
+        Ok(ids)
+    }
+
+    /// Unmix the spectra of `measurements` into non-negative abundances of the named
+    /// `endmembers` and write them to the scalar table called `name`, with one column per
+    /// endmember and a `residual` column holding the root-mean-square misfit. Only wavelengths
+    /// present in every endmember are used. Returns the IDs of the unmixed measurements.
+    pub fn unmix(
+        &self,
+        name: &str,
+        source: Source,
+        measurements: &[u32],
+        endmembers: &[(&str, &Spectrum)],
+    ) -> Result<Vec<u32>, Error> {
+        let wavelengths: Vec<u32> = match endmembers.first() {
+            Some((_, first)) => first
+                .keys()
+                .filter(|id| endmembers.iter().all(|(_, e)| e.contains_key(id)))
+                .copied()
+                .collect(),
+            None => Vec::new(),
+        };
+        if wavelengths.is_empty() {
+            let e = "endmembers share no wavelengths".to_string();
+            return Err(Error::InvalidParameter(e));
+        }
+        let matrix: Vec<Vec<f64>> = endmembers
+            .iter()
+            .map(|(_, e)| wavelengths.iter().map(|id| e[id]).collect())
+            .collect();
+        let ata: Vec<Vec<f64>> = matrix
+            .iter()
+            .map(|a| matrix.iter().map(|b| dot(a, b)).collect())
+            .collect();
+        let columns: Vec<&str> = endmembers
+            .iter()
+            .map(|(name, _)| *name)
+            .chain(["residual"])
+            .collect();
+        let provenance = Provenance::new("unmix", &[source.name(), "measurements"])
+            .parameter("endmembers", columns[..endmembers.len()].join(","))
+            .parameter("method", "nnls");
+        let mut table = self.create_scalars(name, &columns, &provenance)?;
+        let mut ids = Vec::new();
+        self.scan(source, measurements, &wavelengths, |id, row| {
+            let atb: Vec<f64> = matrix.iter().map(|a| dot(a, row)).collect();
+            let mut abundances = nnls(&ata, &atb);
+            let squares: f64 = (0..row.len())
+                .map(|i| {
+                    let model: f64 = abundances.iter().zip(&matrix).map(|(x, a)| x * a[i]).sum();
+                    (row[i] - model).powi(2)
+                })
+                .sum();
+            abundances.push((squares / row.len() as f64).sqrt());
+            table.push(id, &abundances)?;
+            ids.push(id);
+            Ok(())
+        })?;
+        table.commit()?;
+        Ok(ids)
+    }
+
+    /// Stream the spectra of `measurements` from `source` as dense rows ordered by `wavelengths`,
+    /// skipping any measurement that lacks a value at one of them. Each row is passed to `f` as
+    /// soon as it is complete and then dropped, so only spectra committed in parts across several
+    /// batches are held in memory until their last part is read. Stops at the first error
+    /// returned by `f`.
+    fn scan<F>(
+        &self,
+        source: Source,
+        measurements: &[u32],
+        wavelengths: &[u32],
+        mut f: F,
+    ) -> Result<(), Error>
+    where
+        F: FnMut(u32, &[f64]) -> Result<(), Error>,
+    {
+        let wanted: HashSet<&u32> = measurements.iter().collect();
+        let columns: HashMap<u32, usize> = wavelengths
+            .iter()
+            .enumerate()
+            .map(|(index, id)| (*id, index))
+            .collect();
+        let mut partial: HashMap<u32, Vec<f64>> = HashMap::new();
+        let mut done: HashSet<u32> = HashSet::new();
+        let (reader, value) = self.stream(source)?;
+        for batch in reader {
+            let spectra = group(&[batch?], value, |id| {
+                wanted.contains(&id) && !done.contains(&id)
+            });
+            let mut ids: Vec<&u32> = spectra.keys().collect();
+            ids.sort_unstable();
+            for id in ids {
+                let mut row = partial
+                    .remove(id)
+                    .unwrap_or_else(|| vec![f64::NAN; wavelengths.len()]);
+                spectra[id]
+                    .iter()
+                    .filter_map(|(wavelength, v)| Some((*columns.get(wavelength)?, *v)))
+                    .for_each(|(index, v)| row[index] = v);
+                match row.iter().all(|v| !v.is_nan()) {
+                    true => {
+                        f(*id, &row)?;
+                        done.insert(*id);
+                    }
+                    false => _ = partial.insert(*id, row),
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/* ---------------------------------------------------------------------------------- Unit Tests */
+
+#[cfg(all(
+    test,
+    feature = "x",
+    feature = "y",
+    not(feature = "z"),
+    not(feature = "a")
+))]
+mod tests {
+    use std::fs::remove_dir_all;
+
+    use uom::si::f64::{Length, Time};
+    use uom::si::length::micrometer;
+    use uom::si::time::millisecond;
+
+    use super::*;
+    use crate::Kind;
+
+    #[test]
+    fn pca_and_unmixing() {
+        const PATH: &str = "test-analysis";
+        let mut db = Database::new(PATH).unwrap();
+        let wavelengths = db.wavelengths.push(vec![400.0, 500.0, 600.0]).unwrap();
+        db.wavelengths.commit().unwrap();
+        let integration = Time::new::<millisecond>(10.0);
+        let e1 = Spectrum::from_iter(wavelengths.iter().copied().zip([1.0, 0.0, 1.0]));
+        let e2 = Spectrum::from_iter(wavelengths.iter().copied().zip([0.0, 1.0, 1.0]));
+        let ids: Vec<u32> = [0.0, 0.25, 0.5, 1.0]
+            .into_iter()
+            .map(|t| {
+                let x = Length::new::<micrometer>(t);
+                let id = db
+                    .measurements
+                    .push(Kind::Sample, x, x, integration, &[])
+                    .unwrap();
+                let spectrum = vec![1.0 - t, t, 1.0]; // (1 − t)·e1 + t·e2
+                db.intensities.push(id, &wavelengths, spectrum);
+                id
+            })
+            .collect();
+        db.measurements.commit().unwrap();
+        db.intensities.commit().unwrap();
+
+        let pca = db.pca(Source::Intensities, &ids, &wavelengths, 1).unwrap();
+        assert_eq!(pca.count, 4);
+        let half = 0.5f64.sqrt();
+        assert!((pca.loadings[0][0] - half).abs() < 1E-9); // Loading along e1 − e2
+        assert!((pca.loadings[0][1] + half).abs() < 1E-9);
+        assert!(pca.loadings[0][2].abs() < 1E-9);
+        db.pca_scores("pca", Source::Intensities, &ids, &pca)
+            .unwrap();
+        let scores = db.read_scalars("pca").unwrap();
+        assert_eq!(scores.measurements, ids);
+        let spread = scores.values["pc1"][0] - scores.values["pc1"][3];
+        assert!((spread - 2.0 * half).abs() < 1E-9);
+
+        let endmembers = [("e1", &e1), ("e2", &e2)];
+        db.unmix("abundance", Source::Intensities, &ids, &endmembers)
+            .unwrap();
+        let abundances = db.read_scalars("abundance").unwrap();
+        assert!((abundances.values["e1"][1] - 0.75).abs() < 1E-9);
+        assert!((abundances.values["e2"][1] - 0.25).abs() < 1E-9);
+        assert!(abundances.values["residual"].iter().all(|r| *r < 1E-9));
+        assert!((abundances.values["x"][2] - 0.5).abs() < 1E-9);
+        remove_dir_all(PATH).unwrap();
+    }
+
+    #[test]
+    fn spectra_committed_in_parts() {
+        const PATH: &str = "test-analysis-parts";
+        let mut db = Database::new(PATH).unwrap();
+        let wavelengths = db.wavelengths.push(vec![400.0, 500.0, 600.0]).unwrap();
+        db.wavelengths.commit().unwrap();
+        let integration = Time::new::<millisecond>(10.0);
+        let ids: Vec<u32> = [0.0, 0.25, 0.5, 1.0]
+            .into_iter()
+            .map(|t| {
+                let x = Length::new::<micrometer>(t);
+                db.measurements
+                    .push(Kind::Sample, x, x, integration, &[])
+                    .unwrap()
+            })
+            .collect();
+        db.measurements.commit().unwrap();
+        for (id, t) in ids.iter().zip([0.0, 0.25, 0.5, 1.0]) {
+            db.intensities.push(*id, &wavelengths[..1], vec![1.0 - t]);
+        }
+        db.intensities.commit().unwrap();
+        for (id, t) in ids.iter().zip([0.0, 0.25, 0.5, 1.0]) {
+            db.intensities.push(*id, &wavelengths[1..], vec![t, 1.0]);
+        }
+        db.intensities.commit().unwrap();
+
+        let pca = db.pca(Source::Intensities, &ids, &wavelengths, 1).unwrap();
+        assert_eq!(pca.count, 4);
+        let half = 0.5f64.sqrt();
+        assert!((pca.loadings[0][0] - half).abs() < 1E-9); // Same as when committed whole
+        assert!((pca.loadings[0][1] + half).abs() < 1E-9);
+        db.pca_scores("pca", Source::Intensities, &ids, &pca)
+            .unwrap();
+        assert_eq!(db.read_scalars("pca").unwrap().measurements, ids); // Once each
+        remove_dir_all(PATH).unwrap();
+    }
+}
@@ -0,0 +1,260 @@
+/*
+Project: Optic
+GitHub: https://github.com/MillieFD/optic
+
+BSD 3-Clause License, Copyright (c) 2026, Amelia Fraser-Dale
+
+Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the conditions of the LICENSE are met.
+*/
+
+/* ----------------------------------------------------------------------------- Private Imports */
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock};
+
+use arrow::array::{ArrayRef, AsArray, RecordBatch, StringBuilder, UInt32Builder};
+use arrow::datatypes::DataType::{UInt32, Utf8};
+use arrow::datatypes::{Field, Schema, UInt32Type};
+use arrow::ipc::writer::StreamWriter;
+
+use crate::{Database, Error, Measurement, Reader, Writer};
+
+/* ------------------------------------------------------------------------------ Public Exports */
+
+/// Arbitrary key/value attributes of samples or sessions, such as a sample name, operator or
+/// experiment, stored in the `samples` and `sessions` tables.
+pub struct Attributes {
+    stream: StreamWriter<File>,
+    id: UInt32Builder,
+    key: StringBuilder,
+    value: StringBuilder,
+    next: u32,
+    pub path: PathBuf,
+}
+
+/// Condition on the attributes of the sample or session that a measurement belongs to.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Filter {
+    /// The measurement's sample has attribute `key` equal to `value`.
+    Sample { key: String, value: String },
+    /// The measurement's session has attribute `key` equal to `value`.
+    Session { key: String, value: String },
+    /// The measurement was acquired with this instrument configuration.
+    Instrument(u32),
+}
+
+impl Attributes {
+    pub(super) fn new<P>(path: P, name: &str) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref().join(name).with_extension("arrow");
+        let stream = Self::open_stream_writer(&path)?;
+        let mut attributes = Self {
+            stream,
+            id: UInt32Builder::new(),
+            key: StringBuilder::new(),
+            value: StringBuilder::new(),
+            next: 0,
+            path,
+        };
+        attributes.next = attributes
+            .read()?
+            .keys()
+            .map(|id| id + 1)
+            .max()
+            .unwrap_or(0);
+        Ok(attributes)
+    }
+
+    /// Allocate a new ID with `attributes` and return it.
+    pub fn create(&mut self, attributes: &[(&str, &str)]) -> u32 {
+        let id = self.next;
+        self.annotate(id, attributes);
+        id
+    }
+
+    /// Add `attributes` to `id`. Later values replace earlier ones with the same key.
+    pub fn annotate(&mut self, id: u32, attributes: &[(&str, &str)]) {
+        self.reserve(id);
+        for (key, value) in attributes {
+            self.id.append_value(id);
+            self.key.append_value(key);
+            self.value.append_value(value);
+        }
+    }
+
+    /// Read the committed attributes of every ID.
+    pub fn read(&self) -> Result<BTreeMap<u32, BTreeMap<String, String>>, Error> {
+        let mut attributes: BTreeMap<u32, BTreeMap<String, String>> = BTreeMap::new();
+        for batch in self.batches()? {
+            let ids = batch.column(0).as_primitive::<UInt32Type>().values();
+            let keys = batch.column(1).as_string::<i32>();
+            let values = batch.column(2).as_string::<i32>();
+            for ((id, key), value) in ids.iter().zip(keys).zip(values) {
+                let (key, value) = (key.unwrap_or_default(), value.unwrap_or_default());
+                attributes
+                    .entry(*id)
+                    .or_default()
+                    .insert(key.to_string(), value.to_string());
+            }
+        }
+        Ok(attributes)
+    }
+
+    pub fn commit(&mut self) -> Result<(), Error> {
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(self.id.finish()),
+            Arc::new(self.key.finish()),
+            Arc::new(self.value.finish()),
+        ];
+        let batch = RecordBatch::try_new(Self::schema(), columns)?;
+        self.write(&batch)
+    }
+
+    /// Never allocate `id` or any lower ID.
+    pub(crate) fn reserve(&mut self, id: u32) {
+        self.next = self.next.max(id + 1);
+    }
+
+    /// IDs whose committed attribute `key` equals `value`.
+    fn matching(&self, key: &str, value: &str) -> Result<Vec<u32>, Error> {
+        Ok(self
+            .read()?
+            .into_iter()
+            .filter(|(_, attributes)| attributes.get(key).is_some_and(|v| v == value))
+            .map(|(id, _)| id)
+            .collect())
+    }
+}
+
+impl Database {
+    /// Read every committed measurement that satisfies all `filters`.
+    pub fn select(&self, filters: &[Filter]) -> Result<Vec<Measurement>, Error> {
+        let mut records = self.measurements.read()?;
+        for filter in filters {
+            match filter {
+                Filter::Sample { key, value } => {
+                    let samples = self.samples.matching(key, value)?;
+                    records.retain(|r| r.sample.is_some_and(|s| samples.contains(&s)));
+                }
+                Filter::Session { key, value } => {
+                    let sessions = self.sessions.matching(key, value)?;
+                    records.retain(|r| sessions.contains(&r.session));
+                }
+                Filter::Instrument(instrument) => {
+                    records.retain(|r| r.instrument == Some(*instrument));
+                }
+            }
+        }
+        Ok(records)
+    }
+}
+
+/* ----------------------------------------------------------------------- Trait Implementations */
+
+impl Writer for Attributes {
+    const SCHEMA: LazyLock<Arc<Schema>> = LazyLock::new(|| {
+        let fields = [
+            Field::new("id", UInt32, false).into(),
+            Field::new("key", Utf8, false).into(),
+            Field::new("value", Utf8, false).into(),
+        ];
+        Schema::new(fields).into()
+    });
+
+    fn stream(&mut self) -> &mut StreamWriter<File> {
+        &mut self.stream
+    }
+
+    fn write(&mut self, batch: &RecordBatch) -> Result<(), Error> {
+        let ids = batch.column(0).as_primitive::<UInt32Type>();
+        ids.values().iter().for_each(|id| self.reserve(*id)); // Never reuse imported IDs
+        self.stream.write(batch).map_err(Error::from)
+    }
+}
+
+impl Reader for Attributes {
+    fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+/* ---------------------------------------------------------------------------------- Unit Tests */
+
+#[cfg(all(
+    test,
+    feature = "x",
+    feature = "y",
+    not(feature = "z"),
+    not(feature = "a")
+))]
+mod tests {
+    use std::fs::remove_dir_all;
+
+    use uom::si::f64::{Length, Time};
+    use uom::si::length::micrometer;
+    use uom::si::time::millisecond;
+
+    use super::*;
+    use crate::Kind;
+
+    #[test]
+    fn samples_and_sessions() {
+        const PATH: &str = "test-attributes";
+        let zero = Length::new::<micrometer>(0.0);
+        let integration = Time::new::<millisecond>(10.0);
+        let mut db = Database::new(PATH).unwrap();
+        let first = db.measurements.session();
+        db.sessions.annotate(first, &[("operator", "alice")]);
+        let oak = db.samples.create(&[("species", "oak")]);
+        let ash = db.samples.create(&[("species", "ash"), ("site", "north")]);
+        db.samples.commit().unwrap();
+        db.sessions.commit().unwrap();
+        db.measurements.set_sample(Some(oak));
+        let a = db
+            .measurements
+            .push(Kind::Sample, zero, zero, integration, &[])
+            .unwrap();
+        db.measurements.set_sample(None);
+        let dark = db
+            .measurements
+            .push(Kind::Dark, zero, zero, integration, &[])
+            .unwrap();
+        db.measurements.commit().unwrap();
+        drop(db);
+
+        let mut db = Database::new(PATH).unwrap();
+        let second = db.measurements.session();
+        assert!(second > first);
+        db.sessions.annotate(second, &[("operator", "bob")]);
+        db.sessions.commit().unwrap();
+        db.measurements.set_sample(Some(ash));
+        let b = db
+            .measurements
+            .push(Kind::Sample, zero, zero, integration, &[])
+            .unwrap();
+        db.measurements.commit().unwrap();
+        assert_eq!(db.samples.create(&[]), ash + 1);
+
+        let samples = db.samples.read().unwrap();
+        assert_eq!(samples[&ash]["site"], "north");
+        let ids = |filters: &[Filter]| -> Vec<u32> {
+            db.select(filters).unwrap().iter().map(|r| r.id).collect()
+        };
+        let filter = |key: &str, value: &str| (key.to_string(), value.to_string());
+        let (key, value) = filter("species", "oak");
+        assert_eq!(ids(&[Filter::Sample { key, value }]), vec![a]);
+        let (key, value) = filter("operator", "alice");
+        assert_eq!(ids(&[Filter::Session { key, value }]), vec![a, dark]);
+        let (key, value) = filter("operator", "bob");
+        let session = Filter::Session { key, value };
+        assert_eq!(ids(std::slice::from_ref(&session)), vec![b]);
+        let (key, value) = filter("species", "oak");
+        assert!(ids(&[session, Filter::Sample { key, value }]).is_empty());
+        remove_dir_all(PATH).unwrap();
+    }
+}
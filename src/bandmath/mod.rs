@@ -0,0 +1,287 @@
+/*
+Project: Optic
+GitHub: https://github.com/MillieFD/optic
+
+BSD 3-Clause License, Copyright (c) 2026, Amelia Fraser-Dale
+
+Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the conditions of the LICENSE are met.
+*/
+
+/* ----------------------------------------------------------------------------- Private Modules */
+
+mod parser;
+
+/* ----------------------------------------------------------------------------- Private Imports */
+
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use uom::si::f64::Length;
+use uom::si::length::nanometer;
+
+use self::parser::{Node, Operator};
+use crate::resample::curve;
+use crate::{Database, Error, Provenance, Source};
+
+/* ------------------------------------------------------------------------------ Public Exports */
+
+/// A band-math expression such as `(R[800nm] - R[670nm]) / (R[800nm] + R[670nm])`.
+///
+/// Supports numbers, `+ - * /`, unary minus and parentheses. `R[λ]` refers to the value at a
+/// single wavelength in `nm` (the default) or `um`, resolved by a [`Resolution`]. `R[λ₁:λ₂]`
+/// always refers to the mean value over the inclusive range `λ₁..=λ₂`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Expression {
+    text: String,
+    root: Node,
+}
+
+/// How single-wavelength references `R[λ]` are matched to the measured wavelengths.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Resolution {
+    /// Use the sample with the nearest wavelength.
+    Nearest,
+    /// Use the mean value over a band of the given total width centred on `λ`.
+    Integrated(Length),
+}
+
+impl Database {
+    /// Evaluate `expression` for the spectra of `measurements` from `source`. Nothing is written
+    /// to disk. Measurements where a band falls outside the measured range evaluate to `NaN`.
+    pub fn band_math(
+        &self,
+        source: Source,
+        measurements: &[u32],
+        expression: &Expression,
+        resolution: Resolution,
+    ) -> Result<HashMap<u32, f64>, Error> {
+        let lookup = self.wavelengths.lookup()?;
+        self.spectra(source, measurements)?
+            .iter()
+            .map(|(id, spectrum)| {
+                let curve = curve(spectrum, &lookup)?;
+                Ok((*id, evaluate(&expression.root, &curve, resolution)))
+            })
+            .collect()
+    }
+
+    /// Evaluate `expression` as [`Database::band_math`] and write it to the scalar table called
+    /// `name` in a single `column`. Returns the IDs of the evaluated measurements.
+    pub fn band_index(
+        &self,
+        name: &str,
+        column: &str,
+        source: Source,
+        measurements: &[u32],
+        expression: &Expression,
+        resolution: Resolution,
+    ) -> Result<Vec<u32>, Error> {
+        let mut values: Vec<_> = self
+            .band_math(source, measurements, expression, resolution)?
+            .into_iter()
+            .collect();
+        values.sort_unstable_by_key(|(id, _)| *id);
+        let provenance = Provenance::new("band_math", &[source.name(), "wavelengths"])
+            .parameter("expression", expression);
+        let provenance = match resolution {
+            Resolution::Nearest => provenance.parameter("resolution", "nearest"),
+            Resolution::Integrated(width) => provenance
+                .parameter("resolution", "integrated")
+                .parameter("width_nm", width.get::<nanometer>()),
+        };
+        let mut table = self.create_scalars(name, &[column], &provenance)?;
+        values
+            .iter()
+            .try_for_each(|(id, value)| table.push(*id, &[*value]))?;
+        table.commit()?;
+        Ok(values.into_iter().map(|(id, _)| id).collect())
+    }
+}
+
+/* ----------------------------------------------------------------------- Trait Implementations */
+
+impl FromStr for Expression {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Ok(Self {
+            text: text.trim().to_string(),
+            root: parser::parse(text)?,
+        })
+    }
+}
+
+impl Display for Expression {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+/* ----------------------------------------------------------------------------- Private Helpers */
+
+fn evaluate(node: &Node, curve: &[(f64, f64)], resolution: Resolution) -> f64 {
+    match node {
+        Node::Number(value) => *value,
+        Node::Band(wl) => {
+            let nm = wl.get::<nanometer>();
+            match resolution {
+                Resolution::Nearest => nearest(curve, nm),
+                Resolution::Integrated(width) => {
+                    let half = width.get::<nanometer>() / 2.0;
+                    mean(curve, nm - half, nm + half)
+                }
+            }
+        }
+        Node::Range(lower, upper) => {
+            mean(curve, lower.get::<nanometer>(), upper.get::<nanometer>())
+        }
+        Node::Negate(node) => -evaluate(node, curve, resolution),
+        Node::Binary(left, operator, right) => {
+            let (a, b) = (
+                evaluate(left, curve, resolution),
+                evaluate(right, curve, resolution),
+            );
+            match operator {
+                Operator::Add => a + b,
+                Operator::Subtract => a - b,
+                Operator::Multiply => a * b,
+                Operator::Divide => a / b,
+            }
+        }
+    }
+}
+
+/// Value of the sample nearest to `nm`, or `NaN` outside the measured range.
+pub(crate) fn nearest(curve: &[(f64, f64)], nm: f64) -> f64 {
+    match (curve.first(), curve.last()) {
+        (Some(first), Some(last)) if (first.0..=last.0).contains(&nm) => curve
+            .iter()
+            .min_by(|a, b| (a.0 - nm).abs().total_cmp(&(b.0 - nm).abs()))
+            .map_or(f64::NAN, |(_, value)| *value),
+        _ => f64::NAN,
+    }
+}
+
+/// Mean of the linearly interpolated spectrum over `lower..=upper` by the trapezoidal rule, or
+/// `NaN` if the band is not fully inside the measured range.
+fn mean(curve: &[(f64, f64)], lower: f64, upper: f64) -> f64 {
+    let at = |x: f64| {
+        let index = curve
+            .partition_point(|(xi, _)| *xi < x)
+            .clamp(1, curve.len() - 1);
+        let ((x0, y0), (x1, y1)) = (curve[index - 1], curve[index]);
+        y0 + (y1 - y0) * (x - x0) / (x1 - x0)
+    };
+    match (curve.first(), curve.last()) {
+        (Some(first), Some(last)) if curve.len() > 1 && first.0 <= lower && upper <= last.0 => {
+            if upper == lower {
+                return at(lower);
+            }
+            let inside = curve
+                .iter()
+                .filter(|(x, _)| lower < *x && *x < upper)
+                .copied();
+            let points: Vec<(f64, f64)> = [(lower, at(lower))]
+                .into_iter()
+                .chain(inside)
+                .chain([(upper, at(upper))])
+                .collect();
+            let area: f64 = points
+                .windows(2)
+                .map(|w| (w[1].0 - w[0].0) * (w[0].1 + w[1].1) / 2.0)
+                .sum();
+            area / (upper - lower)
+        }
+        _ => f64::NAN,
+    }
+}
+
+/* ---------------------------------------------------------------------------------- Unit Tests */
+
+#[cfg(all(
+    test,
+    feature = "x",
+    feature = "y",
+    not(feature = "z"),
+    not(feature = "a")
+))]
+mod tests {
+    use std::fs::remove_dir_all;
+
+    use uom::si::f64::Time;
+    use uom::si::length::micrometer;
+    use uom::si::time::millisecond;
+
+    use super::*;
+    use crate::Kind;
+
+    #[test]
+    fn parse_expressions() {
+        let ndvi: Expression = "(R[800nm]-R[670nm])/(R[0.8um]+R[670])".parse().unwrap();
+        assert_eq!(ndvi.to_string(), "(R[800nm]-R[670nm])/(R[0.8um]+R[670])");
+        let curve = [(670.0, 0.1), (800.0, 0.5)];
+        let value = evaluate(&ndvi.root, &curve, Resolution::Nearest);
+        assert!((value - 0.4 / 0.6).abs() < 1E-12);
+        let negative: Expression = "-2 * R[700nm:800nm] + 1".parse().unwrap();
+        let value = evaluate(
+            &negative.root,
+            &[(600.0, 0.0), (800.0, 1.0)],
+            Resolution::Nearest,
+        );
+        assert!((value + 0.5).abs() < 1E-12); // Mean of 0.5..=1.0 is 0.75
+        for invalid in ["R[800nm", "R[800pm]", "1 +", "(1", "1 2", "X"] {
+            assert!(invalid.parse::<Expression>().is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn band_index_table() {
+        const PATH: &str = "test-bandmath";
+        let mut db = Database::new(PATH).unwrap();
+        let wavelengths = db
+            .wavelengths
+            .push(vec![660.0, 670.0, 680.0, 800.0])
+            .unwrap();
+        db.wavelengths.commit().unwrap();
+        let x = Length::new::<micrometer>(1.0);
+        let integration = Time::new::<millisecond>(10.0);
+        let id = db
+            .measurements
+            .push(Kind::Sample, x, x, integration, &[])
+            .unwrap();
+        db.intensities
+            .push(id, &wavelengths, vec![0.0, 0.1, 0.2, 0.5]);
+        db.measurements.commit().unwrap();
+        db.intensities.commit().unwrap();
+
+        let ndvi: Expression = "(R[800nm]-R[670nm])/(R[800nm]+R[670nm])".parse().unwrap();
+        let width = Length::new::<nanometer>(20.0);
+        let integrated = db
+            .band_math(
+                Source::Intensities,
+                &[id],
+                &ndvi,
+                Resolution::Integrated(width),
+            )
+            .unwrap();
+        assert!(integrated[&id].is_nan()); // 800 ± 10 nm exceeds the measured range
+        db.band_index(
+            "ndvi",
+            "ndvi",
+            Source::Intensities,
+            &[id],
+            &ndvi,
+            Resolution::Nearest,
+        )
+        .unwrap();
+        let columns = db.read_scalars("ndvi").unwrap();
+        assert!((columns.values["ndvi"][0] - 0.4 / 0.6).abs() < 1E-12);
+        assert_eq!(
+            columns.provenance.parameters["expression"],
+            ndvi.to_string()
+        );
+        remove_dir_all(PATH).unwrap();
+    }
+}
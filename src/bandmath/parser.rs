@@ -0,0 +1,175 @@
+/*
+Project: Optic
+GitHub: https://github.com/MillieFD/optic
+
+BSD 3-Clause License, Copyright (c) 2026, Amelia Fraser-Dale
+
+Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the conditions of the LICENSE are met.
+*/
+
+/* ----------------------------------------------------------------------------- Private Imports */
+
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+use uom::si::f64::Length;
+use uom::si::length::{micrometer, nanometer};
+
+use crate::Error;
+
+/* ------------------------------------------------------------------------------ Public Exports */
+
+/// Parsed band-math expression tree.
+#[derive(Clone, Debug, PartialEq)]
+pub(super) enum Node {
+    Number(f64),
+    /// Reflectance at a single wavelength, resolved by the caller's [`super::Resolution`].
+    Band(Length),
+    /// Mean reflectance over an inclusive wavelength range.
+    Range(Length, Length),
+    Negate(Box<Node>),
+    Binary(Box<Node>, Operator, Box<Node>),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(super) enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+/// Parse a complete expression, rejecting trailing input.
+pub(super) fn parse(text: &str) -> Result<Node, Error> {
+    let mut parser = Parser {
+        text,
+        chars: text.char_indices().peekable(),
+    };
+    let node = parser.expression()?;
+    match parser.peek() {
+        None => Ok(node),
+        Some((index, c)) => Err(parser.error(index, &format!("unexpected '{c}'"))),
+    }
+}
+
+/* ----------------------------------------------------------------------------- Private Helpers */
+
+struct Parser<'a> {
+    text: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+}
+
+impl Parser<'_> {
+    /// Next non-whitespace character without consuming it.
+    fn peek(&mut self) -> Option<(usize, char)> {
+        while self.chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+        self.chars.peek().copied()
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), Error> {
+        match self.peek() {
+            Some((_, c)) if c == expected => {
+                self.chars.next();
+                Ok(())
+            }
+            Some((index, c)) => {
+                Err(self.error(index, &format!("expected '{expected}', found '{c}'")))
+            }
+            None => Err(self.error(self.text.len(), &format!("expected '{expected}'"))),
+        }
+    }
+
+    fn error(&self, index: usize, message: &str) -> Error {
+        Error::ParseError(format!(
+            "{message} at column {} of '{}'",
+            index + 1,
+            self.text
+        ))
+    }
+
+    fn expression(&mut self) -> Result<Node, Error> {
+        let mut node = self.term()?;
+        while let Some((_, c @ ('+' | '-'))) = self.peek() {
+            self.chars.next();
+            let operator = match c {
+                '+' => Operator::Add,
+                _ => Operator::Subtract,
+            };
+            node = Node::Binary(Box::new(node), operator, Box::new(self.term()?));
+        }
+        Ok(node)
+    }
+
+    fn term(&mut self) -> Result<Node, Error> {
+        let mut node = self.factor()?;
+        while let Some((_, c @ ('*' | '/'))) = self.peek() {
+            self.chars.next();
+            let operator = match c {
+                '*' => Operator::Multiply,
+                _ => Operator::Divide,
+            };
+            node = Node::Binary(Box::new(node), operator, Box::new(self.factor()?));
+        }
+        Ok(node)
+    }
+
+    fn factor(&mut self) -> Result<Node, Error> {
+        match self.peek() {
+            Some((_, '-')) => {
+                self.chars.next();
+                Ok(Node::Negate(Box::new(self.factor()?)))
+            }
+            Some((_, '(')) => {
+                self.chars.next();
+                let node = self.expression()?;
+                self.expect(')')?;
+                Ok(node)
+            }
+            Some((_, 'R')) => {
+                self.chars.next();
+                self.expect('[')?;
+                let lower = self.wavelength()?;
+                let node = match self.peek() {
+                    Some((_, ':')) => {
+                        self.chars.next();
+                        let upper = self.wavelength()?;
+                        Node::Range(lower.min(upper), lower.max(upper))
+                    }
+                    _ => Node::Band(lower),
+                };
+                self.expect(']')?;
+                Ok(node)
+            }
+            Some((_, c)) if c.is_ascii_digit() || c == '.' => Ok(Node::Number(self.number()?)),
+            Some((index, c)) => Err(self.error(index, &format!("unexpected '{c}'"))),
+            None => Err(self.error(self.text.len(), "unexpected end of expression")),
+        }
+    }
+
+    fn number(&mut self) -> Result<f64, Error> {
+        let start = self.peek().map_or(self.text.len(), |(index, _)| index);
+        let mut end = start;
+        while let Some((index, c)) = self.chars.next_if(|(_, c)| c.is_ascii_digit() || *c == '.') {
+            end = index + c.len_utf8();
+        }
+        self.text[start..end]
+            .parse()
+            .map_err(|_| self.error(start, "invalid number"))
+    }
+
+    /// A number followed by an optional `nm`, `um` or `µm` unit. Nanometres are assumed.
+    fn wavelength(&mut self) -> Result<Length, Error> {
+        let value = self.number()?;
+        let start = self.peek().map_or(self.text.len(), |(index, _)| index);
+        let mut end = start;
+        while let Some((index, c)) = self.chars.next_if(|(_, c)| c.is_alphabetic()) {
+            end = index + c.len_utf8();
+        }
+        match &self.text[start..end] {
+            "" | "nm" => Ok(Length::new::<nanometer>(value)),
+            "um" | "µm" | "μm" => Ok(Length::new::<micrometer>(value)),
+            unit => Err(self.error(start, &format!("unknown unit '{unit}'"))),
+        }
+    }
+}
@@ -0,0 +1,224 @@
+/*
+Project: Optic
+GitHub: https://github.com/MillieFD/optic
+
+BSD 3-Clause License, Copyright (c) 2026, Amelia Fraser-Dale
+
+Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the conditions of the LICENSE are met.
+*/
+
+/* ----------------------------------------------------------------------------- Private Imports */
+
+use std::collections::{BTreeSet, HashMap};
+
+use uom::si::f64::Length;
+use uom::si::length::nanometer;
+
+use crate::{Database, Error, Provenance, Source};
+
+/* ------------------------------------------------------------------------------ Public Exports */
+
+/// Correction from recorded to true wavelengths, for example after recalibrating a spectrometer.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Calibration {
+    /// Corrected `nm = c₀ + c₁λ + c₂λ² + …` for recorded `λ` in nanometres, with coefficients in
+    /// ascending order of power.
+    Polynomial(Vec<f64>),
+    /// `(recorded, corrected)` pairs sorted by recorded wavelength. Linearly interpolated between
+    /// pairs and extrapolated from the first and last segments.
+    Table(Vec<(Length, Length)>),
+}
+
+impl Calibration {
+    /// Corrected value of a `recorded` wavelength.
+    pub fn correct(&self, recorded: Length) -> Length {
+        let nm = recorded.get::<nanometer>();
+        let corrected = match self {
+            Calibration::Polynomial(coefficients) => coefficients
+                .iter()
+                .rev()
+                .fold(0.0, |total, c| total * nm + c),
+            Calibration::Table(pairs) => {
+                let index = pairs
+                    .partition_point(|(x, _)| x.get::<nanometer>() < nm)
+                    .clamp(1, pairs.len() - 1);
+                let [(x0, y0), (x1, y1)] = [pairs[index - 1], pairs[index]]
+                    .map(|(x, y)| (x.get::<nanometer>(), y.get::<nanometer>()));
+                y0 + (y1 - y0) * (nm - x0) / (x1 - x0)
+            }
+        };
+        Length::new::<nanometer>(corrected)
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        match self {
+            Calibration::Polynomial(coefficients) if coefficients.is_empty() => Err(
+                Error::InvalidParameter("Polynomial calibration needs a coefficient".into()),
+            ),
+            Calibration::Table(pairs) if pairs.len() < 2 => Err(Error::InvalidParameter(
+                "Calibration table needs at least two pairs".into(),
+            )),
+            Calibration::Table(pairs) if pairs.windows(2).any(|w| w[1].0 <= w[0].0) => Err(
+                Error::InvalidParameter("Calibration table must be strictly increasing".into()),
+            ),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Database {
+    /// Apply `calibration` to the wavelengths of `measurements` from `source` and write the
+    /// remapped spectra to the derived table called `name`. Corrected wavelengths are registered
+    /// in the `wavelengths` table and values are carried over unchanged, so `source` is left
+    /// intact. Fails if the correction would merge or reorder the recorded wavelengths. Returns
+    /// the IDs of the processed measurements.
+    pub fn recalibrate(
+        &mut self,
+        name: &str,
+        source: Source,
+        measurements: &[u32],
+        calibration: &Calibration,
+    ) -> Result<Vec<u32>, Error> {
+        calibration.validate()?;
+        let lookup = self.wavelengths.lookup()?;
+        let spectra = self.spectra(source, measurements)?;
+        let recorded: BTreeSet<u32> = spectra.values().flat_map(|s| s.keys().copied()).collect();
+        let mut pairs = recorded
+            .into_iter()
+            .map(|id| match lookup.get(&id) {
+                Some(wl) => Ok((id, *wl, calibration.correct(*wl))),
+                None => Err(Error::MissingWavelength(id)),
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        pairs.sort_unstable_by(|a, b| a.1.get::<nanometer>().total_cmp(&b.1.get::<nanometer>()));
+        if pairs.windows(2).any(|w| w[1].2 <= w[0].2) {
+            return Err(Error::InvalidParameter(
+                "Calibration must be strictly increasing over the recorded wavelengths".into(),
+            ));
+        }
+        let provenance = Provenance::new("recalibrate", &[source.name(), "wavelengths"]);
+        let provenance = match calibration {
+            Calibration::Polynomial(coefficients) => {
+                provenance.parameter("calibration", "polynomial").parameter(
+                    "coefficients",
+                    join(coefficients.iter().map(f64::to_string)),
+                )
+            }
+            Calibration::Table(pairs) => provenance.parameter("calibration", "table").parameter(
+                "table_nm",
+                join(pairs.iter().map(|(recorded, corrected)| {
+                    let [r, c] = [recorded, corrected].map(|wl| wl.get::<nanometer>());
+                    format!("{r}={c}")
+                })),
+            ),
+        };
+        let mut ids: Vec<u32> = spectra.keys().copied().collect();
+        ids.sort_unstable();
+        let mut table = self.create_derived(name, &provenance)?; // Before registering wavelengths
+        let nms = pairs
+            .iter()
+            .map(|(_, _, wl)| wl.get::<nanometer>())
+            .collect();
+        let corrected = self.wavelengths.push(nms)?;
+        self.wavelengths.commit()?;
+        let remap: HashMap<u32, u32> = pairs.iter().map(|(id, ..)| *id).zip(corrected).collect();
+        for id in &ids {
+            let (wavelengths, values): (Vec<u32>, Vec<f64>) = spectra[id]
+                .iter()
+                .map(|(wavelength, value)| (remap[wavelength], *value))
+                .unzip();
+            table.push(*id, &wavelengths, values);
+        }
+        table.commit()?;
+        Ok(ids)
+    }
+}
+
+/* ----------------------------------------------------------------------------- Private Helpers */
+
+fn join(items: impl Iterator<Item = String>) -> String {
+    items.collect::<Vec<String>>().join(",")
+}
+
+/* ---------------------------------------------------------------------------------- Unit Tests */
+
+#[cfg(all(
+    test,
+    feature = "x",
+    feature = "y",
+    not(feature = "z"),
+    not(feature = "a")
+))]
+mod tests {
+    use std::fs::remove_dir_all;
+
+    use uom::si::f64::Time;
+    use uom::si::length::micrometer;
+    use uom::si::time::millisecond;
+
+    use super::*;
+    use crate::Kind;
+
+    #[test]
+    fn correct_wavelengths() {
+        let nm = Length::new::<nanometer>;
+        let shift = Calibration::Polynomial(vec![1.5, 1.0]);
+        assert!((shift.correct(nm(500.0)).get::<nanometer>() - 501.5).abs() < 1E-9);
+        let table = Calibration::Table(vec![(nm(400.0), nm(401.0)), (nm(600.0), nm(599.0))]);
+        assert!((table.correct(nm(500.0)).get::<nanometer>() - 500.0).abs() < 1E-9);
+        assert!((table.correct(nm(700.0)).get::<nanometer>() - 698.0).abs() < 1E-9);
+        assert!(
+            Calibration::Table(vec![(nm(400.0), nm(401.0))])
+                .validate()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn recalibrate_measurements() {
+        const PATH: &str = "test-calibration";
+        let mut db = Database::new(PATH).unwrap();
+        let wavelengths = db.wavelengths.push(vec![500.0, 510.0]).unwrap();
+        db.wavelengths.commit().unwrap();
+        let zero = Length::new::<micrometer>(0.0);
+        let integration = Time::new::<millisecond>(10.0);
+        let id = db
+            .measurements
+            .push(Kind::Sample, zero, zero, integration, &[])
+            .unwrap();
+        db.intensities.push(id, &wavelengths, vec![1.0, 2.0]);
+        db.measurements.commit().unwrap();
+        db.intensities.commit().unwrap();
+
+        let reversed = Calibration::Polynomial(vec![1000.0, -1.0]);
+        let result = db.recalibrate("bad", Source::Intensities, &[id], &reversed);
+        assert!(matches!(result, Err(Error::InvalidParameter(_))));
+
+        let shift = Calibration::Polynomial(vec![0.5, 1.0]);
+        let ids = db
+            .recalibrate("fixed", Source::Intensities, &[id], &shift)
+            .unwrap();
+        assert_eq!(ids, vec![id]);
+        let lookup = db.wavelengths.lookup().unwrap();
+        let spectrum = &db.spectra(Source::Derived("fixed"), &[id]).unwrap()[&id];
+        let expected = [(500.5, 1.0), (510.5, 2.0)];
+        assert_eq!(spectrum.len(), expected.len());
+        for ((wl, value), (nm, expected)) in spectrum.iter().zip(expected) {
+            assert!((lookup[wl].get::<nanometer>() - nm).abs() < 1E-9);
+            assert_eq!(*value, expected);
+        }
+        let original = &db.spectra(Source::Intensities, &[id]).unwrap()[&id];
+        assert_eq!(original.keys().copied().collect::<Vec<u32>>(), wavelengths);
+        let provenance = db.provenance("fixed").unwrap();
+        assert_eq!(provenance.parameters["coefficients"], "0.5,1");
+        let count = db.wavelengths.lookup().unwrap().len();
+        let other = Calibration::Polynomial(vec![1.0, 1.0]);
+        assert!(
+            db.recalibrate("fixed", Source::Intensities, &[id], &other)
+                .is_err()
+        );
+        assert_eq!(db.wavelengths.lookup().unwrap().len(), count); // Exists already
+        remove_dir_all(PATH).unwrap();
+    }
+}
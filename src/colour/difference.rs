@@ -0,0 +1,95 @@
+/*
+Project: Optic
+GitHub: https://github.com/MillieFD/optic
+
+BSD 3-Clause License, Copyright (c) 2026, Amelia Fraser-Dale
+
+Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the conditions of the LICENSE are met.
+*/
+
+/* ------------------------------------------------------------------------------ Public Exports */
+
+/// CIE 1976 ΔE*ab.
+pub(super) fn cie76(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| (a - b).powi(2))
+        .sum::<f64>()
+        .sqrt()
+}
+
+/// CIEDE2000 ΔE₀₀ following Sharma, Wu and Dalal (2005) with `kL = kC = kH = 1`.
+pub(super) fn ciede2000([l1, a1, b1]: [f64; 3], [l2, a2, b2]: [f64; 3]) -> f64 {
+    let pow7 = |v: f64| v.powi(7);
+    let chroma = (a1.hypot(b1) + a2.hypot(b2)) / 2.0;
+    let g = 0.5 * (1.0 - (pow7(chroma) / (pow7(chroma) + pow7(25.0))).sqrt());
+    let (a1, a2) = (a1 * (1.0 + g), a2 * (1.0 + g));
+    let (c1, c2) = (a1.hypot(b1), a2.hypot(b2));
+    let hue = |a: f64, b: f64| match a == 0.0 && b == 0.0 {
+        true => 0.0,
+        false => b.atan2(a).to_degrees().rem_euclid(360.0),
+    };
+    let (h1, h2) = (hue(a1, b1), hue(a2, b2));
+    let dl = l2 - l1;
+    let dc = c2 - c1;
+    let dh = match c1 * c2 == 0.0 {
+        true => 0.0,
+        false => match h2 - h1 {
+            d if d > 180.0 => d - 360.0,
+            d if d < -180.0 => d + 360.0,
+            d => d,
+        },
+    };
+    let dh = 2.0 * (c1 * c2).sqrt() * (dh / 2.0).to_radians().sin();
+    let l = (l1 + l2) / 2.0;
+    let c = (c1 + c2) / 2.0;
+    let h = match c1 * c2 == 0.0 {
+        true => h1 + h2,
+        false if (h1 - h2).abs() <= 180.0 => (h1 + h2) / 2.0,
+        false if h1 + h2 < 360.0 => (h1 + h2 + 360.0) / 2.0,
+        false => (h1 + h2 - 360.0) / 2.0,
+    };
+    let t = 1.0 - 0.17 * (h - 30.0).to_radians().cos()
+        + 0.24 * (2.0 * h).to_radians().cos()
+        + 0.32 * (3.0 * h + 6.0).to_radians().cos()
+        - 0.20 * (4.0 * h - 63.0).to_radians().cos();
+    let theta = 30.0 * (-((h - 275.0) / 25.0).powi(2)).exp();
+    let rc = 2.0 * (pow7(c) / (pow7(c) + pow7(25.0))).sqrt();
+    let sl = 1.0 + 0.015 * (l - 50.0).powi(2) / (20.0 + (l - 50.0).powi(2)).sqrt();
+    let sc = 1.0 + 0.045 * c;
+    let sh = 1.0 + 0.015 * c * t;
+    let rt = -(2.0 * theta).to_radians().sin() * rc;
+    ((dl / sl).powi(2) + (dc / sc).powi(2) + (dh / sh).powi(2) + rt * (dc / sc) * (dh / sh)).sqrt()
+}
+
+/* ---------------------------------------------------------------------------------- Unit Tests */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sharma_pairs() {
+        let pairs = [
+            ([50.0, 2.6772, -79.7751], [50.0, 0.0, -82.7485], 2.0425),
+            ([50.0, 3.1571, -77.2803], [50.0, 0.0, -82.7485], 2.8615),
+            ([50.0, 2.5, 0.0], [50.0, 0.0, -2.5], 4.3065),
+            (
+                [60.2574, -34.0099, 36.2677],
+                [60.4626, -34.1751, 39.4387],
+                1.2644,
+            ),
+            (
+                [2.0776, 0.0795, -1.1350],
+                [0.9033, -0.0636, -0.5514],
+                0.9082,
+            ),
+        ];
+        for (a, b, expected) in pairs {
+            assert!((ciede2000(a, b) - expected).abs() < 1E-4);
+            assert!((ciede2000(b, a) - expected).abs() < 1E-4);
+        }
+        assert!((cie76([50.0, 3.0, 4.0], [50.0, 0.0, 0.0]) - 5.0).abs() < 1E-12);
+    }
+}
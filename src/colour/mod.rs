@@ -0,0 +1,415 @@
+/*
+Project: Optic
+GitHub: https://github.com/MillieFD/optic
+
+BSD 3-Clause License, Copyright (c) 2026, Amelia Fraser-Dale
+
+Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the conditions of the LICENSE are met.
+*/
+
+/* ----------------------------------------------------------------------------- Private Modules */
+
+mod cie;
+mod difference;
+
+/* ----------------------------------------------------------------------------- Private Imports */
+
+use std::collections::HashMap;
+
+use uom::si::f64::Length;
+use uom::si::length::nanometer;
+
+use crate::resample::curve;
+use crate::{Database, Error, Source};
+
+/* ------------------------------------------------------------------------------ Public Exports */
+
+/// CIE standard colorimetric observer.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum Observer {
+    /// CIE 1931 2° standard observer.
+    #[default]
+    Cie1931,
+    /// CIE 1964 10° supplementary standard observer.
+    Cie1964,
+}
+
+/// Spectral power distribution of the light source.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum Illuminant {
+    /// CIE standard illuminant D65 (average daylight).
+    #[default]
+    D65,
+    /// CIE standard illuminant A (incandescent tungsten, 2856 K).
+    A,
+    /// CIE illuminant F2 (cool white fluorescent).
+    F2,
+    /// CIE illuminant F7 (broadband daylight fluorescent).
+    F7,
+    /// CIE illuminant F11 (narrow tri-band fluorescent).
+    F11,
+    /// Relative spectral power at each wavelength, sorted by wavelength and linearly interpolated
+    /// between samples.
+    Custom(Vec<(Length, f64)>),
+}
+
+/// Observer and illuminant under which a reflectance spectrum is characterised.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Viewing {
+    pub observer: Observer,
+    pub illuminant: Illuminant,
+}
+
+/// Colour of one reflectance spectrum.
+///
+/// Tristimulus values are integrated over the stored wavelengths that fall inside the visible
+/// range `380..=780 nm` and scaled so that a perfect white reflector has `Y = 1` on the same
+/// axis. CIELAB is relative to that white. sRGB is gamma encoded and not clamped, so values
+/// outside `0..=1` are out of gamut; other illuminants are first adapted to D65 with the Bradford
+/// transform.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Colorimetry {
+    /// CIE `X Y Z`.
+    pub xyz: [f64; 3],
+    /// CIE `x y Y`.
+    pub xyy: [f64; 3],
+    /// CIELAB `L* a* b*`.
+    pub lab: [f64; 3],
+    /// sRGB `R G B`.
+    pub srgb: [f64; 3],
+}
+
+/// Colour-difference formula between two CIELAB colours.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Difference {
+    /// CIE 1976 ΔE*ab, the Euclidean distance in CIELAB.
+    Cie76,
+    /// CIEDE2000 ΔE₀₀ with unit parametric factors.
+    Ciede2000,
+}
+
+impl Difference {
+    /// Colour difference between the CIELAB colours `a` and `b`.
+    pub fn between(&self, a: [f64; 3], b: [f64; 3]) -> f64 {
+        match self {
+            Difference::Cie76 => difference::cie76(a, b),
+            Difference::Ciede2000 => difference::ciede2000(a, b),
+        }
+    }
+}
+
+impl Database {
+    /// Characterise the spectra of `measurements` from `source` as reflectance under `viewing`.
+    /// Nothing is written to disk. Spectra without any sample in the visible range give `NaN`.
+    pub fn colorimetry(
+        &self,
+        source: Source,
+        measurements: &[u32],
+        viewing: &Viewing,
+    ) -> Result<HashMap<u32, Colorimetry>, Error> {
+        let lookup = self.wavelengths.lookup()?;
+        self.spectra(source, measurements)?
+            .iter()
+            .map(|(id, spectrum)| Ok((*id, viewing.characterise(&curve(spectrum, &lookup)?))))
+            .collect()
+    }
+
+    /// Colour difference of each of `measurements` from the `reference` measurement under
+    /// `viewing`, using `formula`. Fails with [`Error::MissingReference`] if `source` has no
+    /// spectrum for `reference`.
+    pub fn colour_difference(
+        &self,
+        source: Source,
+        reference: u32,
+        measurements: &[u32],
+        viewing: &Viewing,
+        formula: Difference,
+    ) -> Result<HashMap<u32, f64>, Error> {
+        let ids: Vec<u32> = measurements.iter().copied().chain([reference]).collect();
+        let colours = self.colorimetry(source, &ids, viewing)?;
+        let target = colours
+            .get(&reference)
+            .ok_or(Error::MissingReference(reference))?
+            .lab;
+        Ok(measurements
+            .iter()
+            .filter_map(|id| {
+                colours
+                    .get(id)
+                    .map(|c| (*id, formula.between(c.lab, target)))
+            })
+            .collect())
+    }
+}
+
+impl Viewing {
+    /// Colorimetry of a reflectance curve of `(nm, R)` pairs sorted by wavelength.
+    pub(crate) fn characterise(&self, curve: &[(f64, f64)]) -> Colorimetry {
+        let (xyz, white) = self.integrate(curve);
+        let sum: f64 = xyz.iter().sum();
+        Colorimetry {
+            xyz,
+            xyy: [xyz[0] / sum, xyz[1] / sum, xyz[1]],
+            lab: lab(xyz, white),
+            srgb: linear_srgb(bradford(xyz, white)).map(gamma),
+        }
+    }
+
+    /// Linear sRGB of a reflectance curve of `(nm, R)` pairs sorted by wavelength.
+    #[cfg_attr(not(all(feature = "x", feature = "y")), allow(dead_code))]
+    pub(crate) fn linear_srgb(&self, curve: &[(f64, f64)]) -> [f64; 3] {
+        let (xyz, white) = self.integrate(curve);
+        linear_srgb(bradford(xyz, white))
+    }
+
+    /// Tristimulus values of `curve` and of a perfect white reflector on the same axis. Each
+    /// sample is weighted by half the distance between its neighbours.
+    fn integrate(&self, curve: &[(f64, f64)]) -> ([f64; 3], [f64; 3]) {
+        let visible: Vec<(f64, f64)> = curve
+            .iter()
+            .copied()
+            .filter(|(nm, _)| (cie::START..=END).contains(nm))
+            .collect();
+        let mut xyz = [0.0; 3];
+        let mut white = [0.0; 3];
+        for (index, (nm, r)) in visible.iter().enumerate() {
+            let lower = index.checked_sub(1).map_or(*nm, |i| visible[i].0);
+            let upper = visible.get(index + 1).map_or(*nm, |(x, _)| *x);
+            let weight = match visible.len() {
+                1 => 1.0,
+                _ => (upper - lower) / 2.0,
+            };
+            let power = self.illuminant.power(*nm) * weight;
+            let cmf = self.observer.cmf(*nm);
+            (0..3).for_each(|i| {
+                xyz[i] += r * power * cmf[i];
+                white[i] += power * cmf[i];
+            });
+        }
+        let scale = white[1];
+        (xyz.map(|v| v / scale), white.map(|v| v / scale))
+    }
+}
+
+/// Apply the sRGB transfer function to a linear value, mirrored for negative values.
+pub(crate) fn gamma(linear: f64) -> f64 {
+    match linear.abs() <= 0.0031308 {
+        true => 12.92 * linear,
+        false => linear.signum() * (1.055 * linear.abs().powf(1.0 / 2.4) - 0.055),
+    }
+}
+
+/* ----------------------------------------------------------------------------- Private Helpers */
+
+/// Convert CIE XYZ to linear sRGB primaries (D65 white point).
+fn linear_srgb([x, y, z]: [f64; 3]) -> [f64; 3] {
+    [
+        3.2404542 * x - 1.5371385 * y - 0.4985314 * z,
+        -0.9692660 * x + 1.8760108 * y + 0.0415560 * z,
+        0.0556434 * x - 0.2040259 * y + 1.0572252 * z,
+    ]
+}
+
+const END: f64 = 780.0;
+
+/// sRGB reference white.
+const WHITE_D65: [f64; 3] = [0.95047, 1.0, 1.08883];
+
+impl Observer {
+    /// Colour matching functions at `nm`, zero outside the tabulated range.
+    fn cmf(&self, nm: f64) -> [f64; 3] {
+        let table = match self {
+            Observer::Cie1931 => &cie::CIE1931,
+            Observer::Cie1964 => &cie::CIE1964,
+        };
+        [0, 1, 2].map(|i| tabulated(nm, cie::STEP, |j| table.get(j).map(|row| row[i])))
+    }
+}
+
+impl Illuminant {
+    /// Relative spectral power at `nm`.
+    fn power(&self, nm: f64) -> f64 {
+        let step = cie::FLUORESCENT_STEP;
+        match self {
+            Illuminant::D65 => tabulated(nm, cie::STEP, |i| cie::D65.get(i).copied()),
+            Illuminant::A => cie::a(nm),
+            Illuminant::F2 => tabulated(nm, step, |i| cie::F2.get(i).copied()),
+            Illuminant::F7 => tabulated(nm, step, |i| cie::F7.get(i).copied()),
+            Illuminant::F11 => tabulated(nm, step, |i| cie::F11.get(i).copied()),
+            Illuminant::Custom(samples) => {
+                let curve: Vec<(f64, f64)> = samples
+                    .iter()
+                    .map(|(wl, power)| (wl.get::<nanometer>(), *power))
+                    .collect();
+                interpolate(&curve, nm)
+            }
+        }
+    }
+}
+
+/// Linearly interpolate a table sampled every `step` from [`cie::START`], zero outside it.
+fn tabulated(nm: f64, step: f64, at: impl Fn(usize) -> Option<f64>) -> f64 {
+    let position = (nm - cie::START) / step;
+    if position < 0.0 {
+        return 0.0;
+    }
+    let index = position.floor() as usize;
+    match (at(index), at(index + 1)) {
+        (Some(y0), Some(y1)) => y0 + (y1 - y0) * position.fract(),
+        (Some(y0), None) if position.fract() == 0.0 => y0,
+        _ => 0.0,
+    }
+}
+
+/// Linearly interpolate a sorted curve, held constant beyond its ends.
+fn interpolate(curve: &[(f64, f64)], nm: f64) -> f64 {
+    let index = curve.partition_point(|(x, _)| *x < nm);
+    match (
+        index.checked_sub(1).map(|i| curve[i]),
+        curve.get(index).copied(),
+    ) {
+        (Some((x0, y0)), Some((x1, y1))) if x1 > x0 => y0 + (y1 - y0) * (nm - x0) / (x1 - x0),
+        (_, Some((_, y))) | (Some((_, y)), None) => y,
+        (None, None) => f64::NAN,
+    }
+}
+
+/// CIELAB relative to `white`.
+fn lab(xyz: [f64; 3], white: [f64; 3]) -> [f64; 3] {
+    const EPSILON: f64 = 216.0 / 24389.0;
+    const KAPPA: f64 = 24389.0 / 27.0;
+    let f = |t: f64| match t > EPSILON {
+        true => t.cbrt(),
+        false => (KAPPA * t + 16.0) / 116.0,
+    };
+    let [fx, fy, fz] = [0, 1, 2].map(|i| f(xyz[i] / white[i]));
+    [116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz)]
+}
+
+/// Adapt `xyz` viewed under `white` to the sRGB D65 white with the Bradford transform.
+fn bradford(xyz: [f64; 3], white: [f64; 3]) -> [f64; 3] {
+    const M: [[f64; 3]; 3] = [
+        [0.8951, 0.2664, -0.1614],
+        [-0.7502, 1.7135, 0.0367],
+        [0.0389, -0.0685, 1.0296],
+    ];
+    const INVERSE: [[f64; 3]; 3] = [
+        [0.9869929, -0.1470543, 0.1599627],
+        [0.4323053, 0.5183603, 0.0492912],
+        [-0.0085287, 0.0400428, 0.9684867],
+    ];
+    let multiply = |m: &[[f64; 3]; 3], v: [f64; 3]| {
+        m.map(|row| row.iter().zip(v).map(|(a, b)| a * b).sum::<f64>())
+    };
+    let (source, target) = (multiply(&M, white), multiply(&M, WHITE_D65));
+    let cone = multiply(&M, xyz);
+    multiply(&INVERSE, [0, 1, 2].map(|i| cone[i] * target[i] / source[i]))
+}
+
+/* ---------------------------------------------------------------------------------- Unit Tests */
+
+#[cfg(all(
+    test,
+    feature = "x",
+    feature = "y",
+    not(feature = "z"),
+    not(feature = "a")
+))]
+mod tests {
+    use std::fs::remove_dir_all;
+
+    use uom::si::f64::Time;
+    use uom::si::length::micrometer;
+    use uom::si::time::millisecond;
+
+    use super::*;
+    use crate::Kind;
+
+    /// A flat reflectance of 1 every nanometre across the visible range.
+    fn white() -> Vec<(f64, f64)> {
+        (380..=780).map(|nm| (nm as f64, 1.0)).collect()
+    }
+
+    #[test]
+    fn white_points() {
+        let cases = [
+            (Observer::Cie1931, Illuminant::D65, [0.3127, 0.3290]),
+            (Observer::Cie1964, Illuminant::D65, [0.3138, 0.3310]),
+            (Observer::Cie1931, Illuminant::A, [0.4476, 0.4074]),
+            (Observer::Cie1931, Illuminant::F2, [0.3721, 0.3751]),
+            (Observer::Cie1931, Illuminant::F7, [0.3129, 0.3292]),
+            (Observer::Cie1931, Illuminant::F11, [0.3805, 0.3769]),
+        ];
+        for (observer, illuminant, [x, y]) in cases {
+            let viewing = Viewing {
+                observer,
+                illuminant,
+            };
+            let colour = viewing.characterise(&white());
+            assert!(
+                (colour.xyy[0] - x).abs() < 2E-3,
+                "{viewing:?} x {}",
+                colour.xyy[0]
+            );
+            assert!(
+                (colour.xyy[1] - y).abs() < 2E-3,
+                "{viewing:?} y {}",
+                colour.xyy[1]
+            );
+            assert!((colour.lab[0] - 100.0).abs() < 1E-9);
+            assert!(colour.lab[1].abs() < 1E-9 && colour.lab[2].abs() < 1E-9);
+            assert!(colour.srgb.iter().all(|v| (v - 1.0).abs() < 1E-6));
+        }
+    }
+
+    #[test]
+    fn colour_differences() {
+        const PATH: &str = "test-colour";
+        let mut db = Database::new(PATH).unwrap();
+        let nm: Vec<f64> = (380..=780).step_by(5).map(f64::from).collect();
+        let wavelengths = db.wavelengths.push(nm.clone()).unwrap();
+        db.wavelengths.commit().unwrap();
+        let integration = Time::new::<millisecond>(10.0);
+        let zero = Length::new::<micrometer>(0.0);
+        let grey = |r: f64| nm.iter().map(|_| r).collect::<Vec<f64>>();
+        let red: Vec<f64> = nm
+            .iter()
+            .map(|nm| if *nm > 600.0 { 0.9 } else { 0.1 })
+            .collect();
+        let ids: Vec<u32> = [grey(0.5), grey(0.5), red]
+            .into_iter()
+            .map(|values| {
+                let id = db
+                    .measurements
+                    .push(Kind::Sample, zero, zero, integration, &[])
+                    .unwrap();
+                db.intensities.push(id, &wavelengths, values);
+                id
+            })
+            .collect();
+        db.measurements.commit().unwrap();
+        db.intensities.commit().unwrap();
+
+        let viewing = Viewing::default();
+        let colours = db.colorimetry(Source::Intensities, &ids, &viewing).unwrap();
+        let grey = colours[&ids[0]];
+        assert!((grey.xyz[1] - 0.5).abs() < 1E-9);
+        assert!((grey.lab[0] - 76.0693).abs() < 1E-3);
+        assert!(grey.lab[1].abs() < 1E-9 && grey.lab[2].abs() < 1E-9);
+        let red = colours[&ids[2]];
+        assert!(red.lab[1] > 30.0 && red.srgb[0] > red.srgb[1] && red.srgb[0] > red.srgb[2]);
+
+        for formula in [Difference::Cie76, Difference::Ciede2000] {
+            let differences = db
+                .colour_difference(Source::Intensities, ids[0], &ids, &viewing, formula)
+                .unwrap();
+            assert_eq!(differences[&ids[1]], 0.0);
+            assert!(differences[&ids[2]] > 10.0);
+        }
+        let missing =
+            db.colour_difference(Source::Intensities, 99, &ids, &viewing, Difference::Cie76);
+        assert!(matches!(missing, Err(Error::MissingReference(99))));
+        remove_dir_all(PATH).unwrap();
+    }
+}
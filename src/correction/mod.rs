@@ -0,0 +1,380 @@
+/*
+Project: Optic
+GitHub: https://github.com/MillieFD/optic
+
+BSD 3-Clause License, Copyright (c) 2026, Amelia Fraser-Dale
+
+Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the conditions of the LICENSE are met.
+*/
+
+/* ----------------------------------------------------------------------------- Private Imports */
+
+use std::collections::{HashMap, HashSet};
+use std::fs::{DirBuilder, OpenOptions, remove_file};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, AsArray, Float64Array, RecordBatch, UInt32Array};
+use arrow::datatypes::DataType::{Float64, UInt32};
+use arrow::datatypes::{Field, Float64Type, Schema, UInt32Type};
+
+use crate::analysis::linalg::solve;
+use crate::derived::{Table, list};
+use crate::intensities::Spectrum;
+use crate::writer::new_stream_writer;
+use crate::{Database, Derived, Error, Provenance, Reader};
+
+/* ------------------------------------------------------------------------------ Public Exports */
+
+/// Detector correction profile of an instrument, stored in the `corrections` directory.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Profile {
+    /// Coefficients `cᵢ` of the recorded fraction of true counts as a polynomial in recorded
+    /// counts, in ascending order of power. Corrected counts are `counts / Σ cᵢ countsⁱ`. Empty
+    /// for no nonlinearity correction.
+    pub nonlinearity: Vec<f64>,
+    /// Wavelength IDs of the rows and columns of `stray_light`.
+    pub wavelengths: Vec<u32>,
+    /// Stray-light distribution matrix `D` where `D[i][j]` is the fraction of the signal at
+    /// `wavelengths[j]` recorded at `wavelengths[i]`, excluding the in-band response. Measured
+    /// spectra are corrected as `(I + D)⁻¹ · counts`. Empty for no stray-light correction.
+    pub stray_light: Vec<Vec<f64>>,
+}
+
+impl Profile {
+    fn validate(&self) -> Result<(), Error> {
+        let n = self.wavelengths.len();
+        let distinct: HashSet<&u32> = self.wavelengths.iter().collect();
+        match self.stray_light.is_empty() {
+            true => Ok(()),
+            false if distinct.len() != n => Err(Error::InvalidParameter(
+                "Stray-light wavelengths must be distinct".into(),
+            )),
+            false
+                if self.stray_light.len() != n || self.stray_light.iter().any(|r| r.len() != n) =>
+            {
+                Err(Error::InvalidParameter(
+                    "Stray-light matrix must be square with one row per wavelength".into(),
+                ))
+            }
+            false => Ok(()),
+        }
+    }
+}
+
+impl Database {
+    fn corrections_directory(&self) -> PathBuf {
+        self.path.join("corrections")
+    }
+
+    /// Store `profile` under `name`. Names follow the same rules as [`Database::create_derived`].
+    /// Fails if a profile with the same name already exists.
+    pub fn store_profile(&self, name: &str, profile: &Profile) -> Result<(), Error> {
+        profile.validate()?;
+        let directory = self.corrections_directory();
+        let path = Derived::locate(&directory, name)?;
+        DirBuilder::new().recursive(true).create(&directory)?;
+        let file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)?;
+        let rows = profile.stray_light.len();
+        let columns = profile.stray_light.first().map_or(0, Vec::len);
+        let metadata = HashMap::from([
+            (NONLINEARITY.to_string(), join(&profile.nonlinearity)),
+            (WAVELENGTHS.to_string(), join(&profile.wavelengths)),
+            (SHAPE.to_string(), join(&[rows, columns])),
+        ]);
+        let fields = vec![
+            Field::new("row", UInt32, false),
+            Field::new("column", UInt32, false),
+            Field::new("value", Float64, false),
+        ];
+        let schema = Schema::new(fields).with_metadata(metadata);
+        let (mut rows, mut columns, mut values) = (Vec::new(), Vec::new(), Vec::new());
+        for (row, entries) in profile.wavelengths.iter().zip(&profile.stray_light) {
+            for (column, value) in profile.wavelengths.iter().zip(entries) {
+                if *value != 0.0 {
+                    rows.push(*row);
+                    columns.push(*column);
+                    values.push(*value);
+                }
+            }
+        }
+        let arrays: Vec<ArrayRef> = vec![
+            Arc::new(UInt32Array::from(rows)),
+            Arc::new(UInt32Array::from(columns)),
+            Arc::new(Float64Array::from(values)),
+        ];
+        let batch = RecordBatch::try_new(Arc::new(schema.clone()), arrays)?;
+        let mut stream = new_stream_writer(file, &schema)?;
+        stream.write(&batch)?;
+        stream.finish().map_err(Error::from)
+    }
+
+    /// List the names of every correction profile in alphabetical order.
+    pub fn profiles(&self) -> Result<Vec<String>, Error> {
+        list(&self.corrections_directory())
+    }
+
+    /// Read the correction profile called `name`.
+    pub fn read_profile(&self, name: &str) -> Result<Profile, Error> {
+        let path = Derived::locate(&self.corrections_directory(), name)?;
+        let reader = Table(path).reader()?;
+        let metadata = reader.schema().metadata().clone();
+        let parse = |key: &str| -> Result<Vec<String>, Error> {
+            let text = metadata.get(key).cloned().unwrap_or_default();
+            Ok(text
+                .split(',')
+                .filter(|item| !item.is_empty())
+                .map(str::to_string)
+                .collect())
+        };
+        let number = |item: String| {
+            item.parse()
+                .map_err(|_| Error::ParseError(format!("'{item}' in correction profile")))
+        };
+        let nonlinearity = parse(NONLINEARITY)?
+            .into_iter()
+            .map(number)
+            .collect::<Result<Vec<f64>, Error>>()?;
+        let wavelengths = parse(WAVELENGTHS)?
+            .into_iter()
+            .map(|item| number(item).map(|id: f64| id as u32))
+            .collect::<Result<Vec<u32>, Error>>()?;
+        let index: HashMap<u32, usize> = wavelengths
+            .iter()
+            .enumerate()
+            .map(|(i, w)| (*w, i))
+            .collect();
+        let shape = parse(SHAPE)?
+            .into_iter()
+            .map(|item| number(item).map(|size: f64| size as usize))
+            .collect::<Result<Vec<usize>, Error>>()?;
+        let n = wavelengths.len();
+        let mut stray_light = match shape[..] {
+            [0, 0] => Vec::new(), // No stray-light correction
+            [rows, columns] if rows == n && columns == n => vec![vec![0.0; n]; n],
+            _ => {
+                let e = format!("stray-light shape {shape:?} for {n} wavelengths");
+                return Err(Error::ParseError(e));
+            }
+        };
+        for batch in reader {
+            let batch = batch?;
+            let rows = batch.column(0).as_primitive::<UInt32Type>().values();
+            let columns = batch.column(1).as_primitive::<UInt32Type>().values();
+            let values = batch.column(2).as_primitive::<Float64Type>().values();
+            for ((row, column), value) in rows.iter().zip(columns).zip(values) {
+                match (index.get(row), index.get(column)) {
+                    (Some(r), Some(c)) if !stray_light.is_empty() => stray_light[*r][*c] = *value,
+                    (Some(_), Some(_)) => {
+                        let e = "stray-light values without a matrix".to_string();
+                        return Err(Error::ParseError(e));
+                    }
+                    (None, _) => return Err(Error::MissingWavelength(*row)),
+                    (_, None) => return Err(Error::MissingWavelength(*column)),
+                }
+            }
+        }
+        Ok(Profile {
+            nonlinearity,
+            wavelengths,
+            stray_light,
+        })
+    }
+
+    /// Delete the correction profile called `name`. Tables already corrected are not affected.
+    pub fn delete_profile(&self, name: &str) -> Result<(), Error> {
+        let path = Derived::locate(&self.corrections_directory(), name)?;
+        remove_file(path).map_err(Error::from)
+    }
+
+    /// Apply `profile` to the raw `intensities` of `measurements`: nonlinearity first, then stray
+    /// light. Nothing is written to disk. Fails with [`Error::MissingWavelength`] if a spectrum
+    /// lacks a wavelength of the stray-light matrix.
+    pub fn detector_corrected(
+        &self,
+        measurements: &[u32],
+        profile: &Profile,
+    ) -> Result<HashMap<u32, Spectrum>, Error> {
+        profile.validate()?;
+        let inverse = match profile.stray_light.is_empty() {
+            true => None,
+            false => Some(invert(&profile.stray_light).ok_or(Error::InvalidParameter(
+                "Stray-light matrix is singular".into(),
+            ))?),
+        };
+        let linearise = |counts: f64| match profile.nonlinearity.is_empty() {
+            true => counts,
+            false => {
+                let fraction = profile
+                    .nonlinearity
+                    .iter()
+                    .rev()
+                    .fold(0.0, |total, c| total * counts + c);
+                counts / fraction
+            }
+        };
+        self.intensities
+            .spectra(measurements)?
+            .into_iter()
+            .map(|(id, spectrum)| {
+                let mut spectrum: Spectrum = spectrum
+                    .into_iter()
+                    .map(|(wavelength, counts)| (wavelength, linearise(counts)))
+                    .collect();
+                if let Some(inverse) = &inverse {
+                    let counts = profile
+                        .wavelengths
+                        .iter()
+                        .map(|w| spectrum.get(w).copied().ok_or(Error::MissingWavelength(*w)))
+                        .collect::<Result<Vec<f64>, Error>>()?;
+                    for (wavelength, row) in profile.wavelengths.iter().zip(inverse) {
+                        let value = row.iter().zip(&counts).map(|(a, b)| a * b).sum();
+                        spectrum.insert(*wavelength, value);
+                    }
+                }
+                Ok((id, spectrum))
+            })
+            .collect()
+    }
+
+    /// Apply the stored correction profile called `profile` as [`Database::detector_corrected`]
+    /// and write the result to the derived table called `name`. Returns the IDs of the corrected
+    /// measurements.
+    pub fn correct_detector(
+        &self,
+        name: &str,
+        measurements: &[u32],
+        profile: &str,
+    ) -> Result<Vec<u32>, Error> {
+        let stored = self.read_profile(profile)?;
+        let mut spectra: Vec<_> = self
+            .detector_corrected(measurements, &stored)?
+            .into_iter()
+            .collect();
+        spectra.sort_unstable_by_key(|(id, _)| *id);
+        let provenance = Provenance::new("detector_correction", &["intensities", "corrections"])
+            .parameter("profile", profile)
+            .parameter("nonlinearity", join(&stored.nonlinearity))
+            .parameter("stray_light", !stored.stray_light.is_empty());
+        let mut table = self.create_derived(name, &provenance)?;
+        for (id, spectrum) in &spectra {
+            let (wavelengths, values): (Vec<u32>, Vec<f64>) = spectrum.iter().unzip();
+            table.push(*id, &wavelengths, values);
+        }
+        table.commit()?;
+        Ok(spectra.into_iter().map(|(id, _)| id).collect())
+    }
+}
+
+/* ----------------------------------------------------------------------------- Private Helpers */
+
+const NONLINEARITY: &str = "nonlinearity";
+const WAVELENGTHS: &str = "wavelengths";
+const SHAPE: &str = "stray_light_shape";
+
+fn join<T: ToString>(items: &[T]) -> String {
+    items
+        .iter()
+        .map(T::to_string)
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+/// Invert `I + distribution`, or `None` if it is singular.
+fn invert(distribution: &[Vec<f64>]) -> Option<Vec<Vec<f64>>> {
+    let identity = |i: usize| -> Vec<f64> {
+        (0..distribution.len())
+            .map(|j| if i == j { 1.0 } else { 0.0 })
+            .collect()
+    };
+    let matrix = distribution
+        .iter()
+        .enumerate()
+        .map(|(i, row)| row.iter().zip(identity(i)).map(|(d, e)| d + e).collect())
+        .collect();
+    solve(matrix, (0..distribution.len()).map(identity).collect())
+}
+
+/* ---------------------------------------------------------------------------------- Unit Tests */
+
+#[cfg(all(
+    test,
+    feature = "x",
+    feature = "y",
+    not(feature = "z"),
+    not(feature = "a")
+))]
+mod tests {
+    use std::fs::remove_dir_all;
+
+    use uom::si::f64::{Length, Time};
+    use uom::si::length::micrometer;
+    use uom::si::time::millisecond;
+
+    use super::*;
+    use crate::{Kind, Source};
+
+    #[test]
+    fn correct_counts() {
+        const PATH: &str = "test-correction";
+        let mut db = Database::new(PATH).unwrap();
+        let wavelengths = db.wavelengths.push(vec![500.0, 600.0, 700.0]).unwrap();
+        db.wavelengths.commit().unwrap();
+        let zero = Length::new::<micrometer>(0.0);
+        let integration = Time::new::<millisecond>(10.0);
+        let id = db
+            .measurements
+            .push(Kind::Sample, zero, zero, integration, &[])
+            .unwrap();
+        // True counts [100, 200, 300] with 10% of the first band leaking into the second
+        db.intensities
+            .push(id, &wavelengths, vec![100.0, 210.0, 300.0]);
+        db.measurements.commit().unwrap();
+        db.intensities.commit().unwrap();
+
+        let profile = Profile {
+            nonlinearity: vec![1.0],
+            wavelengths: wavelengths[..2].to_vec(),
+            stray_light: vec![vec![0.0, 0.0], vec![0.1, 0.0]],
+        };
+        let invalid = Profile {
+            stray_light: vec![vec![0.0]],
+            ..profile.clone()
+        };
+        assert!(db.store_profile("bad", &invalid).is_err());
+        db.store_profile("spectrometer", &profile).unwrap();
+        assert_eq!(db.profiles().unwrap(), vec!["spectrometer"]);
+        assert_eq!(db.read_profile("spectrometer").unwrap(), profile);
+        let zeros = Profile {
+            stray_light: vec![vec![0.0; 2]; 2],
+            ..profile.clone()
+        };
+        db.store_profile("zeros", &zeros).unwrap();
+        assert_eq!(db.read_profile("zeros").unwrap(), zeros); // Not read back as empty
+        db.delete_profile("zeros").unwrap();
+
+        let ids = db
+            .correct_detector("corrected", &[id], "spectrometer")
+            .unwrap();
+        assert_eq!(ids, vec![id]);
+        let spectrum = &db.spectra(Source::Derived("corrected"), &[id]).unwrap()[&id];
+        let expected = [100.0, 200.0, 300.0];
+        for (value, expected) in spectrum.values().zip(expected) {
+            assert!((value - expected).abs() < 1E-9);
+        }
+
+        let saturating = Profile {
+            nonlinearity: vec![1.0, -0.001], // Half the true counts are recorded at 500 counts
+            ..Profile::default()
+        };
+        let corrected = db.detector_corrected(&[id], &saturating).unwrap();
+        assert!((corrected[&id][&wavelengths[0]] - 100.0 / 0.9).abs() < 1E-9);
+        db.delete_profile("spectrometer").unwrap();
+        assert!(db.profiles().unwrap().is_empty());
+        remove_dir_all(PATH).unwrap();
+    }
+}
@@ -0,0 +1,365 @@
+/*
+Project: Optic
+GitHub: https://github.com/MillieFD/optic
+
+BSD 3-Clause License, Copyright (c) 2026, Amelia Fraser-Dale
+
+Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the conditions of the LICENSE are met.
+*/
+
+//! Minimal zlib codec for the HDF5 `deflate` filter and PNG images. Compression uses LZ77 with
+//! fixed Huffman codes; decompression supports stored, fixed and dynamic blocks.
+
+/* ----------------------------------------------------------------------------- Private Imports */
+
+use crate::Error;
+
+/* --------------------------------------------------------------------------------- Constants */
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+const WINDOW: usize = 32_768;
+const MAX_CHAIN: usize = 64;
+
+/* ------------------------------------------------------------------------------ Public Exports */
+
+/// Compress `data` into a zlib stream.
+pub(crate) fn compress(data: &[u8]) -> Vec<u8> {
+    let mut bits = BitWriter::default();
+    bits.write(1, 1); // BFINAL
+    bits.write(1, 2); // BTYPE = fixed Huffman
+    let mut head = vec![usize::MAX; 1 << 15];
+    let mut prev = vec![usize::MAX; data.len()];
+    let hash = |i: usize| {
+        let h = (data[i] as usize) << 10 ^ (data[i + 1] as usize) << 5 ^ data[i + 2] as usize;
+        h & 0x7FFF
+    };
+    let mut i = 0;
+    while i < data.len() {
+        let (mut length, mut distance) = (0, 0);
+        if i + 3 <= data.len() {
+            let h = hash(i);
+            let mut candidate = head[h];
+            let mut chain = 0;
+            while candidate != usize::MAX && i - candidate <= WINDOW && chain < MAX_CHAIN {
+                let n = data[candidate..]
+                    .iter()
+                    .zip(&data[i..])
+                    .take(258)
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                if n > length {
+                    (length, distance) = (n, i - candidate);
+                }
+                candidate = prev[candidate];
+                chain += 1;
+            }
+            prev[i] = head[h];
+            head[h] = i;
+        }
+        if length >= 3 {
+            bits.length(length, distance);
+            (i + 1..i + length)
+                .filter(|j| j + 3 <= data.len())
+                .for_each(|j| {
+                    let h = hash(j);
+                    prev[j] = head[h];
+                    head[h] = j;
+                });
+            i += length;
+        } else {
+            bits.symbol(data[i] as u16);
+            i += 1;
+        }
+    }
+    bits.symbol(256); // End of block
+    let mut stream = vec![0x78, 0x01];
+    stream.extend(bits.finish());
+    stream.extend(adler32(data).to_be_bytes());
+    stream
+}
+
+/// Decompress a zlib stream.
+#[cfg_attr(not(feature = "hdf5"), allow(dead_code))]
+pub(crate) fn decompress(stream: &[u8]) -> Result<Vec<u8>, Error> {
+    let invalid = || Error::ParseError("Invalid deflate stream".into());
+    let [cmf, flg, ..] = *stream else {
+        return Err(invalid());
+    };
+    if cmf & 0x0F != 8 || !(cmf as u16 * 256 + flg as u16).is_multiple_of(31) || flg & 0x20 != 0 {
+        return Err(invalid());
+    }
+    let mut bits = BitReader::new(&stream[2..]);
+    let mut output = Vec::new();
+    loop {
+        let last = bits.read(1)?;
+        match bits.read(2)? {
+            0 => bits.stored(&mut output)?,
+            1 => {
+                let (literals, distances) = Huffman::fixed();
+                bits.codes(&mut output, &literals, &distances)?
+            }
+            2 => {
+                let (literals, distances) = bits.dynamic()?;
+                bits.codes(&mut output, &literals, &distances)?
+            }
+            _ => return Err(invalid()),
+        }
+        if last == 1 {
+            break;
+        }
+    }
+    Ok(output)
+}
+
+/* ----------------------------------------------------------------------------- Private Helpers */
+
+fn adler32(data: &[u8]) -> u32 {
+    let (a, b) = data
+        .chunks(5552)
+        .fold((1u32, 0u32), |(mut a, mut b), chunk| {
+            for &byte in chunk {
+                a += byte as u32;
+                b += a;
+            }
+            (a % 65521, b % 65521)
+        });
+    b << 16 | a
+}
+
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u64,
+    count: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u32, bits: u32) {
+        self.buffer |= (value as u64) << self.count;
+        self.count += bits;
+        while self.count >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.count -= 8;
+        }
+    }
+
+    /// Write a Huffman code, which is packed starting from the most significant bit.
+    fn code(&mut self, code: u32, bits: u32) {
+        self.write(code.reverse_bits() >> (32 - bits), bits);
+    }
+
+    fn symbol(&mut self, symbol: u16) {
+        match symbol {
+            0..=143 => self.code(0x30 + symbol as u32, 8),
+            144..=255 => self.code(0x190 + symbol as u32 - 144, 9),
+            256..=279 => self.code(symbol as u32 - 256, 7),
+            _ => self.code(0xC0 + symbol as u32 - 280, 8),
+        }
+    }
+
+    fn length(&mut self, length: usize, distance: usize) {
+        let index = LENGTH_BASE.partition_point(|&base| base as usize <= length) - 1;
+        self.symbol(257 + index as u16);
+        self.write(
+            (length - LENGTH_BASE[index] as usize) as u32,
+            LENGTH_EXTRA[index] as u32,
+        );
+        let index = DISTANCE_BASE.partition_point(|&base| base as usize <= distance) - 1;
+        self.code(index as u32, 5);
+        self.write(
+            (distance - DISTANCE_BASE[index] as usize) as u32,
+            DISTANCE_EXTRA[index] as u32,
+        );
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.count > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
+
+/// Canonical Huffman decoding table.
+struct Huffman {
+    counts: [u16; 16],
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> Self {
+        let mut counts = [0u16; 16];
+        lengths.iter().for_each(|&len| counts[len as usize] += 1);
+        counts[0] = 0;
+        let mut offsets = [0u16; 16];
+        (1..15).for_each(|len| offsets[len + 1] = offsets[len] + counts[len]);
+        let mut symbols = vec![0; lengths.len()];
+        for (symbol, &len) in lengths.iter().enumerate().filter(|(_, len)| **len != 0) {
+            symbols[offsets[len as usize] as usize] = symbol as u16;
+            offsets[len as usize] += 1;
+        }
+        Self { counts, symbols }
+    }
+
+    fn fixed() -> (Self, Self) {
+        let mut lengths = [8u8; 288];
+        lengths[144..256].fill(9);
+        lengths[256..280].fill(7);
+        (Self::new(&lengths), Self::new(&[5; 30]))
+    }
+}
+
+struct BitReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+    buffer: u32,
+    count: u32,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self {
+            bytes,
+            position: 0,
+            buffer: 0,
+            count: 0,
+        }
+    }
+
+    fn read(&mut self, bits: u32) -> Result<u32, Error> {
+        while self.count < bits {
+            let byte = *self
+                .bytes
+                .get(self.position)
+                .ok_or_else(|| Error::ParseError("Truncated deflate stream".into()))?;
+            self.buffer |= (byte as u32) << self.count;
+            self.position += 1;
+            self.count += 8;
+        }
+        let value = self.buffer & ((1u64 << bits) - 1) as u32;
+        self.buffer >>= bits;
+        self.count -= bits;
+        Ok(value)
+    }
+
+    fn decode(&mut self, huffman: &Huffman) -> Result<u16, Error> {
+        let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+        for len in 1..16 {
+            code |= self.read(1)? as i32;
+            let count = huffman.counts[len] as i32;
+            if code - count < first {
+                return Ok(huffman.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err(Error::ParseError("Invalid Huffman code".into()))
+    }
+
+    fn stored(&mut self, output: &mut Vec<u8>) -> Result<(), Error> {
+        (self.buffer, self.count) = (0, 0); // Discard bits up to the byte boundary
+        let len = self.read(16)? as usize;
+        let nlen = self.read(16)? as usize;
+        if len != !nlen & 0xFFFF || self.position + len > self.bytes.len() {
+            return Err(Error::ParseError("Invalid stored block".into()));
+        }
+        output.extend_from_slice(&self.bytes[self.position..self.position + len]);
+        self.position += len;
+        Ok(())
+    }
+
+    fn dynamic(&mut self) -> Result<(Huffman, Huffman), Error> {
+        const ORDER: [usize; 19] = [
+            16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+        ];
+        let nlen = self.read(5)? as usize + 257;
+        let ndist = self.read(5)? as usize + 1;
+        let ncode = self.read(4)? as usize + 4;
+        let mut lengths = [0u8; 19];
+        for &index in &ORDER[..ncode] {
+            lengths[index] = self.read(3)? as u8;
+        }
+        let codes = Huffman::new(&lengths);
+        let mut lengths = Vec::with_capacity(nlen + ndist);
+        while lengths.len() < nlen + ndist {
+            let (value, repeat) = match self.decode(&codes)? {
+                symbol @ 0..=15 => (symbol as u8, 1),
+                16 => {
+                    let previous = *lengths
+                        .last()
+                        .ok_or_else(|| Error::ParseError("Invalid code lengths".into()))?;
+                    (previous, 3 + self.read(2)?)
+                }
+                17 => (0, 3 + self.read(3)?),
+                _ => (0, 11 + self.read(7)?),
+            };
+            lengths.extend(std::iter::repeat_n(value, repeat as usize));
+        }
+        if lengths.len() > nlen + ndist {
+            return Err(Error::ParseError("Invalid code lengths".into()));
+        }
+        Ok((
+            Huffman::new(&lengths[..nlen]),
+            Huffman::new(&lengths[nlen..]),
+        ))
+    }
+
+    fn codes(
+        &mut self,
+        output: &mut Vec<u8>,
+        literals: &Huffman,
+        distances: &Huffman,
+    ) -> Result<(), Error> {
+        let invalid = || Error::ParseError("Invalid deflate symbol".into());
+        loop {
+            match self.decode(literals)? as usize {
+                symbol @ 0..=255 => output.push(symbol as u8),
+                256 => break Ok(()),
+                symbol => {
+                    let index = symbol - 257;
+                    let base = *LENGTH_BASE.get(index).ok_or_else(invalid)? as usize;
+                    let length = base + self.read(LENGTH_EXTRA[index] as u32)? as usize;
+                    let index = self.decode(distances)? as usize;
+                    let base = *DISTANCE_BASE.get(index).ok_or_else(invalid)? as usize;
+                    let distance = base + self.read(DISTANCE_EXTRA[index] as u32)? as usize;
+                    let start = output.len().checked_sub(distance).ok_or_else(invalid)?;
+                    (start..start + length).for_each(|i| output.push(output[i]));
+                }
+            }
+        }
+    }
+}
+
+/* ---------------------------------------------------------------------------------- Unit Tests */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deflate_round_trip() {
+        let data: Vec<u8> = (0..100_000u32)
+            .flat_map(|n| (n % 977).to_le_bytes())
+            .collect();
+        let compressed = compress(&data);
+        assert!(compressed.len() < data.len() / 2);
+        assert_eq!(decompress(&compressed).unwrap(), data);
+    }
+}
@@ -0,0 +1,264 @@
+/*
+Project: Optic
+GitHub: https://github.com/MillieFD/optic
+
+BSD 3-Clause License, Copyright (c) 2026, Amelia Fraser-Dale
+
+Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the conditions of the LICENSE are met.
+*/
+
+/* ----------------------------------------------------------------------------- Private Modules */
+
+mod provenance;
+
+/* ----------------------------------------------------------------------------- Private Imports */
+
+use std::collections::{HashMap, HashSet};
+use std::fs::{DirBuilder, File, OpenOptions, read_dir, remove_file};
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use arrow::array::RecordBatch;
+use arrow::datatypes::DataType::{Float64, UInt32};
+use arrow::datatypes::{Field, Schema};
+use arrow::ipc::reader::StreamReader;
+use arrow::ipc::writer::StreamWriter;
+
+pub use self::provenance::Provenance;
+use crate::intensities::{Builder, Spectrum, group};
+use crate::writer::new_stream_writer;
+use crate::{Database, Error, Reader};
+
+/* ------------------------------------------------------------------------------ Public Exports */
+
+/// A processed table stored alongside the raw data in the `derived` directory. Rows share the
+/// `(measurement, wavelength)` key of the `intensities` table with a single `value` column.
+pub struct Derived {
+    stream: StreamWriter<File>,
+    builder: Builder,
+    schema: Arc<Schema>,
+    pub name: String,
+    pub path: PathBuf,
+}
+
+/// Table from which spectra are read for processing.
+#[derive(Copy, Clone, Debug)]
+pub enum Source<'a> {
+    /// Raw counts from the `intensities` table.
+    Intensities,
+    /// Values from the derived table with this name.
+    Derived(&'a str),
+}
+
+impl Source<'_> {
+    /// Name recorded in the [`Provenance`] of tables computed from this source.
+    pub fn name(&self) -> &str {
+        match self {
+            Source::Intensities => "intensities",
+            Source::Derived(name) => name,
+        }
+    }
+}
+
+impl Derived {
+    fn create(directory: &Path, name: &str, provenance: &Provenance) -> Result<Self, Error> {
+        let path = Self::locate(directory, name)?;
+        DirBuilder::new().recursive(true).create(directory)?;
+        let file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)?;
+        let fields = [
+            Field::new("measurement", UInt32, false).into(),
+            Field::new("wavelength", UInt32, false).into(),
+            Field::new("value", Float64, false).into(),
+        ];
+        let schema = Arc::new(Schema::new(fields).with_metadata(provenance.metadata()));
+        Ok(Self {
+            stream: new_stream_writer(file, &schema)?,
+            builder: Builder::new(),
+            schema,
+            name: name.to_string(),
+            path,
+        })
+    }
+
+    pub(crate) fn locate(directory: &Path, name: &str) -> Result<PathBuf, Error> {
+        let valid = |c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_';
+        match !name.is_empty() && name.chars().all(valid) {
+            true => Ok(directory.join(name).with_extension("arrow")),
+            false => Err(Error::InvalidName(name.to_string())),
+        }
+    }
+
+    pub fn push(&mut self, measurement: u32, wavelengths: &[u32], values: Vec<f64>) {
+        self.builder.push(measurement, wavelengths, values);
+    }
+
+    pub fn commit(&mut self) -> Result<(), Error> {
+        let columns = self.builder.columns();
+        let batch = RecordBatch::try_new(self.schema.clone(), columns)?;
+        self.stream.write(&batch).map_err(Error::from)
+    }
+}
+
+impl Database {
+    fn derived_directory(&self) -> PathBuf {
+        self.path.join("derived")
+    }
+
+    /// Create an empty derived table called `name`. Names may contain ASCII letters, digits, `-`
+    /// and `_`. Fails if a derived table with the same name already exists.
+    pub fn create_derived(&self, name: &str, provenance: &Provenance) -> Result<Derived, Error> {
+        Derived::create(&self.derived_directory(), name, provenance)
+    }
+
+    /// Create the derived tables `tables` together as [`Database::create_derived`]. If any of
+    /// them cannot be created, those already created are deleted again so that a failed call
+    /// leaves no partial output behind.
+    pub(crate) fn create_derived_all(
+        &self,
+        tables: &[(String, Provenance)],
+    ) -> Result<Vec<Derived>, Error> {
+        let mut created: Vec<Derived> = Vec::with_capacity(tables.len());
+        for (name, provenance) in tables {
+            match self.create_derived(name, provenance) {
+                Ok(table) => created.push(table),
+                Err(error) => {
+                    created
+                        .into_iter()
+                        .for_each(|table| drop(remove_file(table.path)));
+                    return Err(error);
+                }
+            }
+        }
+        Ok(created)
+    }
+
+    /// List the names of every derived table in alphabetical order.
+    pub fn derived(&self) -> Result<Vec<String>, Error> {
+        list(&self.derived_directory())
+    }
+
+    /// Read how the derived table called `name` was produced.
+    pub fn provenance(&self, name: &str) -> Result<Provenance, Error> {
+        let path = Derived::locate(&self.derived_directory(), name)?;
+        let reader = StreamReader::try_new(File::open(path)?, None)?;
+        Ok(Provenance::from(reader.schema().metadata()))
+    }
+
+    /// Read every committed spectrum of the derived table called `name`, keyed by measurement ID.
+    pub fn read_derived(&self, name: &str) -> Result<HashMap<u32, Spectrum>, Error> {
+        let path = Derived::locate(&self.derived_directory(), name)?;
+        let batches = Table(path).batches()?;
+        Ok(group(&batches, "value", |_| true))
+    }
+
+    /// Read the spectra of `measurements` from `source`, keyed by measurement ID.
+    pub fn spectra(
+        &self,
+        source: Source,
+        measurements: &[u32],
+    ) -> Result<HashMap<u32, Spectrum>, Error> {
+        match source {
+            Source::Intensities => self.intensities.spectra(measurements),
+            Source::Derived(name) => {
+                let wanted: HashSet<&u32> = measurements.iter().collect();
+                let path = Derived::locate(&self.derived_directory(), name)?;
+                let batches = Table(path).batches()?;
+                Ok(group(&batches, "value", |id| wanted.contains(&id)))
+            }
+        }
+    }
+
+    /// Stream the committed batches of `source` one at a time, together with the name of the
+    /// column that holds its values.
+    pub(crate) fn stream(
+        &self,
+        source: Source,
+    ) -> Result<(StreamReader<BufReader<File>>, &'static str), Error> {
+        match source {
+            Source::Intensities => Ok((self.intensities.reader()?, "intensity")),
+            Source::Derived(name) => {
+                let path = Derived::locate(&self.derived_directory(), name)?;
+                Ok((Table(path).reader()?, "value"))
+            }
+        }
+    }
+
+    /// Delete the derived table called `name`. Raw data is never affected.
+    pub fn delete_derived(&self, name: &str) -> Result<(), Error> {
+        let path = Derived::locate(&self.derived_directory(), name)?;
+        remove_file(path).map_err(Error::from)
+    }
+}
+
+/* ----------------------------------------------------------------------------- Private Helpers */
+
+/// List the stems of every `.arrow` file in `directory` in alphabetical order.
+pub(crate) fn list(directory: &Path) -> Result<Vec<String>, Error> {
+    if !directory.exists() {
+        return Ok(Vec::new());
+    }
+    let mut names: Vec<String> = read_dir(directory)?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "arrow")
+        })
+        .filter_map(|path| Some(path.file_stem()?.to_str()?.to_string()))
+        .collect();
+    names.sort_unstable();
+    Ok(names)
+}
+
+/// Read-only handle on a table file.
+pub(crate) struct Table(pub(crate) PathBuf);
+
+/* ----------------------------------------------------------------------- Trait Implementations */
+
+impl Reader for Derived {
+    fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Reader for Table {
+    fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+/* ---------------------------------------------------------------------------------- Unit Tests */
+
+#[cfg(test)]
+mod tests {
+    use std::fs::remove_dir_all;
+
+    use super::*;
+
+    #[test]
+    fn derived_lifecycle() {
+        const PATH: &str = "test-derived";
+        let db = Database::new(PATH).unwrap();
+        let provenance = Provenance::new("scale", &["intensities"]).parameter("factor", 2.0);
+        let mut table = db.create_derived("scaled", &provenance).unwrap();
+        table.push(0, &[0, 1], vec![2.0, 4.0]);
+        table.commit().unwrap();
+        assert!(db.create_derived("scaled", &provenance).is_err()); // Already exists
+        assert!(db.create_derived("../escape", &provenance).is_err());
+
+        assert_eq!(db.derived().unwrap(), vec!["scaled"]);
+        assert_eq!(db.provenance("scaled").unwrap(), provenance);
+        let spectra = db.read_derived("scaled").unwrap();
+        assert_eq!(spectra[&0].get(&1), Some(&4.0));
+
+        db.delete_derived("scaled").unwrap();
+        assert!(db.derived().unwrap().is_empty());
+        assert!(db.intensities.path.exists());
+        remove_dir_all(PATH).unwrap();
+    }
+}
@@ -0,0 +1,90 @@
+/*
+Project: Optic
+GitHub: https://github.com/MillieFD/optic
+
+BSD 3-Clause License, Copyright (c) 2026, Amelia Fraser-Dale
+
+Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the conditions of the LICENSE are met.
+*/
+
+/* ----------------------------------------------------------------------------- Private Imports */
+
+use std::collections::{BTreeMap, HashMap};
+
+/* ------------------------------------------------------------------------------ Public Exports */
+
+/// How a derived table was produced. Stored in the table's schema metadata.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Provenance {
+    pub operation: String,
+    pub sources: Vec<String>,
+    pub parameters: BTreeMap<String, String>,
+    pub version: String,
+}
+
+impl Provenance {
+    /// Record an `operation` applied to the named `sources` by this version of the software.
+    pub fn new<S>(operation: S, sources: &[&str]) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            operation: operation.into(),
+            sources: sources.iter().map(|source| source.to_string()).collect(),
+            parameters: BTreeMap::new(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+
+    pub fn parameter<K, V>(mut self, key: K, value: V) -> Self
+    where
+        K: Into<String>,
+        V: ToString,
+    {
+        self.parameters.insert(key.into(), value.to_string());
+        self
+    }
+
+    pub(crate) fn metadata(&self) -> HashMap<String, String> {
+        let mut metadata: HashMap<String, String> = self
+            .parameters
+            .iter()
+            .map(|(key, value)| (format!("{PARAMETER}{key}"), value.clone()))
+            .collect();
+        metadata.insert(OPERATION.into(), self.operation.clone());
+        metadata.insert(SOURCES.into(), self.sources.join(","));
+        metadata.insert(VERSION.into(), self.version.clone());
+        metadata
+    }
+}
+
+/* ----------------------------------------------------------------------- Trait Implementations */
+
+impl From<&HashMap<String, String>> for Provenance {
+    fn from(metadata: &HashMap<String, String>) -> Self {
+        let get = |key: &str| metadata.get(key).cloned().unwrap_or_default();
+        Self {
+            operation: get(OPERATION),
+            sources: get(SOURCES)
+                .split(',')
+                .filter(|source| !source.is_empty())
+                .map(str::to_string)
+                .collect(),
+            parameters: metadata
+                .iter()
+                .filter_map(|(key, value)| {
+                    Some((key.strip_prefix(PARAMETER)?.into(), value.clone()))
+                })
+                .collect(),
+            version: get(VERSION),
+        }
+    }
+}
+
+/* ----------------------------------------------------------------------------- Private Helpers */
+
+const OPERATION: &str = "operation";
+const SOURCES: &str = "sources";
+const VERSION: &str = "version";
+const PARAMETER: &str = "parameter.";
@@ -0,0 +1,271 @@
+/*
+Project: Optic
+GitHub: https://github.com/MillieFD/optic
+
+BSD 3-Clause License, Copyright (c) 2026, Amelia Fraser-Dale
+
+Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the conditions of the LICENSE are met.
+*/
+
+/* ----------------------------------------------------------------------------- Private Imports */
+
+use std::collections::{BTreeMap, HashMap};
+
+use uom::si::length::{micrometer, nanometer};
+
+use crate::intensities::Spectrum;
+use crate::quality::{WINDOW, median, noise, running_median, spikes};
+use crate::{Database, Error, Grouping, Provenance, Source};
+
+/* ------------------------------------------------------------------------------ Public Exports */
+
+/// How cosmic-ray spikes are detected and replaced.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Despike {
+    /// Samples exceeding a 5-sample running median by more than `sigma` times the noise of their
+    /// own spectrum are replaced by the running median.
+    Median { sigma: f64 },
+    /// Samples exceeding the median of the replicates at the same wavelength by more than `sigma`
+    /// times the noise of their own spectrum are replaced by that median. In a pair the median
+    /// would still hold half of any spike, so each spectrum is compared with the other instead.
+    /// Measurements without a replicate are left unchanged.
+    Replicates { grouping: Grouping, sigma: f64 },
+}
+
+/// Spectra after despiking, keyed by measurement ID.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Despiked {
+    pub spectra: HashMap<u32, Spectrum>,
+    /// Original value of every replaced sample.
+    pub replaced: HashMap<u32, Spectrum>,
+}
+
+impl Database {
+    /// Detect and replace spikes in the spectra of `measurements` from `source`. Nothing is
+    /// written to disk.
+    pub fn despiked(
+        &self,
+        source: Source,
+        measurements: &[u32],
+        method: &Despike,
+    ) -> Result<Despiked, Error> {
+        let sigma = match method {
+            Despike::Median { sigma } | Despike::Replicates { sigma, .. } => *sigma,
+        };
+        if sigma.is_nan() || sigma <= 0.0 {
+            return Err(Error::InvalidParameter("Sigma must be positive".into()));
+        }
+        let lookup = self.wavelengths.lookup()?;
+        let order = |spectrum: &Spectrum| -> Result<Vec<u32>, Error> {
+            let mut ids = spectrum
+                .keys()
+                .map(|id| match lookup.get(id) {
+                    Some(wl) => Ok((wl.get::<nanometer>(), *id)),
+                    None => Err(Error::MissingWavelength(*id)),
+                })
+                .collect::<Result<Vec<(f64, u32)>, Error>>()?;
+            ids.sort_unstable_by(|a, b| a.0.total_cmp(&b.0));
+            Ok(ids.into_iter().map(|(_, id)| id).collect())
+        };
+        let mut spectra = self.spectra(source, measurements)?;
+        let mut mask: HashMap<u32, Spectrum> = HashMap::new();
+        match method {
+            Despike::Median { .. } => {
+                for (id, spectrum) in spectra.iter_mut() {
+                    let wavelengths = order(spectrum)?;
+                    let values: Vec<f64> = wavelengths.iter().map(|w| spectrum[w]).collect();
+                    let smooth = running_median(&values, WINDOW);
+                    for index in spikes(&values, WINDOW, sigma) {
+                        let wavelength = wavelengths[index];
+                        let original = spectrum.insert(wavelength, smooth[index]);
+                        mask.entry(*id)
+                            .or_default()
+                            .insert(wavelength, original.unwrap());
+                    }
+                }
+            }
+            Despike::Replicates { grouping, .. } => {
+                for group in self.replicates(measurements, grouping)? {
+                    let members: Vec<u32> = group
+                        .into_iter()
+                        .filter(|id| spectra.contains_key(id))
+                        .collect();
+                    if members.len() < 2 {
+                        continue;
+                    }
+                    let mut samples: BTreeMap<u32, Vec<f64>> = BTreeMap::new();
+                    for id in &members {
+                        for (wavelength, value) in &spectra[id] {
+                            samples.entry(*wavelength).or_default().push(*value);
+                        }
+                    }
+                    let medians: Spectrum = samples
+                        .into_iter()
+                        .map(|(wavelength, values)| (wavelength, median(values)))
+                        .collect();
+                    let references: Vec<Spectrum> = match members[..] {
+                        [a, b] => vec![spectra[&b].clone(), spectra[&a].clone()],
+                        _ => vec![medians; members.len()],
+                    };
+                    for (id, reference) in members.iter().zip(&references) {
+                        let spectrum = spectra.get_mut(id).unwrap();
+                        let wavelengths = order(spectrum)?;
+                        let values: Vec<f64> = wavelengths.iter().map(|w| spectrum[w]).collect();
+                        let floor = 1E-9 * values.iter().fold(0.0, |max: f64, v| max.max(v.abs()));
+                        let threshold = sigma * noise(&values).max(floor);
+                        for (wavelength, value) in spectrum.iter_mut() {
+                            let Some(&expected) = reference.get(wavelength) else {
+                                continue;
+                            };
+                            if *value - expected > threshold {
+                                mask.entry(*id).or_default().insert(*wavelength, *value);
+                                *value = expected;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Ok(Despiked {
+            spectra,
+            replaced: mask,
+        })
+    }
+
+    /// Despike as [`Database::despiked`] and write the result to the derived table called
+    /// `name`. The original value of every replaced sample is written to the derived table
+    /// `{name}_mask`, so its `(measurement, wavelength)` keys list exactly the replaced samples.
+    /// Returns the IDs of the processed measurements.
+    pub fn despike(
+        &self,
+        name: &str,
+        source: Source,
+        measurements: &[u32],
+        method: &Despike,
+    ) -> Result<Vec<u32>, Error> {
+        let Despiked { spectra, replaced } = self.despiked(source, measurements, method)?;
+        let provenance = Provenance::new("despike", &[source.name(), "wavelengths"]);
+        let provenance = match method {
+            Despike::Median { sigma } => provenance
+                .parameter("method", "median")
+                .parameter("window", WINDOW)
+                .parameter("sigma", sigma),
+            Despike::Replicates { grouping, sigma } => {
+                let provenance = provenance
+                    .parameter("method", "replicates")
+                    .parameter("sigma", sigma);
+                match grouping {
+                    Grouping::Position(tolerance) => provenance
+                        .parameter("grouping", "position")
+                        .parameter("tolerance_um", tolerance.get::<micrometer>()),
+                    Grouping::Key(_) => provenance.parameter("grouping", "key"),
+                }
+            }
+        };
+        let mut ids: Vec<u32> = spectra.keys().copied().collect();
+        ids.sort_unstable();
+        let tables = [
+            (name.to_string(), provenance.clone()),
+            (format!("{name}_mask"), provenance),
+        ];
+        let mut tables = self.create_derived_all(&tables)?;
+        for id in &ids {
+            let (wavelengths, values): (Vec<u32>, Vec<f64>) = spectra[id].iter().unzip();
+            tables[0].push(*id, &wavelengths, values);
+            if let Some(replaced) = replaced.get(id) {
+                let (wavelengths, values): (Vec<u32>, Vec<f64>) = replaced.iter().unzip();
+                tables[1].push(*id, &wavelengths, values);
+            }
+        }
+        tables.iter_mut().try_for_each(|table| table.commit())?;
+        Ok(ids)
+    }
+}
+
+/* ---------------------------------------------------------------------------------- Unit Tests */
+
+#[cfg(all(
+    test,
+    feature = "x",
+    feature = "y",
+    not(feature = "z"),
+    not(feature = "a")
+))]
+mod tests {
+    use std::fs::remove_dir_all;
+
+    use uom::si::f64::{Length, Time};
+    use uom::si::time::millisecond;
+
+    use super::*;
+    use crate::Kind;
+
+    #[test]
+    fn remove_spikes() {
+        const PATH: &str = "test-despike";
+        let mut db = Database::new(PATH).unwrap();
+        let nm: Vec<f64> = (0..30).map(|i| 500.0 + i as f64).collect();
+        let wavelengths = db.wavelengths.push(nm).unwrap();
+        db.wavelengths.commit().unwrap();
+        let noise = |i: usize, seed: f64| ((i as f64 * 12.9898 + seed).sin() * 43758.5453).fract();
+        let zero = Length::new::<micrometer>(0.0);
+        let integration = Time::new::<millisecond>(10.0);
+        let ids: Vec<u32> = (0..3)
+            .map(|replicate| {
+                let mut values: Vec<f64> = (0..30)
+                    .map(|i| 100.0 + noise(i, replicate as f64))
+                    .collect();
+                if replicate == 1 {
+                    values[12] += 500.0; // Cosmic ray
+                }
+                let id = db
+                    .measurements
+                    .push(Kind::Sample, zero, zero, integration, &[])
+                    .unwrap();
+                db.intensities.push(id, &wavelengths, values);
+                id
+            })
+            .collect();
+        db.measurements.commit().unwrap();
+        db.intensities.commit().unwrap();
+
+        let median = Despike::Median { sigma: 8.0 };
+        let Despiked { spectra, replaced } =
+            db.despiked(Source::Intensities, &ids, &median).unwrap();
+        assert_eq!(replaced.keys().collect::<Vec<_>>(), vec![&ids[1]]);
+        let wavelength = replaced[&ids[1]].keys().next();
+        assert_eq!(wavelength, Some(&wavelengths[12]));
+        assert!((spectra[&ids[1]][&wavelengths[12]] - 100.0).abs() < 1.0);
+
+        let replicates = Despike::Replicates {
+            grouping: Grouping::Position(Length::new::<micrometer>(1.0)),
+            sigma: 8.0,
+        };
+        let processed = db
+            .despike("clean", Source::Intensities, &ids, &replicates)
+            .unwrap();
+        assert_eq!(processed, ids);
+        let mask = db.read_derived("clean_mask").unwrap();
+        assert_eq!(mask.len(), 1);
+        assert!(mask[&ids[1]][&wavelengths[12]] > 500.0);
+        let clean = db.read_derived("clean").unwrap();
+        assert!((clean[&ids[1]][&wavelengths[12]] - 100.0).abs() < 1.0);
+        assert_eq!(clean[&ids[0]].len(), 30);
+        db.delete_derived("clean").unwrap(); // Only the mask remains
+        assert!(
+            db.despike("clean", Source::Intensities, &ids, &replicates)
+                .is_err()
+        );
+        assert_eq!(db.derived().unwrap(), vec!["clean_mask"]);
+
+        let pair = db
+            .despiked(Source::Intensities, &ids[..2], &replicates)
+            .unwrap();
+        assert_eq!(pair.replaced.len(), 1);
+        assert!(pair.replaced[&ids[1]][&wavelengths[12]] > 500.0);
+        let value = pair.spectra[&ids[1]][&wavelengths[12]];
+        assert!((value - 100.0).abs() < 1.0); // Not the mean of 100 and 600
+        remove_dir_all(PATH).unwrap();
+    }
+}
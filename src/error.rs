@@ -20,6 +20,11 @@ use arrow::error::ArrowError;
 pub enum Error {
     ArrowError(ArrowError),
     IOError(std::io::Error),
+    ParseError(String),
+    MissingReference(u32),
+    MissingWavelength(u32),
+    InvalidName(String),
+    InvalidParameter(String),
 }
 
 /* ----------------------------------------------------------------------- Trait Implementations */
@@ -29,6 +34,11 @@ impl Display for Error {
         match self {
             Error::ArrowError(e) => write!(f, "Arrow Error: {}", e),
             Error::IOError(e) => write!(f, "IO Error: {}", e),
+            Error::ParseError(e) => write!(f, "Parse Error: {}", e),
+            Error::MissingReference(id) => write!(f, "Missing Reference: measurement {}", id),
+            Error::MissingWavelength(id) => write!(f, "Missing Wavelength: ID {}", id),
+            Error::InvalidName(name) => write!(f, "Invalid Name: '{}'", name),
+            Error::InvalidParameter(e) => write!(f, "Invalid Parameter: {}", e),
         }
     }
 }
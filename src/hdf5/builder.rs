@@ -0,0 +1,303 @@
+/*
+Project: Optic
+GitHub: https://github.com/MillieFD/optic
+
+BSD 3-Clause License, Copyright (c) 2026, Amelia Fraser-Dale
+
+Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the conditions of the LICENSE are met.
+*/
+
+/* ----------------------------------------------------------------------------- Private Imports */
+
+use super::checksum::lookup3;
+use super::{Message, UNDEFINED, Values};
+use crate::deflate::compress;
+
+/* --------------------------------------------------------------------------------- Constants */
+
+const SUPERBLOCK: usize = 48;
+const CHUNK_ELEMENTS: u64 = 65_536;
+const BTREE_K: usize = 32; // Default chunk B-tree node width
+
+/* ------------------------------------------------------------------------------ Public Exports */
+
+/// Assembles an HDF5 file in memory. Objects are appended as they are created, so children must be
+/// created before the groups that link to them.
+pub(super) struct Builder {
+    buffer: Vec<u8>,
+}
+
+impl Builder {
+    pub(super) fn new() -> Self {
+        Self {
+            buffer: vec![0; SUPERBLOCK],
+        }
+    }
+
+    fn allocate(&mut self, bytes: &[u8]) -> u64 {
+        let address = self.buffer.len() as u64;
+        self.buffer.extend_from_slice(bytes);
+        address
+    }
+
+    /// Write a version 2 object header containing `messages`.
+    fn header(&mut self, messages: &[(Message, Vec<u8>)]) -> u64 {
+        let mut body = Vec::new();
+        for (message, data) in messages {
+            body.push(*message as u8);
+            body.extend((data.len() as u16).to_le_bytes());
+            body.push(matches!(message, Message::Datatype) as u8); // Constant flag
+            body.extend(data);
+        }
+        let mut header = b"OHDR".to_vec();
+        header.push(2); // Version
+        header.push(0x02); // Four byte chunk size
+        header.extend((body.len() as u32).to_le_bytes());
+        header.extend(body);
+        header.extend(lookup3(&header).to_le_bytes());
+        self.allocate(&header)
+    }
+
+    /// Create a group containing hard `links` to previously created objects.
+    pub(super) fn group(&mut self, links: &[(&str, u64)], attributes: &[(&str, &str)]) -> u64 {
+        let mut info = vec![0, 0]; // Link info version and flags
+        info.extend(UNDEFINED.to_le_bytes()); // Compact storage has no fractal heap
+        info.extend(UNDEFINED.to_le_bytes()); // or name index
+        let mut messages = vec![(Message::LinkInfo, info), (Message::GroupInfo, vec![0, 0])];
+        for (name, address) in links {
+            let mut link = vec![1, 0, name.len() as u8];
+            link.extend(name.as_bytes());
+            link.extend(address.to_le_bytes());
+            messages.push((Message::Link, link));
+        }
+        messages.extend(attributes.iter().map(attribute));
+        self.header(&messages)
+    }
+
+    /// Create a dataset with the given row-major `dims`. Non-empty datasets are chunked and
+    /// compressed with the `shuffle` and `deflate` filters.
+    pub(super) fn dataset(
+        &mut self,
+        values: &Values,
+        dims: &[u64],
+        attributes: &[(&str, &str)],
+    ) -> u64 {
+        let mut messages = vec![
+            (Message::Dataspace, dataspace(dims)),
+            (Message::Datatype, values.datatype()),
+        ];
+        let mut layout = vec![3]; // Version
+        match dims.contains(&0) {
+            true => {
+                layout.push(1); // Contiguous
+                layout.extend(UNDEFINED.to_le_bytes());
+                layout.extend(0u64.to_le_bytes());
+            }
+            false => {
+                let chunk = chunk(dims);
+                let btree = self.chunks(values, dims, &chunk);
+                layout.push(2); // Chunked
+                layout.push(dims.len() as u8 + 1);
+                layout.extend(btree.to_le_bytes());
+                chunk
+                    .iter()
+                    .for_each(|&n| layout.extend((n as u32).to_le_bytes()));
+                layout.extend((values.size() as u32).to_le_bytes());
+                messages.push((Message::Filters, filters(values.size())));
+            }
+        }
+        messages.push((Message::Layout, layout));
+        messages.extend(attributes.iter().map(attribute));
+        self.header(&messages)
+    }
+
+    /// Write every chunk of `values` and return the address of the chunk index B-tree.
+    fn chunks(&mut self, values: &Values, dims: &[u64], chunk: &[u64]) -> u64 {
+        let size = values.size();
+        let bytes = values.bytes();
+        let grid: Vec<u64> = dims
+            .iter()
+            .zip(chunk)
+            .map(|(d, c)| d.div_ceil(*c))
+            .collect();
+        let mut entries = Vec::new();
+        for index in odometer(&grid) {
+            let origin: Vec<u64> = index.iter().zip(chunk).map(|(i, c)| i * c).collect();
+            let mut data = Vec::with_capacity(chunk.iter().product::<u64>() as usize * size);
+            for offset in odometer(chunk) {
+                let position: Vec<u64> = origin.iter().zip(&offset).map(|(o, i)| o + i).collect();
+                match position.iter().zip(dims).all(|(p, d)| p < d) {
+                    true => {
+                        let element = flatten(&position, dims) as usize * size;
+                        data.extend_from_slice(&bytes[element..element + size]);
+                    }
+                    false => data.extend(std::iter::repeat_n(0, size)), // Edge chunk padding
+                }
+            }
+            let compressed = compress(&shuffle(&data, size));
+            let address = self.allocate(&compressed);
+            entries.push((key(compressed.len() as u32, &origin), address));
+        }
+        let last = entries
+            .last()
+            .map(|(key, _)| key.clone())
+            .unwrap_or_default();
+        let end: Vec<u64> = last.1.iter().zip(chunk).map(|(o, c)| o + c).collect();
+        self.btree(entries, key(0, &end), dims.len())
+    }
+
+    /// Build a version 1 B-tree over `entries` sorted by chunk offset.
+    fn btree(&mut self, mut entries: Vec<(Key, u64)>, end: Key, rank: usize) -> u64 {
+        let mut level = 0u8;
+        loop {
+            let nodes: Vec<_> = entries.chunks(2 * BTREE_K).map(<[_]>::to_vec).collect();
+            let mut parents = Vec::with_capacity(nodes.len());
+            for (i, node) in nodes.iter().enumerate() {
+                let right = nodes
+                    .get(i + 1)
+                    .map_or(end.clone(), |next| next[0].0.clone());
+                let address = self.node(level, node, &right, rank);
+                parents.push((node[0].0.clone(), address));
+            }
+            if parents.len() == 1 {
+                break parents[0].1;
+            }
+            entries = parents;
+            level += 1;
+        }
+    }
+
+    fn node(&mut self, level: u8, entries: &[(Key, u64)], right: &Key, rank: usize) -> u64 {
+        let key_size = 8 + 8 * (rank + 1);
+        let capacity = 24 + (2 * BTREE_K + 1) * key_size + 2 * BTREE_K * 8;
+        let mut node = b"TREE".to_vec();
+        node.push(1); // Raw data chunk node
+        node.push(level);
+        node.extend((entries.len() as u16).to_le_bytes());
+        node.extend(UNDEFINED.to_le_bytes()); // Left sibling
+        node.extend(UNDEFINED.to_le_bytes()); // Right sibling
+        for (key, child) in entries {
+            key.encode(&mut node);
+            node.extend(child.to_le_bytes());
+        }
+        right.encode(&mut node);
+        node.resize(capacity, 0); // Nodes are always allocated at full capacity
+        self.allocate(&node)
+    }
+
+    /// Write the superblock and return the finished file.
+    pub(super) fn finish(mut self, root: u64) -> Vec<u8> {
+        let eof = self.buffer.len() as u64;
+        let mut superblock = b"\x89HDF\r\n\x1A\n".to_vec();
+        superblock.extend([2, 8, 8, 0]); // Version, offset size, length size, flags
+        superblock.extend(0u64.to_le_bytes()); // Base address
+        superblock.extend(UNDEFINED.to_le_bytes()); // Superblock extension
+        superblock.extend(eof.to_le_bytes());
+        superblock.extend(root.to_le_bytes());
+        superblock.extend(lookup3(&superblock).to_le_bytes());
+        self.buffer[..SUPERBLOCK].copy_from_slice(&superblock);
+        self.buffer
+    }
+}
+
+/* ----------------------------------------------------------------------------- Private Helpers */
+
+/// Chunk B-tree key: filtered chunk size and chunk offset in elements.
+#[derive(Clone, Default)]
+struct Key(u32, Vec<u64>);
+
+impl Key {
+    fn encode(&self, buffer: &mut Vec<u8>) {
+        buffer.extend(self.0.to_le_bytes());
+        buffer.extend(0u32.to_le_bytes()); // Filter mask
+        self.1.iter().for_each(|o| buffer.extend(o.to_le_bytes()));
+        buffer.extend(0u64.to_le_bytes()); // Datatype dimension
+    }
+}
+
+fn key(size: u32, offset: &[u64]) -> Key {
+    Key(size, offset.to_vec())
+}
+
+/// Choose chunk dimensions of roughly [`CHUNK_ELEMENTS`] spanning complete trailing dimensions.
+fn chunk(dims: &[u64]) -> Vec<u64> {
+    let mut remaining = CHUNK_ELEMENTS;
+    let mut chunk: Vec<u64> = dims
+        .iter()
+        .rev()
+        .map(|&d| {
+            let n = d.min(remaining).max(1);
+            remaining = (remaining / n).max(1);
+            n
+        })
+        .collect();
+    chunk.reverse();
+    chunk
+}
+
+/// Iterate every index within `dims` in row-major order.
+pub(super) fn odometer(dims: &[u64]) -> impl Iterator<Item = Vec<u64>> + '_ {
+    let total: u64 = dims.iter().product();
+    (0..total).map(move |mut n| {
+        let mut index = vec![0; dims.len()];
+        for (i, d) in dims.iter().enumerate().rev() {
+            index[i] = n % d;
+            n /= d;
+        }
+        index
+    })
+}
+
+pub(super) fn flatten(index: &[u64], dims: &[u64]) -> u64 {
+    index.iter().zip(dims).fold(0, |flat, (i, d)| flat * d + i)
+}
+
+pub(super) fn shuffle(data: &[u8], size: usize) -> Vec<u8> {
+    let n = data.len() / size;
+    let mut shuffled = vec![0; data.len()];
+    for (i, element) in data.chunks_exact(size).enumerate() {
+        element
+            .iter()
+            .enumerate()
+            .for_each(|(b, &byte)| shuffled[b * n + i] = byte);
+    }
+    shuffled
+}
+
+fn dataspace(dims: &[u64]) -> Vec<u8> {
+    let mut message = vec![2, dims.len() as u8, 0, 1]; // Version, rank, flags, simple
+    dims.iter().for_each(|d| message.extend(d.to_le_bytes()));
+    message
+}
+
+fn filters(size: usize) -> Vec<u8> {
+    let mut message = vec![2, 2]; // Version, filter count
+    for (id, value) in [(2u16, size as u32), (1, 6)] {
+        message.extend(id.to_le_bytes());
+        message.extend(0u16.to_le_bytes()); // Flags
+        message.extend(1u16.to_le_bytes()); // Client data count
+        message.extend(value.to_le_bytes()); // Element size or compression level
+    }
+    message
+}
+
+fn attribute((name, value): &(&str, &str)) -> (Message, Vec<u8>) {
+    let mut datatype = vec![0x13, 0x01, 0, 0]; // Null padded ASCII string
+    datatype.extend((value.len().max(1) as u32).to_le_bytes());
+    let dataspace = [2, 0, 0, 0]; // Scalar
+    let mut message = vec![3, 0]; // Version, flags
+    message.extend((name.len() as u16 + 1).to_le_bytes());
+    message.extend((datatype.len() as u16).to_le_bytes());
+    message.extend((dataspace.len() as u16).to_le_bytes());
+    message.push(0); // ASCII name
+    message.extend(name.as_bytes());
+    message.push(0);
+    message.extend(datatype);
+    message.extend(dataspace);
+    message.extend(value.as_bytes());
+    if value.is_empty() {
+        message.push(0);
+    }
+    (Message::Attribute, message)
+}
@@ -0,0 +1,61 @@
+/*
+Project: Optic
+GitHub: https://github.com/MillieFD/optic
+
+BSD 3-Clause License, Copyright (c) 2026, Amelia Fraser-Dale
+
+Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the conditions of the LICENSE are met.
+*/
+
+/* ------------------------------------------------------------------------------ Public Exports */
+
+/// Jenkins `lookup3` hash used by HDF5 to checksum superblocks and object headers.
+pub(super) fn lookup3(bytes: &[u8]) -> u32 {
+    let init = 0xDEADBEEF_u32.wrapping_add(bytes.len() as u32);
+    let (mut a, mut b, mut c) = (init, init, init);
+    if bytes.is_empty() {
+        return c;
+    }
+    let mut blocks = bytes.chunks(12).peekable();
+    while let Some(block) = blocks.next() {
+        let mut padded = [0u8; 12];
+        padded[..block.len()].copy_from_slice(block);
+        let word = |i: usize| u32::from_le_bytes(padded[i..i + 4].try_into().unwrap());
+        a = a.wrapping_add(word(0));
+        b = b.wrapping_add(word(4));
+        c = c.wrapping_add(word(8));
+        match blocks.peek() {
+            Some(_) => mix(&mut a, &mut b, &mut c),
+            None => finalise(&mut a, &mut b, &mut c),
+        }
+    }
+    c
+}
+
+/* ----------------------------------------------------------------------------- Private Helpers */
+
+fn mix(a: &mut u32, b: &mut u32, c: &mut u32) {
+    *a = a.wrapping_sub(*c) ^ c.rotate_left(4);
+    *c = c.wrapping_add(*b);
+    *b = b.wrapping_sub(*a) ^ a.rotate_left(6);
+    *a = a.wrapping_add(*c);
+    *c = c.wrapping_sub(*b) ^ b.rotate_left(8);
+    *b = b.wrapping_add(*a);
+    *a = a.wrapping_sub(*c) ^ c.rotate_left(16);
+    *c = c.wrapping_add(*b);
+    *b = b.wrapping_sub(*a) ^ a.rotate_left(19);
+    *a = a.wrapping_add(*c);
+    *c = c.wrapping_sub(*b) ^ b.rotate_left(4);
+    *b = b.wrapping_add(*a);
+}
+
+fn finalise(a: &mut u32, b: &mut u32, c: &mut u32) {
+    *c = (*c ^ *b).wrapping_sub(b.rotate_left(14));
+    *a = (*a ^ *c).wrapping_sub(c.rotate_left(11));
+    *b = (*b ^ *a).wrapping_sub(a.rotate_left(25));
+    *c = (*c ^ *b).wrapping_sub(b.rotate_left(16));
+    *a = (*a ^ *c).wrapping_sub(c.rotate_left(4));
+    *b = (*b ^ *a).wrapping_sub(a.rotate_left(14));
+    *c = (*c ^ *b).wrapping_sub(b.rotate_left(24));
+}
@@ -0,0 +1,720 @@
+/*
+Project: Optic
+GitHub: https://github.com/MillieFD/optic
+
+BSD 3-Clause License, Copyright (c) 2026, Amelia Fraser-Dale
+
+Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the conditions of the LICENSE are met.
+*/
+
+/* ----------------------------------------------------------------------------- Private Modules */
+
+mod builder;
+mod checksum;
+mod parser;
+
+/* ----------------------------------------------------------------------------- Private Imports */
+
+use std::collections::HashMap;
+use std::fs::{read, write};
+use std::path::Path;
+use std::sync::Arc;
+
+use arrow::array::{
+    Array,
+    ArrayRef,
+    AsArray,
+    BooleanArray,
+    DurationMicrosecondArray,
+    Float64Array,
+    Int64Array,
+    RecordBatch,
+    StringArray,
+    TimestampMicrosecondArray,
+    UInt8Array,
+    UInt32Array,
+    make_array,
+};
+use arrow::datatypes::TimeUnit::Microsecond;
+use arrow::datatypes::{
+    DataType,
+    DurationMicrosecondType,
+    Field,
+    Float64Type,
+    Int64Type,
+    Schema,
+    TimestampMicrosecondType,
+    UInt8Type,
+    UInt32Type,
+};
+
+use self::builder::Builder;
+use self::parser::Parser;
+use crate::attributes::Attributes;
+use crate::instruments::Instruments;
+use crate::intensities::Intensities;
+use crate::measurements::{Measurements, UNIT};
+use crate::wavelengths::Wavelengths;
+use crate::{Database, Error, Extra, Reader, Writer};
+
+/* --------------------------------------------------------------------------------- Constants */
+
+const UNDEFINED: u64 = u64::MAX;
+const TABLES: [&str; 6] = [
+    "wavelengths",
+    "measurements",
+    "intensities",
+    "samples",
+    "sessions",
+    "instruments",
+];
+/// Group holding a `1` for present and `0` for null values of every nullable column, except
+/// integer IDs which use [`MISSING`], at `valid/{table}/{column}`.
+const VALID: &str = "valid";
+/// Fill value of missing entries in nullable integer columns, such as measurements without a
+/// sample.
+const MISSING: u32 = u32::MAX;
+
+/* ------------------------------------------------------------------------------ Public Exports */
+
+impl Database {
+    /// Export every committed table, including the sample and session attributes and the
+    /// instrument registry, to a single HDF5 file for archival.
+    ///
+    /// Each table becomes a group of equal length column datasets with a `units` attribute where
+    /// applicable. Setting `cube` also writes a dense `measurement × wavelength` intensity dataset
+    /// to the `cube` group, with wavelengths ordered by nm and `NaN` marking missing values.
+    /// User-defined measurement columns are exported with their unit. Their names may not contain
+    /// `/` or be longer than 255 bytes.
+    pub fn export_hdf5<P>(&self, path: &P, cube: bool) -> Result<(), Error>
+    where
+        P: AsRef<Path> + ?Sized,
+    {
+        if let Some(extra) = self
+            .measurements
+            .extras()
+            .iter()
+            .find(|extra| extra.name().contains('/') || extra.name().len() > u8::MAX as usize)
+        {
+            return Err(Error::InvalidName(extra.name().to_string()));
+        }
+        let mut builder = Builder::new();
+        let tables = [
+            (Wavelengths::schema(), self.wavelengths.batches()?),
+            (
+                self.measurements.table_schema(),
+                self.measurements.batches()?,
+            ),
+            (Intensities::schema(), self.intensities.batches()?),
+            (Attributes::schema(), self.samples.batches()?),
+            (Attributes::schema(), self.sessions.batches()?),
+            (Instruments::schema(), self.instruments.batches()?),
+        ];
+        let mut links = Vec::new();
+        let mut masks = Vec::new();
+        for (name, (schema, batches)) in TABLES.iter().zip(&tables) {
+            let mut columns = Vec::new();
+            let mut valid = Vec::new();
+            for (index, field) in schema.fields().iter().enumerate() {
+                let values = Values::concat(field.data_type(), batches, index)?;
+                let attributes: Vec<_> = units(name, field)
+                    .map(|u| ("units", u))
+                    .into_iter()
+                    .collect();
+                let dims = [values.len() as u64];
+                columns.push((
+                    field.name().as_str(),
+                    builder.dataset(&values, &dims, &attributes),
+                ));
+                if masked(field) {
+                    let mask = Values::U8(
+                        batches
+                            .iter()
+                            .map(|batch| batch.column(index))
+                            .flat_map(|c| (0..c.len()).map(|row| c.is_valid(row) as u8))
+                            .collect(),
+                    );
+                    valid.push((field.name().as_str(), builder.dataset(&mask, &dims, &[])));
+                }
+            }
+            let group = builder.group(&columns, &[("NX_class", "NXcollection")]);
+            links.push((*name, group));
+            masks.push((*name, builder.group(&valid, &[])));
+        }
+        links.push((VALID, builder.group(&masks, &[])));
+        if cube {
+            let [(_, wavelengths), (_, measurements), (_, intensities), ..] = &tables;
+            let cube = self.cube(&mut builder, wavelengths, measurements, intensities)?;
+            links.push(("cube", cube));
+        }
+        let root = builder.group(&links, &[("creator", CREATOR)]);
+        write(path, builder.finish(root)).map_err(Error::from)
+    }
+
+    /// Create a new [`Database`] at `destination` from an HDF5 file written by
+    /// [`Database::export_hdf5`]. The optional `cube` group is derived data and is not imported.
+    /// Tables missing from files written by earlier versions are left empty. Measurements pushed
+    /// to the new database belong to a new session after every imported one.
+    pub fn import_hdf5<P, Q>(source: &P, destination: &Q) -> Result<Database, Error>
+    where
+        P: AsRef<Path> + ?Sized,
+        Q: AsRef<Path> + ?Sized,
+    {
+        let bytes = read(source)?;
+        let parser = Parser::new(&bytes)?;
+        let present = parser.members("")?;
+        let base = Measurements::schema();
+        let mut extras = Vec::new();
+        for name in parser.members(TABLES[1])? {
+            if base.field_with_name(&name).is_ok() {
+                continue;
+            }
+            let path = format!("{}/{name}", TABLES[1]);
+            let extra = match parser.values(&parser.open(&path)?)? {
+                Values::F64(_) => Extra::Float {
+                    unit: parser.attribute(&path, "units")?.unwrap_or_default(),
+                    name,
+                },
+                Values::I64(_) => Extra::Integer { name },
+                Values::Text(_) => Extra::Text { name },
+                Values::U8(_) => Extra::Boolean { name },
+                Values::U32(_) => return Err(Error::ParseError(format!("Invalid '{path}'"))),
+            };
+            extras.push(extra);
+        }
+        let mut db = Database::open_tables(destination, Some(&extras))?;
+        let batch = |name: &str, schema: Arc<Schema>| -> Result<RecordBatch, Error> {
+            let columns = schema
+                .fields()
+                .iter()
+                .map(|field| {
+                    let dataset = parser.open(&format!("{name}/{}", field.name()))?;
+                    let valid = match masked(field) {
+                        true => {
+                            let path = format!("{VALID}/{name}/{}", field.name());
+                            match parser.values(&parser.open(&path)?)? {
+                                Values::U8(mask) => Some(mask.iter().map(|&v| v != 0).collect()),
+                                _ => return Err(Error::ParseError(format!("Invalid '{path}'"))),
+                            }
+                        }
+                        false => None,
+                    };
+                    parser.values(&dataset)?.array(field, valid)
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            RecordBatch::try_new(schema, columns).map_err(Error::from)
+        };
+        db.wavelengths
+            .write(&batch(TABLES[0], Wavelengths::schema())?)?;
+        db.measurements
+            .write(&batch(TABLES[1], db.measurements.table_schema())?)?;
+        db.intensities
+            .write(&batch(TABLES[2], Intensities::schema())?)?;
+        for (name, table) in TABLES[3..].iter().zip([&mut db.samples, &mut db.sessions]) {
+            if present.iter().any(|p| p == name) {
+                table.write(&batch(name, Attributes::schema())?)?;
+            }
+        }
+        if present.iter().any(|p| p == TABLES[5]) {
+            db.instruments
+                .write(&batch(TABLES[5], Instruments::schema())?)?;
+        }
+        db.start_session()?; // Imported sessions belong to the original database
+        Ok(db)
+    }
+
+    fn cube(
+        &self,
+        builder: &mut Builder,
+        wavelengths: &[RecordBatch],
+        measurements: &[RecordBatch],
+        intensities: &[RecordBatch],
+    ) -> Result<u64, Error> {
+        let mut axis: Vec<(f64, u32)> = wavelengths
+            .iter()
+            .flat_map(|batch| {
+                let ids = batch
+                    .column(0)
+                    .as_primitive::<UInt32Type>()
+                    .values()
+                    .to_vec();
+                let nms = batch
+                    .column(1)
+                    .as_primitive::<Float64Type>()
+                    .values()
+                    .to_vec();
+                nms.into_iter().zip(ids)
+            })
+            .collect();
+        axis.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let mut rows: Vec<u32> = measurements
+            .iter()
+            .flat_map(|batch| {
+                batch
+                    .column(0)
+                    .as_primitive::<UInt32Type>()
+                    .values()
+                    .to_vec()
+            })
+            .collect();
+        rows.sort_unstable();
+        let columns: HashMap<u32, usize> = axis
+            .iter()
+            .enumerate()
+            .map(|(i, (_, id))| (*id, i))
+            .collect();
+        let index: HashMap<u32, usize> = rows.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+        let mut data = vec![f64::NAN; rows.len() * axis.len()];
+        for batch in intensities {
+            let m = batch.column(0).as_primitive::<UInt32Type>().values();
+            let w = batch.column(1).as_primitive::<UInt32Type>().values();
+            let i = batch.column(2).as_primitive::<Float64Type>().values();
+            for ((m, w), i) in m.iter().zip(w.iter()).zip(i.iter()) {
+                if let (Some(row), Some(column)) = (index.get(m), columns.get(w)) {
+                    data[row * axis.len() + column] = *i;
+                }
+            }
+        }
+        let dims = [rows.len() as u64, axis.len() as u64];
+        let (nms, ids): (Vec<f64>, Vec<u32>) = axis.into_iter().unzip();
+        let links = [
+            (
+                "data",
+                builder.dataset(&Values::F64(data), &dims, &[("units", "counts")]),
+            ),
+            (
+                "measurement",
+                builder.dataset(&Values::U32(rows), &dims[..1], &[]),
+            ),
+            (
+                "wavelength",
+                builder.dataset(&Values::U32(ids), &dims[1..], &[]),
+            ),
+            (
+                "nm",
+                builder.dataset(&Values::F64(nms), &dims[1..], &[("units", "nm")]),
+            ),
+        ];
+        let attributes = [("NX_class", "NXdata"), ("signal", "data")];
+        Ok(builder.group(&links, &attributes))
+    }
+}
+
+/* ----------------------------------------------------------------------------- Private Helpers */
+
+const CREATOR: &str = concat!("wray ", env!("CARGO_PKG_VERSION"));
+
+/// Object header message types.
+#[derive(Copy, Clone)]
+enum Message {
+    Dataspace = 0x01,
+    LinkInfo = 0x02,
+    Datatype = 0x03,
+    Link = 0x06,
+    Layout = 0x08,
+    GroupInfo = 0x0A,
+    Filters = 0x0B,
+    Attribute = 0x0C,
+}
+
+/// Column values in one of the element types used by the database schemas.
+#[derive(Debug, PartialEq)]
+enum Values {
+    U8(Vec<u8>),
+    U32(Vec<u32>),
+    I64(Vec<i64>),
+    F64(Vec<f64>),
+    /// Fixed length, null padded UTF-8 strings as wide as the longest one.
+    Text(Vec<String>),
+}
+
+impl Values {
+    fn concat(datatype: &DataType, batches: &[RecordBatch], index: usize) -> Result<Self, Error> {
+        let columns = batches.iter().map(|batch| batch.column(index));
+        match datatype {
+            DataType::UInt8 => Ok(Values::U8(
+                columns
+                    .flat_map(|c| c.as_primitive::<UInt8Type>().values().to_vec())
+                    .collect(),
+            )),
+            DataType::Boolean => Ok(Values::U8(
+                columns
+                    .flat_map(|c| {
+                        c.as_boolean()
+                            .values()
+                            .iter()
+                            .map(u8::from)
+                            .collect::<Vec<u8>>()
+                    })
+                    .collect(),
+            )),
+            DataType::Int64 => Ok(Values::I64(
+                columns
+                    .flat_map(|c| c.as_primitive::<Int64Type>().values().to_vec())
+                    .collect(),
+            )),
+            DataType::UInt32 => Ok(Values::U32(
+                columns
+                    .flat_map(|c| {
+                        c.as_primitive::<UInt32Type>()
+                            .iter()
+                            .map(|v| v.unwrap_or(MISSING))
+                            .collect::<Vec<u32>>()
+                    })
+                    .collect(),
+            )),
+            DataType::Float64 => Ok(Values::F64(
+                columns
+                    .flat_map(|c| c.as_primitive::<Float64Type>().values().to_vec())
+                    .collect(),
+            )),
+            DataType::Timestamp(Microsecond, _) => Ok(Values::I64(
+                columns
+                    .flat_map(|c| {
+                        c.as_primitive::<TimestampMicrosecondType>()
+                            .values()
+                            .to_vec()
+                    })
+                    .collect(),
+            )),
+            DataType::Duration(Microsecond) => Ok(Values::I64(
+                columns
+                    .flat_map(|c| {
+                        c.as_primitive::<DurationMicrosecondType>()
+                            .values()
+                            .to_vec()
+                    })
+                    .collect(),
+            )),
+            DataType::Utf8 => Ok(Values::Text(
+                columns
+                    .flat_map(|c| {
+                        c.as_string::<i32>()
+                            .iter()
+                            .map(|v| v.unwrap_or_default().to_string())
+                            .collect::<Vec<String>>()
+                    })
+                    .collect(),
+            )),
+            other => Err(Error::ParseError(format!("Cannot export {other} columns"))),
+        }
+    }
+
+    /// Convert to a column of `field`, with nulls where `valid` is `false`.
+    fn array(self, field: &Field, valid: Option<Vec<bool>>) -> Result<ArrayRef, Error> {
+        let datatype = field.data_type();
+        let array: ArrayRef = match (self, datatype) {
+            (Values::U8(v), DataType::UInt8) => Arc::new(UInt8Array::from(v)),
+            (Values::U8(v), DataType::Boolean) => Arc::new(BooleanArray::from(
+                v.into_iter().map(|v| v != 0).collect::<Vec<_>>(),
+            )),
+            (Values::I64(v), DataType::Int64) => Arc::new(Int64Array::from(v)),
+            (Values::U32(v), DataType::UInt32) if field.is_nullable() => {
+                let v = v.into_iter().map(|v| (v != MISSING).then_some(v));
+                Arc::new(UInt32Array::from_iter(v))
+            }
+            (Values::U32(v), DataType::UInt32) => Arc::new(UInt32Array::from(v)),
+            (Values::F64(v), DataType::Float64) => Arc::new(Float64Array::from(v)),
+            (Values::I64(v), DataType::Timestamp(Microsecond, _)) => {
+                Arc::new(TimestampMicrosecondArray::from(v))
+            }
+            (Values::I64(v), DataType::Duration(Microsecond)) => {
+                Arc::new(DurationMicrosecondArray::from(v))
+            }
+            (Values::Text(v), DataType::Utf8) => Arc::new(StringArray::from(v)),
+            _ => return Err(Error::ParseError(format!("Expected {datatype} column"))),
+        };
+        let Some(valid) = valid else {
+            return Ok(array);
+        };
+        let data = array.into_data().into_builder();
+        Ok(make_array(data.nulls(Some(valid.into())).build()?))
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Values::U8(v) => v.len(),
+            Values::U32(v) => v.len(),
+            Values::I64(v) => v.len(),
+            Values::F64(v) => v.len(),
+            Values::Text(v) => v.len(),
+        }
+    }
+
+    fn size(&self) -> usize {
+        match self {
+            Values::U8(_) => 1,
+            Values::U32(_) => 4,
+            Values::I64(_) | Values::F64(_) => 8,
+            Values::Text(v) => v.iter().map(String::len).max().unwrap_or_default().max(1),
+        }
+    }
+
+    fn bytes(&self) -> Vec<u8> {
+        match self {
+            Values::U8(v) => v.clone(),
+            Values::U32(v) => v.iter().flat_map(|n| n.to_le_bytes()).collect(),
+            Values::I64(v) => v.iter().flat_map(|n| n.to_le_bytes()).collect(),
+            Values::F64(v) => v.iter().flat_map(|n| n.to_le_bytes()).collect(),
+            Values::Text(v) => {
+                let width = self.size();
+                v.iter()
+                    .flat_map(|s| {
+                        let mut bytes = s.as_bytes().to_vec();
+                        bytes.resize(width, 0);
+                        bytes
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// Encode the datatype message for this element type.
+    fn datatype(&self) -> Vec<u8> {
+        match self {
+            Values::U8(_) => vec![0x10, 0x00, 0, 0, 1, 0, 0, 0, 0, 0, 8, 0],
+            Values::U32(_) => vec![0x10, 0x00, 0, 0, 4, 0, 0, 0, 0, 0, 32, 0],
+            Values::I64(_) => vec![0x10, 0x08, 0, 0, 8, 0, 0, 0, 0, 0, 64, 0],
+            Values::F64(_) => vec![
+                0x11, 0x20, 63, 0, 8, 0, 0, 0, 0, 0, 64, 0, 52, 11, 0, 52, 0xFF, 0x03, 0, 0,
+            ],
+            Values::Text(_) => {
+                let mut datatype = vec![0x13, 0x11, 0, 0]; // Null padded UTF-8 string
+                datatype.extend((self.size() as u32).to_le_bytes());
+                datatype
+            }
+        }
+    }
+}
+
+/// Whether `field` needs a mask in the [`VALID`] group.
+fn masked(field: &Field) -> bool {
+    field.is_nullable() && field.data_type() != &DataType::UInt32
+}
+
+/// Units of `field` in `table`. User-defined measurement columns carry their own unit.
+fn units<'a>(table: &str, field: &'a Field) -> Option<&'a str> {
+    match (table, field.name().as_str()) {
+        ("wavelengths", "nm") => Some("nm"),
+        ("measurements", "x" | "y" | "z" | "a") | ("instruments", "slit") => Some("um"),
+        ("measurements", "timestamp" | "integration") | ("instruments", "registered") => Some("us"),
+        ("intensities", "intensity") => Some("counts"),
+        ("instruments", "illumination" | "viewing") => Some("degree"),
+        _ => field.metadata().get(UNIT).map(String::as_str),
+    }
+}
+
+/* ---------------------------------------------------------------------------------- Unit Tests */
+
+#[cfg(all(
+    test,
+    feature = "x",
+    feature = "y",
+    not(feature = "z"),
+    not(feature = "a")
+))]
+mod tests {
+    use std::fs::remove_dir_all;
+
+    use uom::si::angle::degree;
+    use uom::si::f64::{Angle, Length, Time};
+    use uom::si::length::micrometer;
+    use uom::si::time::millisecond;
+
+    use super::*;
+    use crate::{Geometry, Instrument, Kind, Value};
+
+    #[test]
+    fn lookup3_reference() {
+        assert_eq!(checksum::lookup3(b""), 0xDEADBEEF);
+        assert_eq!(
+            checksum::lookup3(b"Four score and seven years ago"),
+            0x17770551
+        );
+    }
+
+    /// Build the file checked in as `fixtures/writer.h5`. After an intentional change to the
+    /// writer, regenerate the fixture and check it with `h5dump src/hdf5/fixtures/writer.h5`,
+    /// which must list the root attribute and the `counts`, `values`, `names` and `empty`
+    /// datasets with the values asserted in [`writer_fixture`].
+    fn fixture() -> Vec<u8> {
+        let mut builder = Builder::new();
+        let counts: Vec<u32> = (0..300 * 300).map(|i| i % 251).collect();
+        let counts = builder.dataset(&Values::U32(counts), &[300, 300], &[]); // Two chunks
+        let values = Values::F64(vec![0.5, -1.0, 2.25, 1E-9, 1E9, f64::MIN_POSITIVE]);
+        let values = builder.dataset(&values, &[2, 3], &[("units", "nm")]);
+        let names = Values::Text(vec!["dark".into(), "white".into(), "λ".into()]);
+        let names = builder.dataset(&names, &[3], &[]);
+        let empty = builder.dataset(&Values::I64(Vec::new()), &[0], &[]);
+        let links = [
+            ("counts", counts),
+            ("values", values),
+            ("names", names),
+            ("empty", empty),
+        ];
+        let root = builder.group(&links, &[("format", "wray")]);
+        builder.finish(root)
+    }
+
+    #[test]
+    fn writer_fixture() {
+        let bytes = include_bytes!("fixtures/writer.h5");
+        assert_eq!(fixture(), bytes); // The writer output is unchanged
+        let file = Parser::new(bytes).unwrap();
+        assert_eq!(
+            file.attribute("/", "format").unwrap().as_deref(),
+            Some("wray")
+        );
+        let counts = file.open("counts").unwrap();
+        assert_eq!(counts.dims, [300, 300]);
+        match file.values(&counts).unwrap() {
+            Values::U32(counts) => {
+                assert!(counts.iter().enumerate().all(|(i, c)| *c == i as u32 % 251))
+            }
+            other => panic!("Unexpected {other:?}"),
+        }
+        let values = file.open("values").unwrap();
+        assert_eq!(
+            file.attribute("values", "units").unwrap().as_deref(),
+            Some("nm")
+        );
+        assert_eq!(
+            file.values(&values).unwrap(),
+            Values::F64(vec![0.5, -1.0, 2.25, 1E-9, 1E9, f64::MIN_POSITIVE])
+        );
+        let names = file.values(&file.open("names").unwrap()).unwrap();
+        assert_eq!(
+            names,
+            Values::Text(vec!["dark".into(), "white".into(), "λ".into()])
+        );
+        let empty = file.open("empty").unwrap();
+        assert_eq!(empty.dims, [0]);
+    }
+
+    #[test]
+    fn export_and_import() {
+        const PATH: &str = "test-hdf5";
+        let mut db = Database::new(PATH).unwrap();
+        let wavelengths = db.wavelengths.push(vec![500.0, 400.0, 600.0]).unwrap();
+        for n in 0..3 {
+            let position = Length::new::<micrometer>(n as f64);
+            let integration = Time::new::<millisecond>(10.0);
+            db.measurements.set_sample((n == 1).then_some(7)); // Others have no sample
+            let id = db
+                .measurements
+                .push(Kind::Sample, position, position, integration, &[])
+                .unwrap();
+            db.intensities.push(id, &wavelengths, vec![n as f64; 3]);
+        }
+        db.samples.annotate(7, &[("name", "Æther"), ("", "")]);
+        let mut rig = Instrument {
+            name: "bench".into(),
+            model: "FLAME-S".into(),
+            serial: "FLMS12345".into(),
+            light_source: "HL-2000".into(),
+            geometry: Geometry::Directional {
+                illumination: Angle::new::<degree>(45.0),
+                viewing: Angle::new::<degree>(0.0),
+            },
+            fibre: "QR400-7".into(),
+            slit: Some(Length::new::<micrometer>(25.0)),
+            grating: "#2".into(),
+        };
+        db.instruments.register(&rig).unwrap();
+        rig.geometry = Geometry::Sphere { specular: true };
+        rig.slit = None; // Null angles and slit
+        db.instruments.register(&rig).unwrap();
+        let session = db.measurements.session();
+        db.sessions.annotate(session, &[("operator", "alice")]);
+        db.wavelengths.commit().unwrap();
+        db.measurements.commit().unwrap();
+        db.intensities.commit().unwrap();
+        db.samples.commit().unwrap();
+        db.sessions.commit().unwrap();
+        let file = db.path.join("export.h5");
+        db.export_hdf5(&file, true).unwrap();
+
+        let mut copy = Database::import_hdf5(&file, &db.path.join("copy")).unwrap();
+        assert_eq!(
+            db.wavelengths.batches().unwrap(),
+            copy.wavelengths.batches().unwrap()
+        );
+        assert_eq!(
+            db.measurements.batches().unwrap(),
+            copy.measurements.batches().unwrap()
+        );
+        assert_eq!(
+            db.intensities.batches().unwrap(),
+            copy.intensities.batches().unwrap()
+        );
+        assert_eq!(db.samples.read().unwrap(), copy.samples.read().unwrap());
+        assert_eq!(db.sessions.read().unwrap(), copy.sessions.read().unwrap());
+        assert_eq!(
+            db.instruments.read().unwrap(),
+            copy.instruments.read().unwrap()
+        );
+        assert_eq!(copy.measurements.session(), session + 1); // No session is skipped
+        assert_eq!(copy.samples.create(&[]), 8);
+
+        let bytes = read(&file).unwrap();
+        let parser = Parser::new(&bytes).unwrap();
+        let cube = parser.open("cube/data").unwrap();
+        assert_eq!(cube.dims, vec![3, 3]);
+        let nm = parser.values(&parser.open("cube/nm").unwrap()).unwrap();
+        assert_eq!(nm, Values::F64(vec![400.0, 500.0, 600.0]));
+        remove_dir_all(PATH).unwrap();
+    }
+
+    #[test]
+    fn extra_columns() {
+        const PATH: &str = "test-hdf5-extra";
+        let extras = [
+            Extra::Float {
+                name: "temperature".into(),
+                unit: "degC".into(),
+            },
+            Extra::Integer {
+                name: "frame".into(),
+            },
+            Extra::Text {
+                name: "note".into(),
+            },
+            Extra::Boolean {
+                name: "shutter".into(),
+            },
+        ];
+        let mut db = Database::with_columns(PATH, &extras).unwrap();
+        let position = Length::new::<micrometer>(0.0);
+        let integration = Time::new::<millisecond>(10.0);
+        let values = [
+            ("temperature", Value::Float(21.5)),
+            ("frame", Value::Integer(-3)),
+            ("note", Value::Text("edge".into())),
+            ("shutter", Value::Boolean(true)),
+        ];
+        for extra in [&values[..], &values[2..], &[]] {
+            db.measurements
+                .push(Kind::Dark, position, position, integration, extra)
+                .unwrap();
+        }
+        db.measurements.commit().unwrap();
+        let file = db.path.join("export.h5");
+        db.export_hdf5(&file, false).unwrap();
+
+        let copy = Database::import_hdf5(&file, &db.path.join("copy")).unwrap();
+        assert_eq!(copy.measurements.extras(), extras);
+        assert_eq!(
+            db.measurements.read().unwrap(),
+            copy.measurements.read().unwrap()
+        );
+        drop(db);
+
+        let slash = [Extra::Boolean { name: "a/b".into() }];
+        let db = Database::with_columns("test-hdf5-slash", &slash).unwrap();
+        let result = db.export_hdf5(&db.path.join("export.h5"), false);
+        assert!(matches!(result, Err(Error::InvalidName(_))));
+        remove_dir_all(PATH).unwrap();
+        remove_dir_all("test-hdf5-slash").unwrap();
+    }
+}
@@ -0,0 +1,482 @@
+/*
+Project: Optic
+GitHub: https://github.com/MillieFD/optic
+
+BSD 3-Clause License, Copyright (c) 2026, Amelia Fraser-Dale
+
+Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the conditions of the LICENSE are met.
+*/
+
+/* ----------------------------------------------------------------------------- Private Imports */
+
+use super::builder::{flatten, odometer};
+use super::checksum::lookup3;
+use super::{Message, UNDEFINED, Values};
+use crate::Error;
+use crate::deflate::decompress;
+
+/* ------------------------------------------------------------------------------ Public Exports */
+
+/// Reads files with a version 2 or 3 superblock and version 2 object headers, as written by
+/// [`Builder`](super::builder::Builder). Datasets may be contiguous, compact or chunked with a
+/// version 1 B-tree index and the `shuffle` and `deflate` filters.
+pub(super) struct Parser<'a> {
+    bytes: &'a [u8],
+    root: u64,
+}
+
+impl<'a> Parser<'a> {
+    pub(super) fn new(bytes: &'a [u8]) -> Result<Self, Error> {
+        let mut cursor = Cursor::new(bytes, 0);
+        if cursor.take(8)? != b"\x89HDF\r\n\x1A\n" {
+            return Err(invalid("missing HDF5 signature"));
+        }
+        let version = cursor.u8()?;
+        let (offsets, lengths) = (cursor.u8()?, cursor.u8()?);
+        if !(2..=3).contains(&version) || offsets != 8 || lengths != 8 {
+            return Err(invalid("unsupported superblock"));
+        }
+        cursor.skip(1 + 8 + 8 + 8)?; // Flags, base, extension and end of file addresses
+        let root = cursor.u64()?;
+        if cursor.u32()? != lookup3(&bytes[..44]) {
+            return Err(invalid("superblock checksum mismatch"));
+        }
+        Ok(Self { bytes, root })
+    }
+
+    /// Open the dataset at the end of a `/` separated path of links from the root group.
+    pub(super) fn open(&self, path: &str) -> Result<Dataset, Error> {
+        self.dataset(self.locate(path)?)
+    }
+
+    /// Names of the objects linked from the group at `path`, in the order they were linked.
+    pub(super) fn members(&self, path: &str) -> Result<Vec<String>, Error> {
+        let links = self.links(self.locate(path)?)?;
+        Ok(links.into_iter().map(|(name, _)| name).collect())
+    }
+
+    /// Value of the string attribute `name` of the object at `path`, if it has one.
+    pub(super) fn attribute(&self, path: &str, name: &str) -> Result<Option<String>, Error> {
+        for (message, data) in self.messages(self.locate(path)?)? {
+            if message != Message::Attribute as u8 {
+                continue;
+            }
+            let mut cursor = Cursor::new(data, 0);
+            let version = cursor.u8()?;
+            cursor.skip(1)?; // Flags
+            let (label, datatype, dataspace) = (cursor.u16()?, cursor.u16()?, cursor.u16()?);
+            let padded = |n: u16| match version {
+                1 => (n as u64).div_ceil(8) * 8,
+                _ => n as u64,
+            };
+            if version == 3 {
+                cursor.skip(1)?; // Name character set
+            }
+            let label = cursor.take(label as usize)?;
+            cursor.skip(padded(label.len() as u16) - label.len() as u64)?;
+            if label.strip_suffix(&[0]).unwrap_or(label) != name.as_bytes() {
+                continue;
+            }
+            let mut types = Cursor::new(cursor.take(datatype as usize)?, 0);
+            cursor.skip(padded(datatype) - datatype as u64 + padded(dataspace))?;
+            let class = types.u8()? & 0x0F;
+            types.skip(3)?;
+            if class != 3 {
+                return Err(invalid(&format!("attribute '{name}' is not a string")));
+            }
+            let value = cursor.take(types.u32()? as usize)?;
+            let end = value
+                .iter()
+                .position(|&byte| byte == 0)
+                .unwrap_or(value.len());
+            return Ok(Some(String::from_utf8_lossy(&value[..end]).into_owned()));
+        }
+        Ok(None)
+    }
+
+    /// Follow a `/` separated path of links from the root group to the address of an object.
+    fn locate(&self, path: &str) -> Result<u64, Error> {
+        let mut address = self.root;
+        for name in path.split('/').filter(|name| !name.is_empty()) {
+            address = self
+                .links(address)?
+                .into_iter()
+                .find_map(|(link, target)| (link == name).then_some(target))
+                .ok_or_else(|| invalid(&format!("missing object '{path}'")))?;
+        }
+        Ok(address)
+    }
+
+    fn links(&self, address: u64) -> Result<Vec<(String, u64)>, Error> {
+        let mut links = Vec::new();
+        for (message, data) in self.messages(address)? {
+            if message != Message::Link as u8 {
+                continue;
+            }
+            let mut cursor = Cursor::new(data, 0);
+            let (_version, flags) = (cursor.u8()?, cursor.u8()?);
+            if flags & 0x08 != 0 && cursor.u8()? != 0 {
+                continue; // Soft and external links are not followed
+            }
+            if flags & 0x04 != 0 {
+                cursor.skip(8)?; // Creation order
+            }
+            if flags & 0x10 != 0 {
+                cursor.skip(1)?; // Character set
+            }
+            let length = cursor.uint(1 << (flags & 0x03))? as usize;
+            let name = String::from_utf8_lossy(cursor.take(length)?).into_owned();
+            links.push((name, cursor.u64()?));
+        }
+        Ok(links)
+    }
+
+    fn dataset(&self, address: u64) -> Result<Dataset, Error> {
+        let mut dataset = Dataset::default();
+        for (message, data) in self.messages(address)? {
+            let mut cursor = Cursor::new(data, 0);
+            match message {
+                m if m == Message::Dataspace as u8 => {
+                    let (version, rank) = (cursor.u8()?, cursor.u8()?);
+                    cursor.skip(if version == 1 { 6 } else { 2 })?; // Flags and reserved
+                    dataset.dims = (0..rank).map(|_| cursor.u64()).collect::<Result<_, _>>()?;
+                }
+                m if m == Message::Datatype as u8 => {
+                    dataset.datatype = Some(datatype(&mut cursor)?)
+                }
+                m if m == Message::Layout as u8 => dataset.layout = Some(layout(&mut cursor)?),
+                m if m == Message::Filters as u8 => dataset.filters = filters(&mut cursor)?,
+                _ => {} // Attributes and other messages are not needed
+            }
+        }
+        match (&dataset.datatype, &dataset.layout) {
+            (Some(_), Some(_)) => Ok(dataset),
+            _ => Err(invalid("object is not a dataset")),
+        }
+    }
+
+    /// Parse every message in the object header at `address`, including continuation chunks.
+    fn messages(&self, address: u64) -> Result<Vec<(u8, &'a [u8])>, Error> {
+        let mut cursor = Cursor::new(self.bytes, address);
+        if cursor.take(4)? != b"OHDR" || cursor.u8()? != 2 {
+            return Err(invalid("unsupported object header"));
+        }
+        let flags = cursor.u8()?;
+        cursor.skip(if flags & 0x20 != 0 { 16 } else { 0 })?; // Timestamps
+        cursor.skip(if flags & 0x10 != 0 { 4 } else { 0 })?; // Attribute phase change
+        let size = cursor.uint(1 << (flags & 0x03))?;
+        let mut chunks = vec![(address, cursor.position, cursor.position + size)];
+        let mut messages = Vec::new();
+        while let Some((start, from, to)) = chunks.pop() {
+            let checksum = Cursor::new(self.bytes, to).u32()?;
+            if checksum != lookup3(self.slice(start, to)?) {
+                return Err(invalid("object header checksum mismatch"));
+            }
+            let mut cursor = Cursor::new(self.bytes, from);
+            let header = if flags & 0x04 != 0 { 6 } else { 4 };
+            while cursor.position + header <= to {
+                let message = cursor.u8()?;
+                let length = cursor.u16()? as u64;
+                cursor.skip(header - 3)?; // Message flags and creation order
+                let data = cursor.take(length as usize)?;
+                if message == 0x10 {
+                    let mut continuation = Cursor::new(data, 0);
+                    let (at, length) = (continuation.u64()?, continuation.u64()?);
+                    chunks.push((at, at + 4, at + length - 4)); // Skip "OCHK" and checksum
+                }
+                messages.push((message, data));
+            }
+        }
+        Ok(messages)
+    }
+
+    /// Read every element of `dataset` in row-major order.
+    pub(super) fn values(&self, dataset: &Dataset) -> Result<Values, Error> {
+        let datatype = dataset
+            .datatype
+            .ok_or_else(|| invalid("missing datatype"))?;
+        let size = datatype.size();
+        let total: u64 = dataset.dims.iter().product();
+        let mut bytes = vec![0u8; total as usize * size];
+        match &dataset.layout {
+            Some(Layout::Compact(data)) => {
+                let data = data
+                    .get(..bytes.len())
+                    .ok_or_else(|| invalid("truncated data"))?;
+                bytes.copy_from_slice(data)
+            }
+            Some(Layout::Contiguous(UNDEFINED)) => {} // Never written
+            Some(Layout::Contiguous(address)) => {
+                let len = bytes.len() as u64;
+                bytes.copy_from_slice(self.slice(*address, address + len)?)
+            }
+            Some(Layout::Chunked(btree, chunk)) if *btree != UNDEFINED => {
+                self.chunks(*btree, dataset, chunk, size, &mut bytes)?
+            }
+            _ => {}
+        }
+        datatype.values(&bytes)
+    }
+
+    fn chunks(
+        &self,
+        address: u64,
+        dataset: &Dataset,
+        chunk: &[u64],
+        size: usize,
+        output: &mut [u8],
+    ) -> Result<(), Error> {
+        let rank = dataset.dims.len();
+        let mut cursor = Cursor::new(self.bytes, address);
+        if cursor.take(4)? != b"TREE" || cursor.u8()? != 1 {
+            return Err(invalid("unsupported chunk index"));
+        }
+        let level = cursor.u8()?;
+        let entries = cursor.u16()?;
+        cursor.skip(16)?; // Siblings
+        for _ in 0..entries {
+            let nbytes = cursor.u32()? as usize;
+            let mask = cursor.u32()?;
+            let origin: Vec<u64> = (0..=rank).map(|_| cursor.u64()).collect::<Result<_, _>>()?;
+            let child = cursor.u64()?;
+            if level > 0 {
+                self.chunks(child, dataset, chunk, size, output)?;
+                continue;
+            }
+            let mut data = self.slice(child, child + nbytes as u64)?.to_vec();
+            for (i, (id, values)) in dataset.filters.iter().enumerate().rev() {
+                if mask & (1 << i) != 0 {
+                    continue; // Filter was skipped when writing this chunk
+                }
+                data = match id {
+                    1 => decompress(&data)?,
+                    2 => unshuffle(&data, values.first().map_or(size, |&n| n as usize)),
+                    _ => return Err(invalid(&format!("unsupported filter {id}"))),
+                };
+            }
+            for (n, offset) in odometer(chunk).enumerate() {
+                let position: Vec<u64> = origin.iter().zip(&offset).map(|(o, i)| o + i).collect();
+                if position.iter().zip(&dataset.dims).all(|(p, d)| p < d) {
+                    let to = flatten(&position, &dataset.dims) as usize * size;
+                    let from = n * size;
+                    let element = data
+                        .get(from..from + size)
+                        .ok_or_else(|| invalid("truncated chunk"))?;
+                    output[to..to + size].copy_from_slice(element);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn slice(&self, from: u64, to: u64) -> Result<&'a [u8], Error> {
+        self.bytes
+            .get(from as usize..to as usize)
+            .ok_or_else(|| invalid("address out of bounds"))
+    }
+}
+
+#[derive(Default)]
+pub(super) struct Dataset {
+    pub dims: Vec<u64>,
+    datatype: Option<Datatype>,
+    layout: Option<Layout>,
+    filters: Vec<(u16, Vec<u32>)>,
+}
+
+/* ----------------------------------------------------------------------------- Private Helpers */
+
+#[derive(Copy, Clone)]
+enum Datatype {
+    U8,
+    U32,
+    I64,
+    F64,
+    Text(usize),
+}
+
+impl Datatype {
+    fn size(&self) -> usize {
+        match self {
+            Datatype::U8 => 1,
+            Datatype::U32 => 4,
+            Datatype::I64 | Datatype::F64 => 8,
+            Datatype::Text(size) => *size,
+        }
+    }
+
+    fn values(&self, bytes: &[u8]) -> Result<Values, Error> {
+        let values = match self {
+            Datatype::U8 => Values::U8(bytes.to_vec()),
+            Datatype::U32 => Values::U32(
+                bytes
+                    .chunks_exact(4)
+                    .map(|b| u32::from_le_bytes(b.try_into().unwrap()))
+                    .collect(),
+            ),
+            Datatype::I64 => Values::I64(
+                bytes
+                    .chunks_exact(8)
+                    .map(|b| i64::from_le_bytes(b.try_into().unwrap()))
+                    .collect(),
+            ),
+            Datatype::F64 => Values::F64(
+                bytes
+                    .chunks_exact(8)
+                    .map(|b| f64::from_le_bytes(b.try_into().unwrap()))
+                    .collect(),
+            ),
+            Datatype::Text(size) => Values::Text(
+                bytes
+                    .chunks_exact(*size)
+                    .map(|b| {
+                        let end = b.iter().rposition(|&byte| byte != 0).map_or(0, |i| i + 1);
+                        String::from_utf8(b[..end].to_vec()).map_err(|_| invalid("invalid string"))
+                    })
+                    .collect::<Result<_, _>>()?,
+            ),
+        };
+        Ok(values)
+    }
+}
+
+enum Layout {
+    Compact(Vec<u8>),
+    Contiguous(u64),
+    Chunked(u64, Vec<u64>),
+}
+
+struct Cursor<'a> {
+    bytes: &'a [u8],
+    position: u64,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(bytes: &'a [u8], position: u64) -> Self {
+        Self { bytes, position }
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], Error> {
+        let from = self.position as usize;
+        let slice = self
+            .bytes
+            .get(from..from + n)
+            .ok_or_else(|| invalid("unexpected end of data"))?;
+        self.position += n as u64;
+        Ok(slice)
+    }
+
+    fn skip(&mut self, n: u64) -> Result<(), Error> {
+        self.take(n as usize).map(|_| ())
+    }
+
+    fn uint(&mut self, n: usize) -> Result<u64, Error> {
+        let mut buffer = [0u8; 8];
+        buffer[..n].copy_from_slice(self.take(n)?);
+        Ok(u64::from_le_bytes(buffer))
+    }
+
+    fn u8(&mut self) -> Result<u8, Error> {
+        self.uint(1).map(|n| n as u8)
+    }
+
+    fn u16(&mut self) -> Result<u16, Error> {
+        self.uint(2).map(|n| n as u16)
+    }
+
+    fn u32(&mut self) -> Result<u32, Error> {
+        self.uint(4).map(|n| n as u32)
+    }
+
+    fn u64(&mut self) -> Result<u64, Error> {
+        self.uint(8)
+    }
+}
+
+fn invalid(reason: &str) -> Error {
+    Error::ParseError(format!("Invalid HDF5 file: {reason}"))
+}
+
+fn datatype(cursor: &mut Cursor) -> Result<Datatype, Error> {
+    let class = cursor.u8()? & 0x0F;
+    let bits = cursor.u8()?;
+    cursor.skip(2)?;
+    let size = cursor.u32()?;
+    if class < 2 && bits & 0x01 != 0 {
+        return Err(invalid("big endian data is not supported"));
+    }
+    match (class, size, bits & 0x08 != 0) {
+        (0, 1, false) => Ok(Datatype::U8),
+        (0, 4, false) => Ok(Datatype::U32),
+        (0, 8, true) => Ok(Datatype::I64),
+        (1, 8, _) => Ok(Datatype::F64),
+        (3, 1.., _) => Ok(Datatype::Text(size as usize)),
+        _ => Err(invalid("unsupported datatype")),
+    }
+}
+
+fn layout(cursor: &mut Cursor) -> Result<Layout, Error> {
+    let version = cursor.u8()?;
+    if version != 3 {
+        return Err(invalid("unsupported layout version"));
+    }
+    match cursor.u8()? {
+        0 => {
+            let size = cursor.u16()? as usize;
+            Ok(Layout::Compact(cursor.take(size)?.to_vec()))
+        }
+        1 => Ok(Layout::Contiguous(cursor.u64()?)),
+        2 => {
+            let rank = cursor.u8()? as usize;
+            let address = cursor.u64()?;
+            let dims: Vec<u64> = (0..rank)
+                .map(|_| cursor.u32().map(u64::from))
+                .collect::<Result<_, _>>()?;
+            Ok(Layout::Chunked(address, dims[..rank - 1].to_vec()))
+        }
+        _ => Err(invalid("unsupported layout class")),
+    }
+}
+
+fn filters(cursor: &mut Cursor) -> Result<Vec<(u16, Vec<u32>)>, Error> {
+    let version = cursor.u8()?;
+    let count = cursor.u8()?;
+    if version == 1 {
+        cursor.skip(6)?;
+    }
+    (0..count)
+        .map(|_| {
+            let id = cursor.u16()?;
+            let name = match version == 1 || id >= 256 {
+                true => cursor.u16()?,
+                false => 0,
+            };
+            let _flags = cursor.u16()?;
+            let values = cursor.u16()?;
+            cursor.skip(match version {
+                1 => (name as u64).div_ceil(8) * 8,
+                _ => name as u64,
+            })?;
+            let data = (0..values)
+                .map(|_| cursor.u32())
+                .collect::<Result<Vec<_>, _>>()?;
+            if version == 1 && values % 2 == 1 {
+                cursor.skip(4)?;
+            }
+            Ok((id, data))
+        })
+        .collect()
+}
+
+fn unshuffle(data: &[u8], size: usize) -> Vec<u8> {
+    let n = data.len() / size;
+    let mut output = vec![0; data.len()];
+    for (i, element) in output.chunks_exact_mut(size).enumerate() {
+        element
+            .iter_mut()
+            .enumerate()
+            .for_each(|(b, byte)| *byte = data[b * n + i]);
+    }
+    output
+}
@@ -0,0 +1,203 @@
+/*
+Project: Optic
+GitHub: https://github.com/MillieFD/optic
+
+BSD 3-Clause License, Copyright (c) 2026, Amelia Fraser-Dale
+
+Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the conditions of the LICENSE are met.
+*/
+
+/* ----------------------------------------------------------------------------- Private Modules */
+
+mod parser;
+
+/* ----------------------------------------------------------------------------- Private Imports */
+
+use std::fs::{metadata, read_to_string};
+use std::path::{Path, PathBuf};
+
+#[cfg(any(feature = "x", feature = "y", feature = "z", feature = "a"))]
+use uom::si::f64::Length;
+#[cfg(any(feature = "x", feature = "y", feature = "z", feature = "a"))]
+use uom::si::length::micrometer;
+
+use self::parser::Export;
+use crate::{Database, Error, Kind};
+
+/* ------------------------------------------------------------------------------ Public Exports */
+
+/// Outcome of importing a single file.
+#[derive(Debug)]
+pub struct Report {
+    pub path: PathBuf,
+    /// The new measurement ID or the reason the file was rejected.
+    pub result: Result<u32, Error>,
+    /// Problems that did not prevent the import, such as an unknown time zone.
+    pub warnings: Vec<String>,
+}
+
+impl Database {
+    /// Bulk import OceanView, SpectraSuite and AvaSoft text exports.
+    ///
+    /// Each file becomes one measurement. The header integration time and timestamp are mapped
+    /// onto the `measurements` table, falling back to the file modification time when the export
+    /// has no date or its time zone is unknown, which is reported as a warning. Stage positions are
+    /// not recorded by these exports and are stored as zero. Files that fail to parse are
+    /// reported individually and do not abort the import. The outer [`Error`] is only returned
+    /// if the imported data cannot be committed.
+    pub fn import<I, P>(&mut self, files: I) -> Result<Vec<Report>, Error>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        let reports = files
+            .into_iter()
+            .map(|file| {
+                let path = file.as_ref().to_path_buf();
+                let mut warnings = Vec::new();
+                let result = self.import_file(&path, &mut warnings);
+                Report {
+                    path,
+                    result,
+                    warnings,
+                }
+            })
+            .collect();
+        self.measurements.commit()?;
+        self.intensities.commit()?;
+        Ok(reports)
+    }
+
+    fn import_file(&mut self, path: &Path, warnings: &mut Vec<String>) -> Result<u32, Error> {
+        let mut export: Export = read_to_string(path)?.parse()?;
+        warnings.append(&mut export.warnings);
+        let timestamp = match export.timestamp {
+            Some(timestamp) => timestamp,
+            None => metadata(path)?.modified()?,
+        };
+        let integration = export
+            .integration
+            .ok_or_else(|| Error::ParseError("Missing integration time".into()))?;
+        let wavelengths = self.wavelengths.push(export.wavelengths)?;
+        self.wavelengths.commit()?; // Later files deduplicate against the committed axis
+        #[cfg(any(feature = "x", feature = "y", feature = "z", feature = "a"))]
+        let origin = Length::new::<micrometer>(0.0);
+        let id = self.measurements.push_at(
+            timestamp,
+            Kind::Sample,
+            #[cfg(feature = "x")]
+            origin,
+            #[cfg(feature = "y")]
+            origin,
+            #[cfg(feature = "z")]
+            origin,
+            #[cfg(feature = "a")]
+            origin,
+            integration,
+            &[],
+        )?;
+        self.intensities.push(id, &wavelengths, export.intensities);
+        Ok(id)
+    }
+}
+
+/* ---------------------------------------------------------------------------------- Unit Tests */
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{remove_dir_all, write};
+    use std::time::{Duration, SystemTime};
+
+    use uom::si::time::microsecond;
+
+    use super::*;
+
+    const OCEANVIEW: &str = "Data from FLMS12345__0__12-34-56-789.txt Node
+
+Date: Mon Jan 01 12:00:00 GMT 2024
+User: lab
+Spectrometer: FLMS12345
+Integration Time (sec): 1.000000E-1
+Number of Pixels in Spectrum: 3
+>>>>>Begin Spectral Data<<<<<
+400.10\t100.5
+400.50\t200.5
+400.90\t300.5
+";
+
+    const AVASOFT: &str = "Data measured with spectrometer [name]: 1108123U1
+Int.time [msec]       :   10,00
+Average               : 1
+Wave   ;Sample   ;Dark     ;Reference;Scope
+[nm]   ;[counts] ;[counts] ;[counts] ;[counts]
+
+ 400,10;   1,000;   0,000;   0,000;  11,000
+ 400,50;   2,000;   0,000;   0,000;  12,000
+ 400,90;   3,000;   0,000;   0,000;  13,000
+";
+
+    #[test]
+    fn parse_oceanview() {
+        let export: Export = OCEANVIEW.parse().unwrap();
+        let expected = SystemTime::UNIX_EPOCH + Duration::from_secs(1_704_110_400);
+        assert_eq!(export.timestamp, Some(expected));
+        assert_eq!(export.integration.unwrap().get::<microsecond>(), 100_000.0);
+        assert_eq!(export.wavelengths, vec![400.1, 400.5, 400.9]);
+        assert_eq!(export.intensities, vec![100.5, 200.5, 300.5]);
+    }
+
+    #[test]
+    fn time_zones() {
+        let utc: Export = OCEANVIEW.parse().unwrap();
+        let utc = utc.timestamp.unwrap();
+        for zone in ["JST", "+0900", "GMT+09:00", "UTC+9"] {
+            let export: Export = OCEANVIEW.replace("GMT", zone).parse().unwrap();
+            let shift = utc.duration_since(export.timestamp.unwrap()).unwrap();
+            assert_eq!(shift, Duration::from_secs(9 * 3_600), "{zone}");
+        }
+        let india: Export = OCEANVIEW.replace("GMT", "IST").parse().unwrap();
+        let shift = utc.duration_since(india.timestamp.unwrap()).unwrap();
+        assert_eq!(shift, Duration::from_secs(19_800));
+        let unknown: Export = OCEANVIEW.replace("GMT", "XYZT").parse().unwrap();
+        assert_eq!(unknown.timestamp, None);
+        assert_eq!(unknown.warnings.len(), 1);
+    }
+
+    #[test]
+    fn parse_avasoft() {
+        let export: Export = AVASOFT.parse().unwrap();
+        assert_eq!(export.timestamp, None);
+        assert_eq!(export.integration.unwrap().get::<microsecond>(), 10_000.0);
+        assert_eq!(export.intensities, vec![11.0, 12.0, 13.0]);
+    }
+
+    #[test]
+    fn pixel_count_mismatch() {
+        let truncated = OCEANVIEW.replace("400.90\t300.5\n", "");
+        assert!(truncated.parse::<Export>().is_err());
+    }
+
+    #[test]
+    fn import_files() {
+        const PATH: &str = "test-import-files";
+        let mut db = Database::new(PATH).unwrap();
+        let files =
+            ["ocean.txt", "avantes.txt", "broken.txt", "tokyo.txt"].map(|f| db.path.join(f));
+        write(&files[0], OCEANVIEW).unwrap();
+        write(&files[1], AVASOFT).unwrap();
+        write(&files[2], "Not a spectrum").unwrap();
+        write(&files[3], OCEANVIEW.replace("GMT", "XYZT")).unwrap();
+        let reports = db.import(&files).unwrap();
+        let ids: Vec<_> = reports
+            .iter()
+            .map(|report| report.result.as_ref().ok().copied())
+            .collect();
+        assert_eq!(ids, vec![Some(0), Some(1), None, Some(2)]);
+        assert!(reports[0].warnings.is_empty());
+        assert_eq!(reports[3].warnings.len(), 1); // Falls back to the modification time
+        let wavelengths = db.wavelengths.push(vec![400.1, 400.5, 400.9]).unwrap();
+        assert_eq!(wavelengths, vec![0, 1, 2]); // Shared axis is deduplicated
+        remove_dir_all(PATH).unwrap();
+    }
+}
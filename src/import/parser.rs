@@ -0,0 +1,244 @@
+/*
+Project: Optic
+GitHub: https://github.com/MillieFD/optic
+
+BSD 3-Clause License, Copyright (c) 2026, Amelia Fraser-Dale
+
+Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the conditions of the LICENSE are met.
+*/
+
+/* ----------------------------------------------------------------------------- Private Imports */
+
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
+
+use uom::si::f64::Time;
+use uom::si::time::{microsecond, millisecond, second};
+
+use crate::Error;
+
+/* ------------------------------------------------------------------------------ Public Exports */
+
+/// Header fields and spectral data parsed from a single vendor text export.
+#[derive(Debug, Default)]
+pub(super) struct Export {
+    pub timestamp: Option<SystemTime>,
+    pub integration: Option<Time>,
+    pub pixels: Option<usize>,
+    pub wavelengths: Vec<f64>,
+    pub intensities: Vec<f64>,
+    /// Problems that did not prevent parsing.
+    pub warnings: Vec<String>,
+}
+
+impl Export {
+    fn header(&mut self, key: &str, value: &str) -> Result<(), Error> {
+        let key = key.trim().to_lowercase();
+        let value = value.trim();
+        match key.as_str() {
+            "date" => {
+                self.timestamp = timestamp(value)?;
+                if self.timestamp.is_none() {
+                    self.warnings.push(format!(
+                        "Unknown time zone in date '{value}', using the file modification time"
+                    ));
+                }
+            }
+            k if k.starts_with("integration time") || k.starts_with("int.time") => {
+                self.integration = Some(integration(k, value)?)
+            }
+            k if k.starts_with("number of pixels") => self.pixels = Some(number(value)? as usize),
+            _ => {} // Ignore unused header fields
+        }
+        Ok(())
+    }
+
+    /// Select the raw counts column from a multi-column AvaSoft export.
+    fn column(names: &str) -> Option<usize> {
+        names
+            .split(';')
+            .map(str::trim)
+            .position(|name| name.eq_ignore_ascii_case("scope"))
+    }
+}
+
+/* ----------------------------------------------------------------------- Trait Implementations */
+
+impl FromStr for Export {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut export = Export::default();
+        let mut column = 1;
+        for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            if line.starts_with(">>>>>End") {
+                break; // Ignore any trailing footer
+            }
+            if line.starts_with(">>>>>") || line.starts_with('[') || line.starts_with('+') {
+                continue; // Section markers and unit rows
+            }
+            if let Some(values) = row(line) {
+                let wavelength = values.first().copied();
+                let intensity = values.get(column).copied();
+                match (wavelength, intensity) {
+                    (Some(wl), Some(i)) => {
+                        export.wavelengths.push(wl);
+                        export.intensities.push(i);
+                    }
+                    _ => return Err(Error::ParseError(format!("Malformed data row '{line}'"))),
+                }
+            } else if !export.wavelengths.is_empty() {
+                return Err(Error::ParseError(format!(
+                    "Unexpected line '{line}' in data"
+                )));
+            } else if let Some(index) = Export::column(line) {
+                column = index;
+            } else if let Some((key, value)) = line.split_once(':') {
+                export.header(key, value)?;
+            }
+        }
+        if export.wavelengths.is_empty() {
+            return Err(Error::ParseError("No spectral data found".into()));
+        }
+        match export.pixels {
+            Some(n) if n != export.wavelengths.len() => Err(Error::ParseError(format!(
+                "Expected {n} pixels but found {}",
+                export.wavelengths.len()
+            ))),
+            _ => Ok(export),
+        }
+    }
+}
+
+/* ----------------------------------------------------------------------------- Private Helpers */
+
+fn number(value: &str) -> Result<f64, Error> {
+    let token = value.split_whitespace().next().unwrap_or_default();
+    token
+        .replace(',', ".")
+        .parse()
+        .map_err(|_| Error::ParseError(format!("Invalid number '{value}'")))
+}
+
+fn row(line: &str) -> Option<Vec<f64>> {
+    // Semicolon and tab separated exports may use a decimal comma
+    let line = match line.contains(';') || line.contains('\t') {
+        true => line.replace(',', "."),
+        false => line.replace(',', " "),
+    };
+    line.split(|c: char| c == ';' || c.is_whitespace())
+        .filter(|token| !token.is_empty())
+        .map(|token| token.parse().ok())
+        .collect()
+}
+
+fn integration(key: &str, value: &str) -> Result<Time, Error> {
+    let n = number(value)?;
+    let unit = key
+        .rsplit(['(', '['])
+        .next()
+        .unwrap_or_default()
+        .trim_end_matches([')', ']']);
+    match unit {
+        "usec" | "us" | "µs" => Ok(Time::new::<microsecond>(n)),
+        "msec" | "ms" => Ok(Time::new::<millisecond>(n)),
+        "sec" | "s" => Ok(Time::new::<second>(n)),
+        _ => Err(Error::ParseError(format!(
+            "Unknown integration time unit '{key}'"
+        ))),
+    }
+}
+
+/// Parse a Java style `Date.toString` timestamp e.g. `Mon Jan 01 12:34:56 GMT 2024`. The zone
+/// may also be a numeric offset such as `+0900` or `GMT+05:30`. Returns `None` for an unknown
+/// time zone abbreviation.
+fn timestamp(value: &str) -> Result<Option<SystemTime>, Error> {
+    const MONTHS: [&str; 12] = [
+        "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+    ];
+    let invalid = || Error::ParseError(format!("Invalid date '{value}'"));
+    let tokens: Vec<&str> = value.split_whitespace().collect();
+    let [_, month, day, time, zone, year] = tokens[..] else {
+        return Err(invalid());
+    };
+    let month = MONTHS
+        .iter()
+        .position(|m| month.to_lowercase().starts_with(m))
+        .ok_or_else(invalid)? as i64
+        + 1;
+    let day: i64 = day.parse().map_err(|_| invalid())?;
+    let year: i64 = year.parse().map_err(|_| invalid())?;
+    let hms: Vec<i64> = time
+        .split(':')
+        .map(str::parse)
+        .collect::<Result<_, _>>()
+        .map_err(|_| invalid())?;
+    let [h, m, s] = hms[..] else {
+        return Err(invalid());
+    };
+    let Some(offset) = offset(zone) else {
+        return Ok(None);
+    };
+    let seconds = days(year, month, day) * 86_400 + h * 3_600 + m * 60 + s - offset * 60;
+    let seconds = u64::try_from(seconds).map_err(|_| invalid())?;
+    Ok(Some(SystemTime::UNIX_EPOCH + Duration::from_secs(seconds)))
+}
+
+/// Offset from UTC in minutes of a time zone abbreviation or a numeric offset such as `+0900`,
+/// `+09:00`, `GMT-3` or `UTC+05:30`. `IST` is taken as India Standard Time.
+fn offset(zone: &str) -> Option<i64> {
+    let zone = zone.to_uppercase();
+    let hours = match zone.as_str() {
+        "GMT" | "UTC" | "Z" | "WET" => Some(0.0),
+        "BST" | "CET" | "WEST" | "WAT" => Some(1.0),
+        "CEST" | "EET" | "CAT" => Some(2.0),
+        "EEST" | "MSK" | "EAT" => Some(3.0),
+        "IST" => Some(5.5),
+        "AWST" | "HKT" | "SGT" => Some(8.0),
+        "JST" | "KST" => Some(9.0),
+        "ACST" => Some(9.5),
+        "AEST" => Some(10.0),
+        "ACDT" => Some(10.5),
+        "AEDT" => Some(11.0),
+        "NZST" => Some(12.0),
+        "NZDT" => Some(13.0),
+        "EDT" => Some(-4.0),
+        "EST" | "CDT" => Some(-5.0),
+        "CST" | "MDT" => Some(-6.0),
+        "MST" | "PDT" => Some(-7.0),
+        "PST" | "AKDT" => Some(-8.0),
+        "AKST" => Some(-9.0),
+        "HST" => Some(-10.0),
+        _ => None,
+    };
+    if let Some(hours) = hours {
+        return Some((hours * 60.0) as i64);
+    }
+    let numeric = zone.trim_start_matches("GMT").trim_start_matches("UTC");
+    let (sign, digits) = match numeric.split_at_checked(1)? {
+        ("+", digits) => (1, digits),
+        ("-", digits) => (-1, digits),
+        _ => return None,
+    };
+    if !digits.chars().all(|c| c.is_ascii_digit() || c == ':') {
+        return None;
+    }
+    let (h, m) = match digits.split_once(':') {
+        Some((h, m)) => (h, m),
+        None if digits.len() == 4 => digits.split_at(2),
+        None => (digits, "0"),
+    };
+    let (h, m): (i64, i64) = (h.parse().ok()?, m.parse().ok()?);
+    (h <= 14 && m < 60).then_some(sign * (h * 60 + m))
+}
+
+/// Days since the UNIX epoch for a proleptic Gregorian calendar date.
+fn days(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let doy = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
@@ -0,0 +1,363 @@
+/*
+Project: Optic
+GitHub: https://github.com/MillieFD/optic
+
+BSD 3-Clause License, Copyright (c) 2026, Amelia Fraser-Dale
+
+Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the conditions of the LICENSE are met.
+*/
+
+/* ----------------------------------------------------------------------------- Private Imports */
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock};
+use std::time::{Duration, SystemTime};
+
+use arrow::array::{
+    Array,
+    ArrayRef,
+    AsArray,
+    Float64Array,
+    RecordBatch,
+    StringArray,
+    TimestampMicrosecondArray,
+    UInt32Array,
+};
+use arrow::datatypes::DataType::{Float64, Timestamp, UInt32, Utf8};
+use arrow::datatypes::TimeUnit::Microsecond;
+use arrow::datatypes::{Field, Float64Type, Schema, TimestampMicrosecondType, UInt32Type};
+use arrow::ipc::writer::StreamWriter;
+use uom::si::angle::degree;
+use uom::si::f64::{Angle, Length};
+use uom::si::length::micrometer;
+
+use crate::{Database, Error, Reader, Writer};
+
+/* ------------------------------------------------------------------------------ Public Exports */
+
+/// Registry of instrument configurations, stored in the `instruments` table. Every change to a
+/// rig is registered as a new version under the same name with its own configuration ID, which
+/// is attached to measurements with [`crate::Database::measurements`].
+pub struct Instruments {
+    stream: StreamWriter<File>,
+    pub path: PathBuf,
+}
+
+/// Illumination and viewing geometry of the probe.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Geometry {
+    /// Directional illumination and viewing at angles from the sample normal e.g. 45°/0°.
+    Directional { illumination: Angle, viewing: Angle },
+    /// Integrating sphere with the specular component included or excluded.
+    Sphere { specular: bool },
+    /// Any other geometry, described in words. The tags stored for the other variants are
+    /// reserved and rejected by [`Instruments::register`].
+    Other(String),
+}
+
+/// Hardware configuration of a rig.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Instrument {
+    /// Name of the rig shared by all of its versions.
+    pub name: String,
+    /// Spectrometer model.
+    pub model: String,
+    /// Spectrometer serial number.
+    pub serial: String,
+    pub light_source: String,
+    pub geometry: Geometry,
+    pub fibre: String,
+    /// Entrance slit width, if known.
+    pub slit: Option<Length>,
+    pub grating: String,
+}
+
+/// One registered version of an [`Instrument`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Configuration {
+    pub id: u32,
+    /// Version of the rig, counting from one.
+    pub version: u32,
+    pub registered: SystemTime,
+    pub instrument: Instrument,
+}
+
+impl Instruments {
+    pub(super) fn new<P>(path: P) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref().join("instruments").with_extension("arrow");
+        Ok(Self {
+            stream: Self::open_stream_writer(&path)?,
+            path,
+        })
+    }
+
+    /// Register `instrument` as the next version of its rig and return its configuration ID.
+    /// Configurations are written immediately.
+    pub fn register(&mut self, instrument: &Instrument) -> Result<u32, Error> {
+        if let Geometry::Other(geometry) = &instrument.geometry
+            && [DIRECTIONAL, INCLUDED, EXCLUDED].contains(&geometry.as_str())
+        {
+            let e = format!("Geometry '{geometry}' is reserved");
+            return Err(Error::InvalidParameter(e));
+        }
+        let configurations = self.read()?;
+        let id = configurations.keys().map(|id| id + 1).max().unwrap_or(0);
+        let version = configurations
+            .values()
+            .filter(|c| c.instrument.name == instrument.name)
+            .map(|c| c.version)
+            .max()
+            .unwrap_or(0)
+            + 1;
+        let registered = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_micros() as i64;
+        let text = |value: &str| Arc::new(StringArray::from(vec![value])) as ArrayRef;
+        let (illumination, viewing) = match &instrument.geometry {
+            Geometry::Directional {
+                illumination,
+                viewing,
+            } => (
+                Some(illumination.get::<degree>()),
+                Some(viewing.get::<degree>()),
+            ),
+            _ => (None, None),
+        };
+        let geometry = match &instrument.geometry {
+            Geometry::Directional { .. } => DIRECTIONAL,
+            Geometry::Sphere { specular: true } => INCLUDED,
+            Geometry::Sphere { specular: false } => EXCLUDED,
+            Geometry::Other(geometry) => geometry,
+        };
+        let slit = instrument.slit.map(|slit| slit.get::<micrometer>());
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(UInt32Array::from(vec![id])),
+            text(&instrument.name),
+            Arc::new(UInt32Array::from(vec![version])),
+            Arc::new(TimestampMicrosecondArray::from(vec![registered])),
+            text(&instrument.model),
+            text(&instrument.serial),
+            text(&instrument.light_source),
+            text(geometry),
+            Arc::new(Float64Array::from(vec![illumination])),
+            Arc::new(Float64Array::from(vec![viewing])),
+            text(&instrument.fibre),
+            Arc::new(Float64Array::from(vec![slit])),
+            text(&instrument.grating),
+        ];
+        let batch = RecordBatch::try_new(Self::schema(), columns)?;
+        self.write(&batch)?;
+        Ok(id)
+    }
+
+    /// Read every registered configuration keyed by configuration ID.
+    pub fn read(&self) -> Result<BTreeMap<u32, Configuration>, Error> {
+        let mut configurations = BTreeMap::new();
+        for batch in self.batches()? {
+            let text = |column: usize, row: usize| {
+                batch
+                    .column(column)
+                    .as_string::<i32>()
+                    .value(row)
+                    .to_string()
+            };
+            let number = |column: usize, row: usize| {
+                let values = batch.column(column).as_primitive::<Float64Type>();
+                values.is_valid(row).then(|| values.value(row))
+            };
+            let ids = batch.column(0).as_primitive::<UInt32Type>();
+            let versions = batch.column(2).as_primitive::<UInt32Type>();
+            let registered = batch.column(3).as_primitive::<TimestampMicrosecondType>();
+            for row in 0..batch.num_rows() {
+                let geometry = match (text(7, row).as_str(), number(8, row), number(9, row)) {
+                    (DIRECTIONAL, Some(illumination), Some(viewing)) => Geometry::Directional {
+                        illumination: Angle::new::<degree>(illumination),
+                        viewing: Angle::new::<degree>(viewing),
+                    },
+                    (INCLUDED, ..) => Geometry::Sphere { specular: true },
+                    (EXCLUDED, ..) => Geometry::Sphere { specular: false },
+                    (other, ..) => Geometry::Other(other.to_string()),
+                };
+                let instrument = Instrument {
+                    name: text(1, row),
+                    model: text(4, row),
+                    serial: text(5, row),
+                    light_source: text(6, row),
+                    geometry,
+                    fibre: text(10, row),
+                    slit: number(11, row).map(Length::new::<micrometer>),
+                    grating: text(12, row),
+                };
+                let registered = registered.value(row).max(0) as u64;
+                let configuration = Configuration {
+                    id: ids.value(row),
+                    version: versions.value(row),
+                    registered: SystemTime::UNIX_EPOCH + Duration::from_micros(registered),
+                    instrument,
+                };
+                configurations.insert(configuration.id, configuration);
+            }
+        }
+        Ok(configurations)
+    }
+
+    /// Every version of the rig called `name`, oldest first.
+    pub fn history(&self, name: &str) -> Result<Vec<Configuration>, Error> {
+        let mut versions: Vec<Configuration> = self
+            .read()?
+            .into_values()
+            .filter(|c| c.instrument.name == name)
+            .collect();
+        versions.sort_unstable_by_key(|c| c.version);
+        Ok(versions)
+    }
+}
+
+impl Database {
+    /// Attach the registered instrument configuration `instrument` to every measurement pushed
+    /// from now on, or detach it with `None`. Fails if no configuration has that ID.
+    pub fn set_instrument(&mut self, instrument: Option<u32>) -> Result<(), Error> {
+        if let Some(id) = instrument
+            && !self.instruments.read()?.contains_key(&id)
+        {
+            let e = format!("Instrument configuration {id} is not registered");
+            return Err(Error::InvalidParameter(e));
+        }
+        self.measurements.set_instrument(instrument);
+        Ok(())
+    }
+}
+
+/* ----------------------------------------------------------------------- Trait Implementations */
+
+impl Writer for Instruments {
+    const SCHEMA: LazyLock<Arc<Schema>> = LazyLock::new(|| {
+        let fields = [
+            Field::new("id", UInt32, false).into(),
+            Field::new("name", Utf8, false).into(),
+            Field::new("version", UInt32, false).into(),
+            Field::new("registered", Timestamp(Microsecond, None), false).into(),
+            Field::new("model", Utf8, false).into(),
+            Field::new("serial", Utf8, false).into(),
+            Field::new("light_source", Utf8, false).into(),
+            Field::new("geometry", Utf8, false).into(),
+            Field::new("illumination", Float64, true).into(),
+            Field::new("viewing", Float64, true).into(),
+            Field::new("fibre", Utf8, false).into(),
+            Field::new("slit", Float64, true).into(),
+            Field::new("grating", Utf8, false).into(),
+        ];
+        Schema::new(fields).into()
+    });
+
+    fn stream(&mut self) -> &mut StreamWriter<File> {
+        &mut self.stream
+    }
+}
+
+impl Reader for Instruments {
+    fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+/* ----------------------------------------------------------------------------- Private Helpers */
+
+/// Stored `geometry` of each [`Geometry`] variant except [`Geometry::Other`], whose text is
+/// stored as it is. Directional angles are stored in degrees in their own columns.
+const DIRECTIONAL: &str = "directional";
+const INCLUDED: &str = "sphere_specular_included";
+const EXCLUDED: &str = "sphere_specular_excluded";
+
+/* ---------------------------------------------------------------------------------- Unit Tests */
+
+#[cfg(all(
+    test,
+    feature = "x",
+    feature = "y",
+    not(feature = "z"),
+    not(feature = "a")
+))]
+mod tests {
+    use std::fs::remove_dir_all;
+
+    use uom::si::f64::Time;
+    use uom::si::time::millisecond;
+
+    use super::*;
+    use crate::{Filter, Kind};
+
+    #[test]
+    fn register_instruments() {
+        const PATH: &str = "test-instruments";
+        let mut db = Database::new(PATH).unwrap();
+        let mut rig = Instrument {
+            name: "bench".into(),
+            model: "FLAME-S".into(),
+            serial: "FLMS12345".into(),
+            light_source: "HL-2000".into(),
+            geometry: Geometry::Directional {
+                illumination: Angle::new::<degree>(45.0),
+                viewing: Angle::new::<degree>(0.0),
+            },
+            fibre: "QR400-7".into(),
+            slit: Some(Length::new::<micrometer>(25.0)),
+            grating: "#2".into(),
+        };
+        let first = db.instruments.register(&rig).unwrap();
+        rig.geometry = Geometry::Sphere { specular: false };
+        rig.slit = None;
+        let second = db.instruments.register(&rig).unwrap();
+        let other = Instrument {
+            name: "field".into(),
+            geometry: Geometry::Other("contact probe".into()),
+            ..rig.clone()
+        };
+        db.instruments.register(&other).unwrap();
+        for reserved in [DIRECTIONAL, INCLUDED, EXCLUDED] {
+            let reserved = Instrument {
+                geometry: Geometry::Other(reserved.into()),
+                ..other.clone()
+            };
+            assert!(db.instruments.register(&reserved).is_err());
+        }
+
+        let zero = Length::new::<micrometer>(0.0);
+        let integration = Time::new::<millisecond>(10.0);
+        assert!(db.set_instrument(Some(3)).is_err()); // Not registered
+        assert_eq!(db.measurements.instrument(), None);
+        db.set_instrument(Some(second)).unwrap();
+        let id = db
+            .measurements
+            .push(Kind::Sample, zero, zero, integration, &[])
+            .unwrap();
+        db.set_instrument(None).unwrap();
+        db.measurements
+            .push(Kind::Sample, zero, zero, integration, &[])
+            .unwrap();
+        db.measurements.commit().unwrap();
+        drop(db);
+
+        let db = Database::new(PATH).unwrap();
+        let history = db.instruments.history("bench").unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!((history[0].id, history[0].version), (first, 1));
+        assert_eq!((history[1].id, history[1].version), (second, 2));
+        assert_eq!(history[1].instrument, rig);
+        let configurations = db.instruments.read().unwrap();
+        assert_eq!(configurations.len(), 3);
+        let slit = configurations[&first].instrument.slit.unwrap();
+        assert!((slit.get::<micrometer>() - 25.0).abs() < 1E-9);
+        let records = db.select(&[Filter::Instrument(second)]).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!((records[0].id, records[0].instrument), (id, Some(second)));
+        remove_dir_all(PATH).unwrap();
+    }
+}
@@ -16,14 +16,14 @@ use arrow::array::{ArrayRef, Float64Builder, UInt32Builder};
 
 /* ------------------------------------------------------------------------------ Public Exports */
 
-pub(super) struct Builder {
+pub(crate) struct Builder {
     measurement: UInt32Builder,
     wavelength: UInt32Builder,
     intensity: Float64Builder,
 }
 
 impl Builder {
-    pub(super) fn new() -> Self {
+    pub(crate) fn new() -> Self {
         Self {
             measurement: Default::default(),
             wavelength: Default::default(),
@@ -31,10 +31,10 @@ impl Builder {
         }
     }
 
-    pub(super) fn push(&mut self, measurement: u32, wavelengths: &Vec<u32>, intensities: Vec<f64>) {
+    pub(crate) fn push(&mut self, measurement: u32, wavelengths: &[u32], intensities: Vec<f64>) {
         wavelengths
-            .into_iter()
-            .map(u32::clone)
+            .iter()
+            .copied()
             .zip(intensities)
             .for_each(|(λ, i)| {
                 self.measurement.append_value(measurement);
@@ -43,7 +43,7 @@ impl Builder {
             })
     }
 
-    pub(super) fn columns(&mut self) -> Vec<ArrayRef> {
+    pub(crate) fn columns(&mut self) -> Vec<ArrayRef> {
         vec![
             Arc::new(self.measurement.finish()),
             Arc::new(self.wavelength.finish()),
@@ -14,23 +14,28 @@ mod builder;
 
 /* ----------------------------------------------------------------------------- Private Imports */
 
-use std::fs::{File, OpenOptions};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs::File;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, LazyLock};
 
-use arrow::array::RecordBatch;
+use arrow::array::{AsArray, RecordBatch};
 use arrow::datatypes::DataType::{Float64, UInt32};
-use arrow::datatypes::{Field, Schema};
+use arrow::datatypes::{Field, Float64Type, Schema, UInt32Type};
 use arrow::ipc::writer::StreamWriter;
 
-use self::builder::Builder;
-use crate::{Error, Writer};
+pub(crate) use self::builder::Builder;
+use crate::{Error, Reader, Writer};
 
 /* ------------------------------------------------------------------------------ Public Exports */
 
+/// Intensity by wavelength ID for a single measurement.
+pub type Spectrum = BTreeMap<u32, f64>;
+
 pub struct Intensities {
     stream: StreamWriter<File>,
     builder: Builder,
+    pub path: PathBuf,
 }
 
 impl Intensities {
@@ -44,17 +49,51 @@ impl Intensities {
             .try_into()
     }
 
-    pub fn push(&mut self, measurement: u32, wavelengths: &Vec<u32>, intensities: Vec<f64>) {
+    pub fn push(&mut self, measurement: u32, wavelengths: &[u32], intensities: Vec<f64>) {
         self.builder.push(measurement, wavelengths, intensities);
     }
 
+    /// Read the committed spectra of the given `measurements`, keyed by measurement ID.
+    pub fn spectra(&self, measurements: &[u32]) -> Result<HashMap<u32, Spectrum>, Error> {
+        let wanted: HashSet<&u32> = measurements.iter().collect();
+        let batches = self.batches()?;
+        Ok(group(&batches, "intensity", |id| wanted.contains(&id)))
+    }
+
     pub fn commit(&mut self) -> Result<(), Error> {
         let columns = self.builder.columns();
         let batch = RecordBatch::try_new(Self::schema(), columns)?;
-        self.stream.write(&batch).map_err(Error::from)
+        self.write(&batch)
     }
 }
 
+/// Group `(measurement, wavelength, value)` rows into spectra keyed by measurement ID, keeping
+/// only measurements accepted by `filter`.
+pub(crate) fn group<F>(batches: &[RecordBatch], value: &str, filter: F) -> HashMap<u32, Spectrum>
+where
+    F: Fn(u32) -> bool,
+{
+    batches.iter().fold(HashMap::new(), |mut spectra, batch| {
+        let column = |name: &str| {
+            batch
+                .column_by_name(name)
+                .unwrap_or_else(|| panic!("Unable to read '{name}' column"))
+        };
+        let measurement = column("measurement").as_primitive::<UInt32Type>();
+        let wavelength = column("wavelength").as_primitive::<UInt32Type>();
+        let value = column(value).as_primitive::<Float64Type>();
+        (0..batch.num_rows())
+            .filter(|&row| filter(measurement.value(row)))
+            .for_each(|row| {
+                spectra
+                    .entry(measurement.value(row))
+                    .or_insert_with(Spectrum::new)
+                    .insert(wavelength.value(row), value.value(row));
+            });
+        spectra
+    })
+}
+
 /* ----------------------------------------------------------------------- Trait Implementations */
 
 impl Writer for Intensities {
@@ -66,29 +105,26 @@ impl Writer for Intensities {
         ];
         Schema::new(fields).into()
     });
-}
 
-impl TryFrom<PathBuf> for Intensities {
-    type Error = Error;
+    fn stream(&mut self) -> &mut StreamWriter<File> {
+        &mut self.stream
+    }
+}
 
-    fn try_from(path: PathBuf) -> Result<Self, Self::Error> {
-        OpenOptions::new()
-            .read(false)
-            .write(true)
-            .create(true)
-            .truncate(false)
-            .open(&path)?
-            .try_into()
+impl Reader for Intensities {
+    fn path(&self) -> &Path {
+        &self.path
     }
 }
 
-impl TryFrom<File> for Intensities {
+impl TryFrom<PathBuf> for Intensities {
     type Error = Error;
 
-    fn try_from(file: File) -> Result<Self, Self::Error> {
+    fn try_from(path: PathBuf) -> Result<Self, Self::Error> {
         Ok(Self {
-            stream: Self::new_stream_writer(file)?,
+            stream: Self::open_stream_writer(&path)?,
             builder: Builder::new(),
+            path,
         })
     }
 }
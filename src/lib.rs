@@ -10,18 +10,78 @@ modification, are permitted provided that the conditions of the LICENSE are met.
 
 #![feature(iter_collect_into)]
 
+mod analysis;
+mod attributes;
+mod bandmath;
+mod calibration;
+mod colour;
+mod correction;
+#[cfg(any(all(feature = "x", feature = "y"), feature = "hdf5"))]
+mod deflate;
+mod derived;
+mod despike;
 mod error;
+#[cfg(feature = "hdf5")]
+mod hdf5;
+mod import;
+mod instruments;
 mod intensities;
+mod library;
 mod measurements;
+mod normalised;
+mod optical;
+#[cfg(all(feature = "x", feature = "y"))]
+mod plan;
+mod preprocess;
+mod quality;
+mod reader;
+mod reflectance;
+#[cfg(all(feature = "x", feature = "y"))]
+mod render;
+mod replicates;
+mod resample;
+mod scalars;
+#[cfg(all(feature = "x", feature = "y"))]
+mod spatial;
 mod wavelengths;
 mod writer;
 
 use std::fs::DirBuilder;
 use std::path::{Path, PathBuf};
 
+pub use self::analysis::Pca;
+pub use self::attributes::{Attributes, Filter};
+pub use self::bandmath::{Expression, Resolution};
+pub use self::calibration::Calibration;
+pub use self::colour::{Colorimetry, Difference, Illuminant, Observer, Viewing};
+pub use self::correction::Profile;
+pub use self::derived::{Derived, Provenance, Source};
+pub use self::despike::{Despike, Despiked};
 pub use self::error::Error;
+pub use self::import::Report;
+pub use self::instruments::{Configuration, Geometry, Instrument, Instruments};
 use self::intensities::Intensities;
+pub use self::intensities::Spectrum;
+pub use self::library::{Library, Match, Similarity};
 use self::measurements::Measurements;
+pub use self::measurements::{Extra, Kind, Record as Measurement, Value};
+pub use self::normalised::Dark;
+pub use self::optical::{Coefficients, Scattering, Transform};
+#[cfg(all(feature = "x", feature = "y"))]
+pub use self::plan::{Order, Plan};
+pub use self::preprocess::{Pipeline, Step};
+pub use self::quality::{Flag, Quality, Thresholds};
+use self::reader::Reader;
+pub use self::reflectance::References;
+#[cfg(all(feature = "x", feature = "y"))]
+pub use self::render::{Colour, Format, Stretch};
+pub use self::replicates::Grouping;
+pub use self::resample::{Interpolation, Matrix};
+#[cfg(all(feature = "x", feature = "y"))]
+pub use self::scalars::Raster;
+pub use self::scalars::{Columns, Scalars};
+#[cfg(all(feature = "x", feature = "y"))]
+pub use self::spatial::{Cube, Gridding};
 use self::wavelengths::Wavelengths;
 use self::writer::Writer;
 
@@ -30,22 +90,69 @@ pub struct Database {
     pub wavelengths: Wavelengths,
     pub measurements: Measurements,
     pub intensities: Intensities,
+    pub samples: Attributes,
+    pub sessions: Attributes,
+    pub instruments: Instruments,
 }
 
 impl Database {
+    /// Open the database at `filepath`, creating it if it does not exist.
     pub fn new<P>(filepath: &P) -> Result<Database, Error>
     where
         P: AsRef<Path> + ?Sized,
     {
-        DirBuilder::new().recursive(true).create(&filepath)?;
+        Self::open(filepath, None)
+    }
+
+    /// Open the database at `filepath`, creating it with the user-defined measurement columns
+    /// `extras` if it does not exist. Fails if an existing database has other columns.
+    pub fn with_columns<P>(filepath: &P, extras: &[Extra]) -> Result<Database, Error>
+    where
+        P: AsRef<Path> + ?Sized,
+    {
+        Self::open(filepath, Some(extras))
+    }
+
+    fn open<P>(filepath: &P, extras: Option<&[Extra]>) -> Result<Database, Error>
+    where
+        P: AsRef<Path> + ?Sized,
+    {
+        let mut db = Self::open_tables(filepath, extras)?;
+        db.start_session()?;
+        Ok(db)
+    }
+
+    /// Open every table as [`Database::with_columns`] without starting a session. The caller
+    /// must call [`Database::start_session`] before pushing measurements.
+    pub(crate) fn open_tables<P>(filepath: &P, extras: Option<&[Extra]>) -> Result<Database, Error>
+    where
+        P: AsRef<Path> + ?Sized,
+    {
+        DirBuilder::new().recursive(true).create(filepath)?;
         let path = filepath.as_ref().canonicalize()?;
-        let db = Database {
+        Ok(Database {
             wavelengths: Wavelengths::new(&path)?,
-            measurements: Measurements::new(&path)?,
+            measurements: Measurements::new(&path, extras)?,
             intensities: Intensities::new(&path)?,
+            samples: Attributes::new(&path, "samples")?,
+            sessions: Attributes::new(&path, "sessions")?,
+            instruments: Instruments::new(&path)?,
             path,
-        };
-        Ok(db)
+        })
+    }
+
+    /// Attach a new session to measurements pushed from now on, skipping every sample and session
+    /// ID already referenced by a committed measurement.
+    pub(crate) fn start_session(&mut self) -> Result<(), Error> {
+        for record in self.measurements.read()? {
+            self.sessions.reserve(record.session);
+            if let Some(sample) = record.sample {
+                self.samples.reserve(sample);
+            }
+        }
+        let session = self.sessions.create(&[]);
+        self.measurements.set_session(session);
+        Ok(())
     }
 }
 
@@ -89,6 +196,21 @@ mod tests {
         remove_dir_all(PATH).unwrap();
     }
 
+    #[test]
+    fn wavelength_tolerance() {
+        const PATH: &str = "test-wavelength-tolerance";
+        let mut db = Database::new(PATH).unwrap();
+        let ids = db.wavelengths.push(vec![500.0, 600.0]).unwrap();
+        db.wavelengths.commit().unwrap();
+        let again = db
+            .wavelengths
+            .push(vec![600.0009, 500.0002, 500.002])
+            .unwrap();
+        assert_eq!(again[..2], [ids[1], ids[0]]); // Within 0.001 nm
+        assert_eq!(again[2], 2);
+        remove_dir_all(PATH).unwrap();
+    }
+
     #[test]
     fn commit_and_read_wavelengths() {
         const PATH: &str = "test-commit-and-read";
@@ -126,6 +248,36 @@ mod tests {
         remove_dir_all(PATH).unwrap();
     }
 
+    #[test]
+    #[cfg(all(feature = "x", feature = "y", not(feature = "z"), not(feature = "a")))]
+    fn commit_and_read_measurements() {
+        use std::time::{Duration, SystemTime};
+
+        use uom::si::f64::{Length, Time};
+        use uom::si::length::micrometer;
+        use uom::si::time::millisecond;
+
+        const PATH: &str = "test-commit-and-read-measurements";
+        let mut db = Database::new(PATH).unwrap();
+        let timestamp = SystemTime::UNIX_EPOCH + Duration::from_micros(1_700_000_000_123_456);
+        let position = Length::new::<micrometer>(12.5);
+        let integration = Time::new::<millisecond>(20.0);
+        let id = db
+            .measurements
+            .push_at(timestamp, Kind::White, position, position, integration, &[])
+            .unwrap();
+        db.measurements.commit().unwrap();
+
+        let records = db.measurements.read().unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].id, id);
+        assert_eq!(records[0].timestamp, timestamp);
+        assert_eq!(records[0].kind, Kind::White);
+        assert!((records[0].x.get::<micrometer>() - 12.5).abs() < 1E-9);
+        assert_eq!(records[0].integration, integration);
+        remove_dir_all(PATH).unwrap();
+    }
+
     #[test]
     fn finalise() {
         const PATH: &str = "test-finalise";
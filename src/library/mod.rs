@@ -0,0 +1,449 @@
+/*
+Project: Optic
+GitHub: https://github.com/MillieFD/optic
+
+BSD 3-Clause License, Copyright (c) 2026, Amelia Fraser-Dale
+
+Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the conditions of the LICENSE are met.
+*/
+
+/* ----------------------------------------------------------------------------- Private Modules */
+
+mod similarity;
+
+/* ----------------------------------------------------------------------------- Private Imports */
+
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{DirBuilder, File, OpenOptions, remove_file};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock};
+
+use arrow::array::{ArrayRef, AsArray, Float64Builder, RecordBatch, StringBuilder};
+use arrow::datatypes::DataType::{Float64, Utf8};
+use arrow::datatypes::{Field, Float64Type, Schema};
+use arrow::ipc::writer::StreamWriter;
+use uom::si::f64::Length;
+use uom::si::length::nanometer;
+
+use crate::derived::{Table, list};
+use crate::resample::{curve, linear};
+use crate::writer::new_stream_writer;
+use crate::{Database, Derived, Error, Provenance, Reader, Source, Writer};
+
+/* ------------------------------------------------------------------------------ Public Exports */
+
+/// A named collection of reference spectra, such as materials or tissues, stored in the `library`
+/// directory. Each entry keeps its own wavelength axis and is interpolated onto the wavelengths of
+/// a measurement when searched.
+pub struct Library {
+    stream: StreamWriter<File>,
+    entry: StringBuilder,
+    wavelength: Float64Builder,
+    value: Float64Builder,
+    pub name: String,
+    pub path: PathBuf,
+}
+
+/// Metric used to compare a measurement with a library entry.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Similarity {
+    /// Spectral angle mapper: the angle in radians between the two spectra as vectors. Lower is
+    /// better and insensitive to overall brightness.
+    Angle,
+    /// Euclidean distance between the two spectra. Lower is better.
+    Euclidean,
+    /// Pearson correlation coefficient. Higher is better.
+    Correlation,
+    /// Spectral information divergence between the two spectra normalised to unit sum. Lower is
+    /// better. Values must be positive.
+    Divergence,
+}
+
+/// Score of one library entry against a measurement.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Match {
+    pub entry: String,
+    pub score: f64,
+}
+
+impl Similarity {
+    /// Compare `a` with `b` sampled at the same wavelengths.
+    pub fn score(&self, a: &[f64], b: &[f64]) -> f64 {
+        match self {
+            Similarity::Angle => similarity::angle(a, b),
+            Similarity::Euclidean => similarity::euclidean(a, b),
+            Similarity::Correlation => similarity::correlation(a, b),
+            Similarity::Divergence => similarity::divergence(a, b),
+        }
+    }
+
+    /// Order scores from the best to the worst match. `NaN` scores rank last.
+    fn rank(&self, a: f64, b: f64) -> Ordering {
+        match (a.is_nan(), b.is_nan()) {
+            (false, false) if *self == Similarity::Correlation => b.total_cmp(&a),
+            (false, false) => a.total_cmp(&b),
+            (nan_a, nan_b) => nan_a.cmp(&nan_b),
+        }
+    }
+
+    /// Whether `score` is at least as good as `threshold`.
+    fn accepts(&self, score: f64, threshold: f64) -> bool {
+        match self {
+            Similarity::Correlation => score >= threshold,
+            _ => score <= threshold,
+        }
+    }
+}
+
+impl Library {
+    /// Append the reference spectrum `entry` sampled at `wavelengths`. Fails without appending
+    /// anything unless there is one value per wavelength.
+    pub fn push(
+        &mut self,
+        entry: &str,
+        wavelengths: &[Length],
+        values: &[f64],
+    ) -> Result<(), Error> {
+        if wavelengths.len() != values.len() {
+            return Err(Error::InvalidParameter(format!(
+                "{} wavelengths but {} values",
+                wavelengths.len(),
+                values.len()
+            )));
+        }
+        wavelengths.iter().zip(values).for_each(|(wl, value)| {
+            self.entry.append_value(entry);
+            self.wavelength.append_value(wl.get::<nanometer>());
+            self.value.append_value(*value);
+        });
+        Ok(())
+    }
+
+    pub fn commit(&mut self) -> Result<(), Error> {
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(self.entry.finish()),
+            Arc::new(self.wavelength.finish()),
+            Arc::new(self.value.finish()),
+        ];
+        let batch = RecordBatch::try_new(Self::schema(), columns)?;
+        self.write(&batch)
+    }
+}
+
+impl Database {
+    fn library_directory(&self) -> PathBuf {
+        self.path.join("library")
+    }
+
+    /// Create an empty spectral library called `name`. Names follow the same rules as
+    /// [`Database::create_derived`].
+    pub fn create_library(&self, name: &str) -> Result<Library, Error> {
+        let directory = self.library_directory();
+        let path = Derived::locate(&directory, name)?;
+        DirBuilder::new().recursive(true).create(&directory)?;
+        let file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)?;
+        Ok(Library {
+            stream: new_stream_writer(file, &Library::schema())?,
+            entry: StringBuilder::new(),
+            wavelength: Float64Builder::new(),
+            value: Float64Builder::new(),
+            name: name.to_string(),
+            path,
+        })
+    }
+
+    /// List the names of every spectral library in alphabetical order.
+    pub fn libraries(&self) -> Result<Vec<String>, Error> {
+        list(&self.library_directory())
+    }
+
+    /// Read every committed entry of the library called `name` as `(wavelength, value)` pairs
+    /// sorted by wavelength.
+    pub fn read_library(&self, name: &str) -> Result<BTreeMap<String, Vec<(Length, f64)>>, Error> {
+        let path = Derived::locate(&self.library_directory(), name)?;
+        let mut entries: BTreeMap<String, Vec<(f64, f64)>> = BTreeMap::new();
+        for batch in Table(path).batches()? {
+            let names = batch.column(0).as_string::<i32>();
+            let wavelengths = batch.column(1).as_primitive::<Float64Type>().values();
+            let values = batch.column(2).as_primitive::<Float64Type>().values();
+            for ((entry, nm), value) in names.iter().zip(wavelengths).zip(values) {
+                let entry = entry.unwrap_or_default().to_string();
+                entries.entry(entry).or_default().push((*nm, *value));
+            }
+        }
+        Ok(entries
+            .into_iter()
+            .map(|(entry, mut samples)| {
+                samples.sort_unstable_by(|a, b| a.0.total_cmp(&b.0));
+                let samples = samples
+                    .into_iter()
+                    .map(|(nm, value)| (Length::new::<nanometer>(nm), value))
+                    .collect();
+                (entry, samples)
+            })
+            .collect())
+    }
+
+    /// Delete the spectral library called `name`.
+    pub fn delete_library(&self, name: &str) -> Result<(), Error> {
+        let path = Derived::locate(&self.library_directory(), name)?;
+        remove_file(path).map_err(Error::from)
+    }
+
+    /// Compare the spectra of `measurements` from `source` with every entry of `library` and
+    /// return up to `count` matches per measurement, best first. Each comparison uses the
+    /// measured wavelengths inside the entry's range, with the entry linearly interpolated onto
+    /// them. Fewer than two shared wavelengths give a `NaN` score.
+    pub fn search(
+        &self,
+        source: Source,
+        measurements: &[u32],
+        library: &str,
+        metric: Similarity,
+        count: usize,
+    ) -> Result<HashMap<u32, Vec<Match>>, Error> {
+        let entries = self.read_library(library)?;
+        let entries: Vec<(String, Vec<(f64, f64)>)> = entries
+            .into_iter()
+            .map(|(entry, samples)| {
+                let samples = samples
+                    .into_iter()
+                    .map(|(wl, value)| (wl.get::<nanometer>(), value))
+                    .collect();
+                (entry, samples)
+            })
+            .collect();
+        let lookup = self.wavelengths.lookup()?;
+        self.spectra(source, measurements)?
+            .iter()
+            .map(|(id, spectrum)| {
+                let curve = curve(spectrum, &lookup)?;
+                let mut matches: Vec<Match> = entries
+                    .iter()
+                    .map(|(entry, samples)| Match {
+                        entry: entry.clone(),
+                        score: compare(&curve, samples, metric),
+                    })
+                    .collect();
+                matches.sort_by(|a, b| metric.rank(a.score, b.score));
+                matches.truncate(count);
+                Ok((*id, matches))
+            })
+            .collect()
+    }
+
+    /// Classify `measurements` by their best match in `library` as [`Database::search`] and write
+    /// the result to the scalar table called `name`. The `class` column holds the index of the
+    /// matched entry in alphabetical order, as listed in the `classes` provenance parameter, and
+    /// the `score` column holds its score. Matches worse than `threshold` are unclassified with a
+    /// `NaN` class. Render the `class` column with [`crate::Columns::raster`] for a classification
+    /// map. Returns the IDs of the classified measurements.
+    pub fn classify(
+        &self,
+        name: &str,
+        source: Source,
+        measurements: &[u32],
+        library: &str,
+        metric: Similarity,
+        threshold: Option<f64>,
+    ) -> Result<Vec<u32>, Error> {
+        let classes: Vec<String> = self.read_library(library)?.into_keys().collect();
+        let mut best: Vec<(u32, Option<Match>)> = self
+            .search(source, measurements, library, metric, 1)?
+            .into_iter()
+            .map(|(id, matches)| (id, matches.into_iter().next()))
+            .collect();
+        best.sort_unstable_by_key(|(id, _)| *id);
+        let provenance = Provenance::new("classify", &[source.name(), "wavelengths"])
+            .parameter("library", library)
+            .parameter("metric", format!("{metric:?}").to_lowercase())
+            .parameter("classes", classes.join(","));
+        let provenance = match threshold {
+            Some(threshold) => provenance.parameter("threshold", threshold),
+            None => provenance,
+        };
+        let mut table = self.create_scalars(name, &["class", "score"], &provenance)?;
+        for (id, best) in &best {
+            let (class, score) = match best {
+                Some(best) if !best.score.is_nan() => {
+                    let class = classes
+                        .binary_search(&best.entry)
+                        .map_or(f64::NAN, |i| i as f64);
+                    match threshold.is_none_or(|t| metric.accepts(best.score, t)) {
+                        true => (class, best.score),
+                        false => (f64::NAN, best.score),
+                    }
+                }
+                _ => (f64::NAN, f64::NAN),
+            };
+            table.push(*id, &[class, score])?;
+        }
+        table.commit()?;
+        Ok(best.into_iter().map(|(id, _)| id).collect())
+    }
+}
+
+/* ----------------------------------------------------------------------------- Private Helpers */
+
+/// Score a measured `curve` against library `samples`, both as `(nm, value)` sorted by wavelength.
+fn compare(curve: &[(f64, f64)], samples: &[(f64, f64)], metric: Similarity) -> f64 {
+    let (Some(first), Some(last)) = (samples.first(), samples.last()) else {
+        return f64::NAN;
+    };
+    let (measured, reference): (Vec<f64>, Vec<f64>) = curve
+        .iter()
+        .filter(|(nm, _)| (first.0..=last.0).contains(nm))
+        .map(|(nm, value)| (*value, linear(samples, *nm)))
+        .unzip();
+    match measured.len() {
+        0 | 1 => f64::NAN,
+        _ => metric.score(&measured, &reference),
+    }
+}
+
+/* ----------------------------------------------------------------------- Trait Implementations */
+
+impl Writer for Library {
+    const SCHEMA: LazyLock<Arc<Schema>> = LazyLock::new(|| {
+        let fields = [
+            Field::new("entry", Utf8, false).into(),
+            Field::new("wavelength", Float64, false).into(),
+            Field::new("value", Float64, false).into(),
+        ];
+        Schema::new(fields).into()
+    });
+
+    fn stream(&mut self) -> &mut StreamWriter<File> {
+        &mut self.stream
+    }
+}
+
+impl Reader for Library {
+    fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+/* ---------------------------------------------------------------------------------- Unit Tests */
+
+#[cfg(all(
+    test,
+    feature = "x",
+    feature = "y",
+    not(feature = "z"),
+    not(feature = "a")
+))]
+mod tests {
+    use std::fs::remove_dir_all;
+
+    use uom::si::f64::Time;
+    use uom::si::length::micrometer;
+    use uom::si::time::millisecond;
+
+    use super::*;
+    use crate::Kind;
+
+    #[test]
+    fn search_library() {
+        const PATH: &str = "test-library";
+        let mut db = Database::new(PATH).unwrap();
+        let nm = |values: &[f64]| -> Vec<Length> {
+            values
+                .iter()
+                .map(|v| Length::new::<nanometer>(*v))
+                .collect()
+        };
+        let mut library = db.create_library("materials").unwrap();
+        library
+            .push("rising", &nm(&[400.0, 500.0, 600.0]), &[1.0, 2.0, 3.0])
+            .unwrap();
+        library
+            .push("falling", &nm(&[400.0, 600.0]), &[3.0, 1.0])
+            .unwrap();
+        assert!(library.push("short", &nm(&[400.0]), &[]).is_err());
+        library.commit().unwrap();
+        assert_eq!(db.libraries().unwrap(), vec!["materials"]);
+        let entries = db.read_library("materials").unwrap();
+        assert_eq!(
+            entries.keys().collect::<Vec<_>>(),
+            vec!["falling", "rising"]
+        );
+        assert_eq!(entries["falling"].len(), 2);
+
+        let wavelengths = db
+            .wavelengths
+            .push(vec![400.0, 450.0, 500.0, 550.0, 600.0])
+            .unwrap();
+        db.wavelengths.commit().unwrap();
+        let integration = Time::new::<millisecond>(10.0);
+        let ids: Vec<u32> = [
+            (0.0, vec![2.0, 3.0, 4.0, 5.0, 6.0]), // Brighter copy of `rising`
+            (10.0, vec![3.0, 2.4, 2.0, 1.5, 1.0]), // Close to `falling`
+        ]
+        .into_iter()
+        .map(|(x, values)| {
+            let x = Length::new::<micrometer>(x);
+            let y = Length::new::<micrometer>(0.0);
+            let id = db
+                .measurements
+                .push(Kind::Sample, x, y, integration, &[])
+                .unwrap();
+            db.intensities.push(id, &wavelengths, values);
+            id
+        })
+        .collect();
+        db.measurements.commit().unwrap();
+        db.intensities.commit().unwrap();
+
+        for metric in [
+            Similarity::Angle,
+            Similarity::Euclidean,
+            Similarity::Correlation,
+            Similarity::Divergence,
+        ] {
+            let results = db
+                .search(Source::Intensities, &ids, "materials", metric, 2)
+                .unwrap();
+            assert_eq!(results[&ids[0]][0].entry, "rising", "{metric:?}");
+            assert_eq!(results[&ids[1]][0].entry, "falling", "{metric:?}");
+            assert_eq!(results[&ids[0]].len(), 2);
+        }
+        let results = db
+            .search(
+                Source::Intensities,
+                &ids,
+                "materials",
+                Similarity::Correlation,
+                1,
+            )
+            .unwrap();
+        assert!((results[&ids[0]][0].score - 1.0).abs() < 1E-9);
+        assert_eq!(results[&ids[0]].len(), 1);
+
+        let classified = db
+            .classify(
+                "classes",
+                Source::Intensities,
+                &ids,
+                "materials",
+                Similarity::Angle,
+                Some(1E-6),
+            )
+            .unwrap();
+        assert_eq!(classified, ids);
+        let columns = db.read_scalars("classes").unwrap();
+        assert_eq!(columns.provenance.parameters["classes"], "falling,rising");
+        assert_eq!(columns.values["class"][0], 1.0);
+        assert!(columns.values["class"][1].is_nan()); // Not within the threshold
+        let raster = columns.raster("class").unwrap();
+        assert_eq!((raster.width, raster.height), (2, 1));
+        db.delete_library("materials").unwrap();
+        assert!(db.libraries().unwrap().is_empty());
+        remove_dir_all(PATH).unwrap();
+    }
+}
@@ -0,0 +1,55 @@
+/*
+Project: Optic
+GitHub: https://github.com/MillieFD/optic
+
+BSD 3-Clause License, Copyright (c) 2026, Amelia Fraser-Dale
+
+Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the conditions of the LICENSE are met.
+*/
+
+/* ----------------------------------------------------------------------------- Private Imports */
+
+use crate::analysis::linalg::dot;
+
+/* ------------------------------------------------------------------------------ Public Exports */
+
+/// Angle in radians between `a` and `b` as vectors.
+pub(super) fn angle(a: &[f64], b: &[f64]) -> f64 {
+    let cosine = dot(a, b) / (dot(a, a) * dot(b, b)).sqrt();
+    cosine.clamp(-1.0, 1.0).acos()
+}
+
+pub(super) fn euclidean(a: &[f64], b: &[f64]) -> f64 {
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| (a - b).powi(2))
+        .sum::<f64>()
+        .sqrt()
+}
+
+/// Pearson correlation coefficient, `NaN` if either input is constant.
+pub(super) fn correlation(a: &[f64], b: &[f64]) -> f64 {
+    let centred = |values: &[f64]| {
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        values.iter().map(|v| v - mean).collect::<Vec<f64>>()
+    };
+    let (a, b) = (centred(a), centred(b));
+    dot(&a, &b) / (dot(&a, &a) * dot(&b, &b)).sqrt()
+}
+
+/// Symmetric Kullback–Leibler divergence of `a` and `b` normalised to unit sum, `NaN` unless
+/// every value is positive.
+pub(super) fn divergence(a: &[f64], b: &[f64]) -> f64 {
+    if a.iter().chain(b).any(|v| *v <= 0.0) {
+        return f64::NAN;
+    }
+    let (sa, sb): (f64, f64) = (a.iter().sum(), b.iter().sum());
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| {
+            let (p, q) = (a / sa, b / sb);
+            (p - q) * (p / q).ln()
+        })
+        .sum()
+}
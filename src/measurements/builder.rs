@@ -21,7 +21,9 @@ use arrow::array::{
     AsArray,
     DurationMicrosecondBuilder,
     Float64Builder,
+    RecordBatch,
     TimestampMicrosecondBuilder,
+    UInt8Builder,
     UInt32Builder,
 };
 use arrow::datatypes::UInt32Type;
@@ -30,12 +32,17 @@ use uom::si::f64::{Length, Time};
 use uom::si::length::micrometer;
 use uom::si::time::microsecond;
 
+use super::Kind;
+use super::extra::{Column, Extra, Value};
+use crate::Error;
+
 /* ------------------------------------------------------------------------------ Public Exports */
 
 pub(super) struct Builder {
     next: AtomicU32,
     id: UInt32Builder,
     timestamp: TimestampMicrosecondBuilder,
+    kind: UInt8Builder,
     #[cfg(feature = "x")]
     x: Float64Builder,
     #[cfg(feature = "y")]
@@ -45,10 +52,23 @@ pub(super) struct Builder {
     #[cfg(feature = "a")]
     a: Float64Builder,
     integration: DurationMicrosecondBuilder,
+    session: UInt32Builder,
+    sample: UInt32Builder,
+    instrument: UInt32Builder,
+    extra: Vec<(String, Column)>,
+    pub(super) context: Context,
+}
+
+/// Session, sample and instrument attached to every pushed row.
+#[derive(Default)]
+pub(super) struct Context {
+    pub(super) session: u32,
+    pub(super) sample: Option<u32>,
+    pub(super) instrument: Option<u32>,
 }
 
 impl Builder {
-    pub(super) fn new<P>(path: &P) -> Self
+    pub(super) fn new<P>(path: &P, extras: &[Extra]) -> Self
     where
         P: AsRef<Path> + ?Sized,
     {
@@ -56,6 +76,7 @@ impl Builder {
             next: Self::read(path),
             id: Default::default(),
             timestamp: Default::default(),
+            kind: Default::default(),
             #[cfg(feature = "x")]
             x: Default::default(),
             #[cfg(feature = "y")]
@@ -65,6 +86,14 @@ impl Builder {
             #[cfg(feature = "a")]
             a: Default::default(),
             integration: Default::default(),
+            session: Default::default(),
+            sample: Default::default(),
+            instrument: Default::default(),
+            extra: extras
+                .iter()
+                .map(|extra| (extra.name().to_string(), extra.builder()))
+                .collect(),
+            context: Default::default(),
         }
     }
 
@@ -73,41 +102,60 @@ impl Builder {
         P: AsRef<Path> + ?Sized,
     {
         let file = File::open(path).expect("Unable to open 'measurements' file");
+        let next = AtomicU32::default();
         StreamReader::try_new(file, None)
             .expect("Unable to read 'measurements' file")
             .filter_map(Result::ok)
-            .fold(Vec::new(), |mut ids, batch| {
-                batch
-                    .column_by_name("id")
-                    .expect("Unable to read 'id' column")
-                    .as_primitive::<UInt32Type>()
-                    .values()
-                    .iter()
-                    .copied()
-                    .collect_into(&mut ids)
-                    .to_owned()
-            })
-            .into_iter()
-            .max()
-            .map(AtomicU32::from)
-            .unwrap_or_default()
+            .for_each(|batch| Self::observe(&next, &batch));
+        next
+    }
+
+    pub(super) fn advance(&mut self, batch: &RecordBatch) {
+        Self::observe(&self.next, batch);
     }
 
+    fn observe(next: &AtomicU32, batch: &RecordBatch) {
+        let ids = batch
+            .column_by_name("id")
+            .expect("Unable to read 'id' column")
+            .as_primitive::<UInt32Type>();
+        if let Some(max) = ids.values().iter().max() {
+            next.fetch_max(max + 1, Ordering::Relaxed);
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)] // One positional argument per enabled axis feature
     pub fn push(
         &mut self,
+        timestamp: SystemTime,
+        kind: Kind,
         #[cfg(feature = "x")] x: Length,
         #[cfg(feature = "y")] y: Length,
         #[cfg(feature = "z")] z: Length,
         #[cfg(feature = "a")] a: Length,
         i: Time,
-    ) -> u32 {
-        let timestamp = SystemTime::UNIX_EPOCH
-            .elapsed()
+        extra: &[(&str, Value)],
+    ) -> Result<u32, Error> {
+        // Check every value before appending anything so the columns stay the same length
+        for (name, value) in extra {
+            match self.extra.iter().find(|(n, _)| n == name) {
+                None => return Err(Error::InvalidName(name.to_string())),
+                Some((_, column)) if !column.accepts(value) => {
+                    return Err(Error::InvalidParameter(format!(
+                        "Column '{name}' cannot hold {value:?}"
+                    )));
+                }
+                Some(_) => {}
+            }
+        }
+        let timestamp = timestamp
+            .duration_since(SystemTime::UNIX_EPOCH)
             .unwrap_or_default()
-            .as_millis() as i64;
+            .as_micros() as i64;
         let id: u32 = self.next.fetch_add(1, Ordering::Relaxed);
         self.id.append_value(id);
         self.timestamp.append_value(timestamp);
+        self.kind.append_value(kind.into());
         #[cfg(feature = "x")]
         self.x.append_value(x.get::<micrometer>());
         #[cfg(feature = "y")]
@@ -117,13 +165,24 @@ impl Builder {
         #[cfg(feature = "a")]
         self.a.append_value(a.get::<micrometer>());
         self.integration.append_value(i.get::<microsecond>() as i64);
-        id // Return the inserted measurement ID
+        self.session.append_value(self.context.session);
+        self.sample.append_option(self.context.sample);
+        self.instrument.append_option(self.context.instrument);
+        for (name, column) in self.extra.iter_mut() {
+            let value = extra
+                .iter()
+                .find(|(n, _)| n == name)
+                .map(|(_, value)| value);
+            column.append(value);
+        }
+        Ok(id) // Return the inserted measurement ID
     }
 
     pub(super) fn columns(&mut self) -> Vec<ArrayRef> {
-        vec![
+        let mut columns: Vec<ArrayRef> = vec![
             Arc::new(self.id.finish()),
             Arc::new(self.timestamp.finish()),
+            Arc::new(self.kind.finish()),
             #[cfg(feature = "x")]
             Arc::new(self.x.finish()),
             #[cfg(feature = "y")]
@@ -133,7 +192,12 @@ impl Builder {
             #[cfg(feature = "a")]
             Arc::new(self.a.finish()),
             Arc::new(self.integration.finish()),
-        ]
+            Arc::new(self.session.finish()),
+            Arc::new(self.sample.finish()),
+            Arc::new(self.instrument.finish()),
+        ];
+        columns.extend(self.extra.iter_mut().map(|(_, column)| column.finish()));
+        columns
     }
 }
 
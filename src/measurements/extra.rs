@@ -0,0 +1,167 @@
+/*
+Project: Optic
+GitHub: https://github.com/MillieFD/optic
+
+BSD 3-Clause License, Copyright (c) 2026, Amelia Fraser-Dale
+
+Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the conditions of the LICENSE are met.
+*/
+
+/* ----------------------------------------------------------------------------- Private Imports */
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arrow::array::{
+    Array,
+    ArrayRef,
+    AsArray,
+    BooleanBuilder,
+    Float64Builder,
+    Int64Builder,
+    StringBuilder,
+};
+use arrow::datatypes::DataType::{Boolean, Float64, Int64, Utf8};
+use arrow::datatypes::{Field, Float64Type, Int64Type};
+
+use crate::Error;
+
+/* ------------------------------------------------------------------------------ Public Exports */
+
+/// User-defined measurement column, such as temperature or lamp current, declared when the
+/// database is created with [`crate::Database::with_columns`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum Extra {
+    /// Floating point values in `unit` e.g. `"degC"`.
+    Float {
+        name: String,
+        unit: String,
+    },
+    Integer {
+        name: String,
+    },
+    Text {
+        name: String,
+    },
+    Boolean {
+        name: String,
+    },
+}
+
+/// Value of a user-defined measurement column.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Float(f64),
+    Integer(i64),
+    Text(String),
+    Boolean(bool),
+}
+
+impl Extra {
+    pub fn name(&self) -> &str {
+        match self {
+            Extra::Float { name, .. }
+            | Extra::Integer { name }
+            | Extra::Text { name }
+            | Extra::Boolean { name } => name,
+        }
+    }
+
+    pub(super) fn field(&self) -> Field {
+        match self {
+            Extra::Float { name, unit } => Field::new(name, Float64, true)
+                .with_metadata(HashMap::from([(UNIT.to_string(), unit.clone())])),
+            Extra::Integer { name } => Field::new(name, Int64, true),
+            Extra::Text { name } => Field::new(name, Utf8, true),
+            Extra::Boolean { name } => Field::new(name, Boolean, true),
+        }
+    }
+
+    pub(super) fn from_field(field: &Field) -> Result<Self, Error> {
+        let name = field.name().clone();
+        match field.data_type() {
+            Float64 => {
+                let unit = field.metadata().get(UNIT).cloned().unwrap_or_default();
+                Ok(Extra::Float { name, unit })
+            }
+            Int64 => Ok(Extra::Integer { name }),
+            Utf8 => Ok(Extra::Text { name }),
+            Boolean => Ok(Extra::Boolean { name }),
+            other => Err(Error::ParseError(format!("'{name}' has type {other}"))),
+        }
+    }
+
+    pub(super) fn builder(&self) -> Column {
+        match self {
+            Extra::Float { .. } => Column::Float(Float64Builder::new()),
+            Extra::Integer { .. } => Column::Integer(Int64Builder::new()),
+            Extra::Text { .. } => Column::Text(StringBuilder::new()),
+            Extra::Boolean { .. } => Column::Boolean(BooleanBuilder::new()),
+        }
+    }
+}
+
+impl Value {
+    /// Value at `row` of a user-defined column, if not null.
+    pub(super) fn at(array: &ArrayRef, row: usize) -> Option<Self> {
+        let value = match array.data_type() {
+            _ if array.is_null(row) => return None,
+            Float64 => Value::Float(array.as_primitive::<Float64Type>().value(row)),
+            Int64 => Value::Integer(array.as_primitive::<Int64Type>().value(row)),
+            Boolean => Value::Boolean(array.as_boolean().value(row)),
+            _ => Value::Text(array.as_string::<i32>().value(row).to_string()),
+        };
+        Some(value)
+    }
+}
+
+/// Builder of one user-defined column.
+pub(super) enum Column {
+    Float(Float64Builder),
+    Integer(Int64Builder),
+    Text(StringBuilder),
+    Boolean(BooleanBuilder),
+}
+
+impl Column {
+    /// Whether `value` has the type of the column.
+    pub(super) fn accepts(&self, value: &Value) -> bool {
+        matches!(
+            (self, value),
+            (Column::Float(_), Value::Float(_))
+                | (Column::Integer(_), Value::Integer(_))
+                | (Column::Text(_), Value::Text(_))
+                | (Column::Boolean(_), Value::Boolean(_))
+        )
+    }
+
+    /// Append `value` or null. Values of another type, rejected by [`Column::accepts`] before
+    /// anything is appended, are also null.
+    pub(super) fn append(&mut self, value: Option<&Value>) {
+        match (self, value) {
+            (Column::Float(builder), Some(Value::Float(v))) => builder.append_value(*v),
+            (Column::Integer(builder), Some(Value::Integer(v))) => builder.append_value(*v),
+            (Column::Text(builder), Some(Value::Text(v))) => builder.append_value(v),
+            (Column::Boolean(builder), Some(Value::Boolean(v))) => builder.append_value(*v),
+            (Column::Float(builder), _) => builder.append_null(),
+            (Column::Integer(builder), _) => builder.append_null(),
+            (Column::Text(builder), _) => builder.append_null(),
+            (Column::Boolean(builder), _) => builder.append_null(),
+        }
+    }
+
+    pub(super) fn finish(&mut self) -> ArrayRef {
+        match self {
+            Column::Float(builder) => Arc::new(builder.finish()),
+            Column::Integer(builder) => Arc::new(builder.finish()),
+            Column::Text(builder) => Arc::new(builder.finish()),
+            Column::Boolean(builder) => Arc::new(builder.finish()),
+        }
+    }
+}
+
+/* ----------------------------------------------------------------------------- Private Helpers */
+
+/// Field metadata key holding the unit of a floating point column.
+pub(crate) const UNIT: &str = "unit";
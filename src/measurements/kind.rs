@@ -0,0 +1,43 @@
+/*
+Project: Optic
+GitHub: https://github.com/MillieFD/optic
+
+BSD 3-Clause License, Copyright (c) 2026, Amelia Fraser-Dale
+
+Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the conditions of the LICENSE are met.
+*/
+
+/* ------------------------------------------------------------------------------ Public Exports */
+
+/// The role of a measurement in diffuse reflectance processing.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+#[repr(u8)]
+pub enum Kind {
+    #[default]
+    Sample = 0,
+    /// Dark reference acquired with the light source blocked.
+    Dark = 1,
+    /// White reference acquired from a diffuse reflectance standard e.g. Spectralon.
+    White = 2,
+    Other = 3,
+}
+
+/* ----------------------------------------------------------------------- Trait Implementations */
+
+impl From<u8> for Kind {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => Kind::Sample,
+            1 => Kind::Dark,
+            2 => Kind::White,
+            _ => Kind::Other,
+        }
+    }
+}
+
+impl From<Kind> for u8 {
+    fn from(kind: Kind) -> Self {
+        kind as u8
+    }
+}
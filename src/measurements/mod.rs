@@ -11,50 +11,140 @@ modification, are permitted provided that the conditions of the LICENSE are met.
 /* ----------------------------------------------------------------------------- Private Modules */
 
 mod builder;
+mod extra;
+mod kind;
+mod record;
 
 /* ----------------------------------------------------------------------------- Private Imports */
 
-use std::fs::{File, OpenOptions};
+use std::collections::HashSet;
+use std::fs::File;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, LazyLock};
+use std::time::SystemTime;
 
 use arrow::array::RecordBatch;
-use arrow::datatypes::DataType::{Duration, Float64, Timestamp, UInt32};
+use arrow::datatypes::DataType::{Duration, Float64, Timestamp, UInt8, UInt32};
 use arrow::datatypes::TimeUnit::Microsecond;
 use arrow::datatypes::{Field, Schema};
+use arrow::ipc::reader::StreamReader;
 use arrow::ipc::writer::StreamWriter;
 use uom::si::f64::{Length, Time};
+#[cfg(all(feature = "x", feature = "y"))]
+use uom::si::length::micrometer;
 
 use self::builder::*;
-use crate::{Error, Writer};
+#[cfg(feature = "hdf5")]
+pub(crate) use self::extra::UNIT;
+pub use self::extra::{Extra, Value};
+pub use self::kind::Kind;
+pub use self::record::Record;
+#[cfg(all(feature = "x", feature = "y"))]
+use crate::spatial::index::Index;
+use crate::writer::open_stream_writer;
+use crate::{Error, Reader, Writer};
 
 /* ------------------------------------------------------------------------------ Public Exports */
 
 pub struct Measurements {
     stream: StreamWriter<File>,
     builder: Builder,
+    /// Standard columns followed by the user-defined columns.
+    schema: Arc<Schema>,
+    extras: Vec<Extra>,
+    /// Positions of every measurement, committed or not, for spatial queries.
+    #[cfg(all(feature = "x", feature = "y"))]
+    index: Index,
+    pub path: PathBuf,
 }
 
 impl Measurements {
-    pub(super) fn new<P>(path: P) -> Result<Self, Error>
+    /// Open the `measurements` table with the user-defined columns it was created with. New
+    /// tables are created with `extras`, or none. Fails if `extras` differ from the columns of an
+    /// existing table.
+    pub(super) fn new<P>(path: P, extras: Option<&[Extra]>) -> Result<Self, Error>
     where
         P: AsRef<Path>,
     {
-        path.as_ref()
-            .join("measurements")
-            .with_extension("arrow")
-            .try_into()
+        let path = path.as_ref().join("measurements").with_extension("arrow");
+        let base = Self::schema();
+        let persisted = File::open(&path)
+            .ok()
+            .and_then(|file| StreamReader::try_new(file, None).ok())
+            .map(|reader| reader.schema());
+        let extras = match (persisted, extras) {
+            (Some(schema), declared) => {
+                let persisted = schema
+                    .fields()
+                    .iter()
+                    .skip(base.fields().len())
+                    .map(|field| Extra::from_field(field))
+                    .collect::<Result<Vec<Extra>, Error>>()?;
+                if declared.is_some_and(|declared| declared != persisted) {
+                    return Err(Error::InvalidParameter(
+                        "Extra columns differ from those the database was created with".into(),
+                    ));
+                }
+                persisted
+            }
+            (None, declared) => declared.unwrap_or_default().to_vec(),
+        };
+        let mut names: HashSet<&str> = base.fields().iter().map(|f| f.name().as_str()).collect();
+        if let Some(extra) = extras.iter().find(|extra| !names.insert(extra.name())) {
+            return Err(Error::InvalidName(extra.name().to_string()));
+        }
+        let fields = base
+            .fields()
+            .iter()
+            .cloned()
+            .chain(extras.iter().map(|extra| extra.field().into()));
+        let schema = Arc::new(Schema::new(fields.collect::<Vec<_>>()));
+        let stream = open_stream_writer(&path, &schema)?;
+        let builder = Builder::new(&path, &extras);
+        #[cfg_attr(not(all(feature = "x", feature = "y")), allow(unused_mut))]
+        let mut measurements = Self {
+            stream,
+            builder,
+            schema,
+            extras,
+            #[cfg(all(feature = "x", feature = "y"))]
+            index: Index::new(CELL),
+            path,
+        };
+        #[cfg(all(feature = "x", feature = "y"))]
+        for record in measurements.read()? {
+            measurements.index.insert(record.id, record.position());
+        }
+        Ok(measurements)
     }
 
+    /// User-defined columns in the order their values are pushed.
+    pub fn extras(&self) -> &[Extra] {
+        &self.extras
+    }
+
+    /// Schema of the table including the user-defined columns.
+    pub(crate) fn table_schema(&self) -> Arc<Schema> {
+        self.schema.clone()
+    }
+
+    /// Push a measurement acquired now and return its ID. `extra` holds values of user-defined
+    /// columns by name. Columns without a value are null. Fails without pushing anything if a
+    /// name is not a user-defined column or a value has the wrong type.
+    #[allow(clippy::too_many_arguments)] // One positional argument per enabled axis feature
     pub fn push(
         &mut self,
+        kind: Kind,
         #[cfg(feature = "x")] x: Length,
         #[cfg(feature = "y")] y: Length,
         #[cfg(feature = "z")] z: Length,
         #[cfg(feature = "a")] a: Length,
         i: Time,
-    ) -> u32 {
-        self.builder.push(
+        extra: &[(&str, Value)],
+    ) -> Result<u32, Error> {
+        self.push_at(
+            SystemTime::now(),
+            kind,
             #[cfg(feature = "x")]
             x,
             #[cfg(feature = "y")]
@@ -64,13 +154,152 @@ impl Measurements {
             #[cfg(feature = "a")]
             a,
             i,
+            extra,
         )
     }
 
+    /// Push a measurement that was acquired at a known `timestamp` e.g. when importing
+    /// historical data. [`Measurements::push`] uses the current system time instead.
+    #[allow(clippy::too_many_arguments)] // One positional argument per enabled axis feature
+    pub fn push_at(
+        &mut self,
+        timestamp: SystemTime,
+        kind: Kind,
+        #[cfg(feature = "x")] x: Length,
+        #[cfg(feature = "y")] y: Length,
+        #[cfg(feature = "z")] z: Length,
+        #[cfg(feature = "a")] a: Length,
+        i: Time,
+        extra: &[(&str, Value)],
+    ) -> Result<u32, Error> {
+        let id = self.builder.push(
+            timestamp,
+            kind,
+            #[cfg(feature = "x")]
+            x,
+            #[cfg(feature = "y")]
+            y,
+            #[cfg(feature = "z")]
+            z,
+            #[cfg(feature = "a")]
+            a,
+            i,
+            extra,
+        )?;
+        #[cfg(all(feature = "x", feature = "y"))]
+        self.index.insert(
+            id,
+            point(
+                x,
+                y,
+                #[cfg(feature = "z")]
+                z,
+            ),
+        );
+        Ok(id)
+    }
+
+    /// Session attached to every measurement pushed since the [`crate::Database`] was opened.
+    /// Describe it with [`crate::Database::sessions`].
+    pub fn session(&self) -> u32 {
+        self.builder.context.session
+    }
+
+    pub(crate) fn set_session(&mut self, session: u32) {
+        self.builder.context.session = session;
+    }
+
+    /// Sample attached to every measurement pushed from now on. Create samples with
+    /// [`crate::Database::samples`].
+    pub fn set_sample(&mut self, sample: Option<u32>) {
+        self.builder.context.sample = sample;
+    }
+
+    pub fn sample(&self) -> Option<u32> {
+        self.builder.context.sample
+    }
+
+    /// Instrument configuration attached to every measurement pushed from now on. Set it with
+    /// [`crate::Database::set_instrument`], which checks that the configuration is registered.
+    pub(crate) fn set_instrument(&mut self, instrument: Option<u32>) {
+        self.builder.context.instrument = instrument;
+    }
+
+    pub fn instrument(&self) -> Option<u32> {
+        self.builder.context.instrument
+    }
+
+    /// IDs of the `count` measurements nearest to a position, nearest first. Uncommitted
+    /// measurements are included.
+    #[cfg(all(feature = "x", feature = "y"))]
+    pub fn nearest(
+        &self,
+        count: usize,
+        x: Length,
+        y: Length,
+        #[cfg(feature = "z")] z: Length,
+    ) -> Vec<u32> {
+        let target = point(
+            x,
+            y,
+            #[cfg(feature = "z")]
+            z,
+        );
+        self.index
+            .nearest(target, count)
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect()
+    }
+
+    /// IDs of every measurement within `radius` of a position, nearest first. Uncommitted
+    /// measurements are included.
+    #[cfg(all(feature = "x", feature = "y"))]
+    pub fn within(
+        &self,
+        radius: Length,
+        x: Length,
+        y: Length,
+        #[cfg(feature = "z")] z: Length,
+    ) -> Vec<u32> {
+        let target = point(
+            x,
+            y,
+            #[cfg(feature = "z")]
+            z,
+        );
+        self.index
+            .within(target, radius.get::<micrometer>())
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect()
+    }
+
+    /// IDs of every measurement whose `x`/`y` position lies inside the polygon with `vertices`,
+    /// in ascending order. Uncommitted measurements are included.
+    #[cfg(all(feature = "x", feature = "y"))]
+    pub fn inside(&self, vertices: &[(Length, Length)]) -> Vec<u32> {
+        let polygon: Vec<[f64; 2]> = vertices
+            .iter()
+            .map(|(x, y)| [x.get::<micrometer>(), y.get::<micrometer>()])
+            .collect();
+        self.index.inside(&polygon)
+    }
+
+    /// Read every committed measurement.
+    pub fn read(&self) -> Result<Vec<Record>, Error> {
+        let records = self
+            .batches()?
+            .iter()
+            .flat_map(Record::from_batch)
+            .collect();
+        Ok(records)
+    }
+
     pub fn commit(&mut self) -> Result<(), Error> {
         let columns = self.builder.columns();
-        let batch = RecordBatch::try_new(Self::schema(), columns)?;
-        self.stream.write(&batch).map_err(Error::from)
+        let batch = RecordBatch::try_new(self.table_schema(), columns)?;
+        self.write(&batch)
     }
 }
 
@@ -81,6 +310,7 @@ impl Writer for Measurements {
         let fields = [
             Field::new("id", UInt32, false).into(),
             Field::new("timestamp", Timestamp(Microsecond, None), false).into(),
+            Field::new("kind", UInt8, false).into(),
             #[cfg(feature = "x")]
             Field::new("x", Float64, false).into(),
             #[cfg(feature = "y")]
@@ -90,24 +320,172 @@ impl Writer for Measurements {
             #[cfg(feature = "a")]
             Field::new("a", Float64, false).into(),
             Field::new("integration", Duration(Microsecond), false).into(),
+            Field::new("session", UInt32, false).into(),
+            Field::new("sample", UInt32, true).into(),
+            Field::new("instrument", UInt32, true).into(),
         ];
         Schema::new(fields).into()
     });
+
+    fn stream(&mut self) -> &mut StreamWriter<File> {
+        &mut self.stream
+    }
+
+    fn write(&mut self, batch: &RecordBatch) -> Result<(), Error> {
+        self.builder.advance(batch); // Never reuse IDs from externally written batches
+        #[cfg(all(feature = "x", feature = "y"))]
+        for record in Record::from_batch(batch) {
+            self.index.insert(record.id, record.position());
+        }
+        self.stream.write(batch).map_err(Error::from)
+    }
 }
 
-impl TryFrom<PathBuf> for Measurements {
-    type Error = Error;
-
-    fn try_from(path: PathBuf) -> Result<Self, Self::Error> {
-        let file = OpenOptions::new()
-            .read(false)
-            .write(true)
-            .create(true)
-            .truncate(false)
-            .open(&path)?;
-        let stream = Self::new_stream_writer(file)?;
-        let builder = Builder::new(&path);
-        let db = Self { stream, builder };
-        Ok(db)
+impl Reader for Measurements {
+    fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+/* ----------------------------------------------------------------------------- Private Helpers */
+
+/// Initial side of the index cells in micrometres. Cells adapt to the scan as it grows.
+#[cfg(all(feature = "x", feature = "y"))]
+const CELL: f64 = 1.0;
+
+/// Position in micrometres as indexed. Planar scans lie at `z = 0`.
+#[cfg(all(feature = "x", feature = "y"))]
+fn point(x: Length, y: Length, #[cfg(feature = "z")] z: Length) -> [f64; 3] {
+    #[cfg(not(feature = "z"))]
+    let z = Length::default();
+    [x, y, z].map(|length| length.get::<micrometer>())
+}
+
+/* ---------------------------------------------------------------------------------- Unit Tests */
+
+#[cfg(all(
+    test,
+    feature = "x",
+    feature = "y",
+    not(feature = "z"),
+    not(feature = "a")
+))]
+mod tests {
+    use std::fs::remove_dir_all;
+
+    use uom::si::time::millisecond;
+
+    use super::*;
+    use crate::Database;
+
+    #[test]
+    fn spatial_queries() {
+        const PATH: &str = "test-measurements";
+        let um = Length::new::<micrometer>;
+        let integration = Time::new::<millisecond>(10.0);
+        let mut db = Database::new(PATH).unwrap();
+        let ids: Vec<u32> = (0..25)
+            .map(|i| {
+                let (x, y) = ((i % 5) as f64 * 10.0, (i / 5) as f64 * 10.0);
+                db.measurements
+                    .push(Kind::Sample, um(x), um(y), integration, &[])
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(db.measurements.nearest(1, um(21.0), um(9.0)), vec![ids[7]]);
+        db.measurements.commit().unwrap();
+        drop(db);
+
+        let db = Database::new(PATH).unwrap(); // The index is rebuilt on open
+        let nearest = db.measurements.nearest(3, um(0.0), um(0.0));
+        assert_eq!(nearest, vec![ids[0], ids[1], ids[5]]);
+        let mut within = db.measurements.within(um(10.5), um(20.0), um(20.0));
+        assert_eq!(within.remove(0), ids[12]);
+        within.sort_unstable();
+        assert_eq!(within, vec![ids[7], ids[11], ids[13], ids[17]]);
+        let triangle = [
+            (um(-1.0), um(-1.0)),
+            (um(25.0), um(-1.0)),
+            (um(-1.0), um(25.0)),
+        ];
+        let inside = db.measurements.inside(&triangle);
+        assert_eq!(
+            inside,
+            vec![ids[0], ids[1], ids[2], ids[5], ids[6], ids[10]]
+        );
+        remove_dir_all(PATH).unwrap();
+    }
+
+    #[test]
+    fn extra_columns() {
+        const PATH: &str = "test-extra-columns";
+        let um = Length::new::<micrometer>;
+        let integration = Time::new::<millisecond>(10.0);
+        let extras = [
+            Extra::Float {
+                name: "temperature".into(),
+                unit: "degC".into(),
+            },
+            Extra::Integer {
+                name: "frame".into(),
+            },
+            Extra::Text {
+                name: "note".into(),
+            },
+            Extra::Boolean {
+                name: "shutter".into(),
+            },
+        ];
+        let mut db = Database::with_columns(PATH, &extras).unwrap();
+        let values = [
+            ("temperature", Value::Float(21.5)),
+            ("frame", Value::Integer(7)),
+            ("note", Value::Text("edge".into())),
+            ("shutter", Value::Boolean(true)),
+        ];
+        let full = db
+            .measurements
+            .push(Kind::Sample, um(0.0), um(0.0), integration, &values)
+            .unwrap();
+        let unknown = [("humidity", Value::Float(0.4))];
+        let mistyped = [
+            ("temperature", Value::Float(20.0)),
+            ("frame", Value::Boolean(false)),
+        ];
+        let push = |db: &mut Database, extra| {
+            db.measurements
+                .push(Kind::Sample, um(1.0), um(1.0), integration, extra)
+        };
+        assert!(matches!(
+            push(&mut db, &unknown),
+            Err(Error::InvalidName(_))
+        ));
+        assert!(matches!(
+            push(&mut db, &mistyped),
+            Err(Error::InvalidParameter(_))
+        ));
+        let partial = db
+            .measurements
+            .push(Kind::Dark, um(0.0), um(0.0), integration, &values[..1])
+            .unwrap();
+        db.measurements.commit().unwrap();
+        drop(db);
+
+        let duplicate = [extras[0].clone(), extras[0].clone()];
+        assert!(Database::with_columns("test-extra-duplicate", &duplicate).is_err());
+        assert!(Database::with_columns(PATH, &extras[..1]).is_err());
+        let db = Database::new(PATH).unwrap(); // Columns are read from the table
+        assert_eq!(db.measurements.extras(), extras);
+        let records = db.measurements.read().unwrap();
+        assert_eq!(records.len(), 2); // Rejected pushes leave nothing behind
+        assert_eq!(records[full as usize].extra.len(), 4);
+        assert_eq!(
+            records[full as usize].extra["note"],
+            Value::Text("edge".into())
+        );
+        let partial = &records[partial as usize].extra;
+        assert_eq!(partial.keys().collect::<Vec<_>>(), vec!["temperature"]);
+        remove_dir_all(PATH).unwrap();
+        remove_dir_all("test-extra-duplicate").unwrap();
     }
 }
@@ -0,0 +1,138 @@
+/*
+Project: Optic
+GitHub: https://github.com/MillieFD/optic
+
+BSD 3-Clause License, Copyright (c) 2026, Amelia Fraser-Dale
+
+Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the conditions of the LICENSE are met.
+*/
+
+/* ----------------------------------------------------------------------------- Private Imports */
+
+use std::collections::BTreeMap;
+use std::time::{Duration, SystemTime};
+
+use arrow::array::{Array, ArrayRef, AsArray, RecordBatch};
+use arrow::datatypes::{
+    DurationMicrosecondType,
+    Float64Type,
+    TimestampMicrosecondType,
+    UInt8Type,
+    UInt32Type,
+};
+#[cfg(any(feature = "x", feature = "y", feature = "z", feature = "a"))]
+use uom::si::f64::Length;
+use uom::si::f64::Time;
+#[cfg(any(feature = "x", feature = "y", feature = "z", feature = "a"))]
+use uom::si::length::micrometer;
+use uom::si::time::microsecond;
+
+use super::extra::Value;
+use super::{Kind, Measurements};
+use crate::Writer;
+
+/* ------------------------------------------------------------------------------ Public Exports */
+
+/// A single committed row of the `measurements` table.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Record {
+    pub id: u32,
+    pub timestamp: SystemTime,
+    pub kind: Kind,
+    #[cfg(feature = "x")]
+    pub x: Length,
+    #[cfg(feature = "y")]
+    pub y: Length,
+    #[cfg(feature = "z")]
+    pub z: Length,
+    #[cfg(feature = "a")]
+    pub a: Length,
+    pub integration: Time,
+    /// Session during which the measurement was pushed.
+    pub session: u32,
+    /// Sample that was measured, if one was selected.
+    pub sample: Option<u32>,
+    /// Configuration of the instrument used, if one was selected.
+    pub instrument: Option<u32>,
+    /// Values of the user-defined columns keyed by column name. Null values are omitted.
+    pub extra: BTreeMap<String, Value>,
+}
+
+impl Record {
+    /// Find the ID of the `kind` reference nearest in time with the same integration time.
+    pub(crate) fn nearest(&self, records: &[Record], kind: Kind) -> Option<u32> {
+        records
+            .iter()
+            .filter(|record| record.kind == kind && record.integration == self.integration)
+            .min_by_key(|record| {
+                record
+                    .timestamp
+                    .duration_since(self.timestamp)
+                    .unwrap_or_else(|e| e.duration())
+            })
+            .map(|record| record.id)
+    }
+
+    /// Position in micrometres as indexed by [`super::Measurements`].
+    #[cfg(all(feature = "x", feature = "y"))]
+    pub(super) fn position(&self) -> [f64; 3] {
+        super::point(
+            self.x,
+            self.y,
+            #[cfg(feature = "z")]
+            self.z,
+        )
+    }
+
+    pub(super) fn from_batch(batch: &RecordBatch) -> impl Iterator<Item = Self> + '_ {
+        let column = |name: &str| {
+            batch
+                .column_by_name(name)
+                .unwrap_or_else(|| panic!("Unable to read '{name}' column"))
+        };
+        let id = column("id").as_primitive::<UInt32Type>();
+        let timestamp = column("timestamp").as_primitive::<TimestampMicrosecondType>();
+        let kind = column("kind").as_primitive::<UInt8Type>();
+        #[cfg(feature = "x")]
+        let x = column("x").as_primitive::<Float64Type>();
+        #[cfg(feature = "y")]
+        let y = column("y").as_primitive::<Float64Type>();
+        #[cfg(feature = "z")]
+        let z = column("z").as_primitive::<Float64Type>();
+        #[cfg(feature = "a")]
+        let a = column("a").as_primitive::<Float64Type>();
+        let integration = column("integration").as_primitive::<DurationMicrosecondType>();
+        let session = column("session").as_primitive::<UInt32Type>();
+        let sample = column("sample").as_primitive::<UInt32Type>();
+        let instrument = column("instrument").as_primitive::<UInt32Type>();
+        let base = Measurements::schema().fields().len();
+        let extra: Vec<(String, ArrayRef)> = batch.schema().fields()[base..]
+            .iter()
+            .map(|field| field.name().clone())
+            .zip(batch.columns()[base..].iter().cloned())
+            .collect();
+        (0..batch.num_rows()).map(move |row| Self {
+            id: id.value(row),
+            timestamp: SystemTime::UNIX_EPOCH
+                + Duration::from_micros(timestamp.value(row).max(0) as u64),
+            kind: kind.value(row).into(),
+            #[cfg(feature = "x")]
+            x: Length::new::<micrometer>(x.value(row)),
+            #[cfg(feature = "y")]
+            y: Length::new::<micrometer>(y.value(row)),
+            #[cfg(feature = "z")]
+            z: Length::new::<micrometer>(z.value(row)),
+            #[cfg(feature = "a")]
+            a: Length::new::<micrometer>(a.value(row)),
+            integration: Time::new::<microsecond>(integration.value(row) as f64),
+            session: session.value(row),
+            sample: sample.is_valid(row).then(|| sample.value(row)),
+            instrument: instrument.is_valid(row).then(|| instrument.value(row)),
+            extra: extra
+                .iter()
+                .filter_map(|(name, array)| Some((name.clone(), Value::at(array, row)?)))
+                .collect(),
+        })
+    }
+}
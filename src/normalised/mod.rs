@@ -0,0 +1,172 @@
+/*
+Project: Optic
+GitHub: https://github.com/MillieFD/optic
+
+BSD 3-Clause License, Copyright (c) 2026, Amelia Fraser-Dale
+
+Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the conditions of the LICENSE are met.
+*/
+
+/* ----------------------------------------------------------------------------- Private Imports */
+
+use std::collections::{HashMap, HashSet};
+
+use uom::si::time::second;
+
+use crate::intensities::Spectrum;
+use crate::measurements::Kind;
+use crate::{Database, Error, Provenance};
+
+/* ------------------------------------------------------------------------------ Public Exports */
+
+/// Dark spectrum subtracted from each measurement before normalisation.
+pub enum Dark {
+    None,
+    /// Use the dark reference nearest in time with the same integration time.
+    Nearest,
+    /// Use an explicit dark measurement ID for each measurement ID.
+    Linked(HashMap<u32, u32>),
+}
+
+impl Database {
+    /// Read the spectra of `measurements` in counts per second, dividing raw counts by the
+    /// stored integration time after optional dark subtraction. Every requested spectrum is read
+    /// and normalised in memory when called and nothing is written to disk. Fails if a
+    /// measurement has a zero integration time.
+    pub fn counts_per_second(
+        &self,
+        measurements: &[u32],
+        dark: &Dark,
+    ) -> Result<HashMap<u32, Spectrum>, Error> {
+        let records = self.measurements.read()?;
+        let wanted: HashSet<&u32> = measurements.iter().collect();
+        let darks: HashMap<u32, u32> = match dark {
+            Dark::None => HashMap::new(),
+            Dark::Linked(links) => links.clone(),
+            Dark::Nearest => records
+                .iter()
+                .filter(|record| wanted.contains(&record.id))
+                .map(|record| {
+                    let dark = record.nearest(&records, Kind::Dark);
+                    dark.map(|dark| (record.id, dark))
+                        .ok_or(Error::MissingReference(record.id))
+                })
+                .collect::<Result<_, _>>()?,
+        };
+        let ids: Vec<u32> = measurements.iter().chain(darks.values()).copied().collect();
+        let spectra = self.intensities.spectra(&ids)?;
+        records
+            .iter()
+            .filter(|record| wanted.contains(&record.id))
+            .filter_map(|record| Some((record, spectra.get(&record.id)?)))
+            .map(|(record, spectrum)| {
+                let dark = match darks.get(&record.id) {
+                    Some(id) => Some(spectra.get(id).ok_or(Error::MissingReference(record.id))?),
+                    None if matches!(dark, Dark::None) => None,
+                    None => return Err(Error::MissingReference(record.id)),
+                };
+                let seconds = record.integration.get::<second>();
+                if seconds <= 0.0 {
+                    return Err(Error::InvalidParameter(format!(
+                        "Measurement {} has no integration time",
+                        record.id
+                    )));
+                }
+                let normalised = spectrum
+                    .iter()
+                    .filter_map(|(wavelength, counts)| {
+                        let offset = match dark {
+                            Some(dark) => *dark.get(wavelength)?,
+                            None => 0.0,
+                        };
+                        Some((*wavelength, (counts - offset) / seconds))
+                    })
+                    .collect();
+                Ok((record.id, normalised))
+            })
+            .collect()
+    }
+
+    /// Materialise [`Database::counts_per_second`] to the derived table called `name`.
+    pub fn normalise(&self, name: &str, measurements: &[u32], dark: &Dark) -> Result<(), Error> {
+        let mut spectra: Vec<_> = self
+            .counts_per_second(measurements, dark)?
+            .into_iter()
+            .collect();
+        spectra.sort_unstable_by_key(|(id, _)| *id);
+        let method = match dark {
+            Dark::None => "none",
+            Dark::Nearest => "nearest",
+            Dark::Linked(_) => "linked",
+        };
+        let provenance = Provenance::new("counts_per_second", &["measurements", "intensities"])
+            .parameter("dark", method);
+        let mut table = self.create_derived(name, &provenance)?;
+        for (id, spectrum) in spectra {
+            let (wavelengths, values): (Vec<u32>, Vec<f64>) = spectrum.into_iter().unzip();
+            table.push(id, &wavelengths, values);
+        }
+        table.commit()
+    }
+}
+
+/* ---------------------------------------------------------------------------------- Unit Tests */
+
+#[cfg(all(
+    test,
+    feature = "x",
+    feature = "y",
+    not(feature = "z"),
+    not(feature = "a")
+))]
+mod tests {
+    use std::fs::remove_dir_all;
+
+    use uom::si::f64::{Length, Time};
+    use uom::si::length::micrometer;
+    use uom::si::time::millisecond;
+
+    use super::*;
+
+    #[test]
+    fn dark_subtracted_counts_per_second() {
+        const PATH: &str = "test-normalised";
+        let mut db = Database::new(PATH).unwrap();
+        let wavelengths = db.wavelengths.push(vec![400.0, 500.0]).unwrap();
+        let origin = Length::new::<micrometer>(0.0);
+        let mut push = |kind, ms, counts| {
+            let integration = Time::new::<millisecond>(ms);
+            let id = db
+                .measurements
+                .push(kind, origin, origin, integration, &[])
+                .unwrap();
+            db.intensities.push(id, &wavelengths, counts);
+            id
+        };
+        push(Kind::Dark, 250.0, vec![10.0, 20.0]);
+        let short = push(Kind::Sample, 250.0, vec![110.0, 220.0]);
+        let long = push(Kind::Sample, 500.0, vec![500.0, 1000.0]);
+        let instant = push(Kind::Sample, 0.0, vec![1.0, 2.0]);
+        db.measurements.commit().unwrap();
+        db.intensities.commit().unwrap();
+
+        let raw = db.counts_per_second(&[short, long], &Dark::None).unwrap();
+        assert_eq!(
+            raw[&long].values().copied().collect::<Vec<_>>(),
+            vec![1000.0, 2000.0]
+        );
+        let dark = db.counts_per_second(&[short], &Dark::Nearest).unwrap();
+        assert_eq!(
+            dark[&short].values().copied().collect::<Vec<_>>(),
+            vec![400.0, 800.0]
+        );
+        assert!(db.counts_per_second(&[long], &Dark::Nearest).is_err()); // No matching dark
+        let result = db.counts_per_second(&[instant], &Dark::None);
+        assert!(matches!(result, Err(Error::InvalidParameter(_))));
+
+        db.normalise("cps", &[short, long], &Dark::None).unwrap();
+        assert_eq!(db.read_derived("cps").unwrap(), raw);
+        remove_dir_all(PATH).unwrap();
+    }
+}
@@ -0,0 +1,46 @@
+/*
+Project: Optic
+GitHub: https://github.com/MillieFD/optic
+
+BSD 3-Clause License, Copyright (c) 2026, Amelia Fraser-Dale
+
+Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the conditions of the LICENSE are met.
+*/
+
+/* ------------------------------------------------------------------------------ Public Exports */
+
+/// Total diffuse reflectance of a semi-infinite turbid medium under the diffusion approximation
+/// (Farrell, Patterson & Wilson 1992) as a function of the reduced albedo `μs' / (μa + μs')`.
+pub(super) fn reflectance(albedo: f64, refractive_index: f64) -> f64 {
+    let a = boundary(refractive_index);
+    let root = (3.0 * (1.0 - albedo)).sqrt();
+    albedo / 2.0 * (-root).exp() * (1.0 + (-4.0 / 3.0 * a * root).exp())
+}
+
+/// Invert [`reflectance`] for the reduced albedo by bisection, in the spirit of inverse
+/// adding-doubling. Returns `NaN` when `r` lies outside the range the model can produce.
+pub(super) fn albedo(r: f64, refractive_index: f64) -> f64 {
+    const ITERATIONS: usize = 60;
+    if !(0.0..=reflectance(1.0, refractive_index)).contains(&r) {
+        return f64::NAN;
+    }
+    let (mut lower, mut upper) = (0.0, 1.0);
+    for _ in 0..ITERATIONS {
+        let middle = (lower + upper) / 2.0;
+        match reflectance(middle, refractive_index) < r {
+            true => lower = middle,
+            false => upper = middle,
+        }
+    }
+    (lower + upper) / 2.0
+}
+
+/* ----------------------------------------------------------------------------- Private Helpers */
+
+/// Internal reflection parameter `A = (1 + rᵢ) / (1 − rᵢ)` using the empirical internal diffuse
+/// reflection `rᵢ` of Groenhuis et al. for a relative refractive index `n`.
+fn boundary(n: f64) -> f64 {
+    let internal = -1.440 / (n * n) + 0.710 / n + 0.668 + 0.0636 * n;
+    (1.0 + internal) / (1.0 - internal)
+}
@@ -0,0 +1,276 @@
+/*
+Project: Optic
+GitHub: https://github.com/MillieFD/optic
+
+BSD 3-Clause License, Copyright (c) 2026, Amelia Fraser-Dale
+
+Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the conditions of the LICENSE are met.
+*/
+
+/* ----------------------------------------------------------------------------- Private Modules */
+
+mod diffusion;
+
+/* ----------------------------------------------------------------------------- Private Imports */
+
+use std::collections::{BTreeMap, HashMap};
+
+use uom::si::f64::{Length, ReciprocalLength};
+use uom::si::length::nanometer;
+use uom::si::reciprocal_length::reciprocal_millimeter;
+
+use crate::intensities::Spectrum;
+use crate::{Database, Derived, Error, Provenance, Source};
+
+/* ------------------------------------------------------------------------------ Public Exports */
+
+/// Dimensionless transform applied to each reflectance value.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Transform {
+    /// Kubelka–Munk remission function `F(R) = (1 − R)² / 2R`, equal to the ratio `K / S`.
+    KubelkaMunk,
+    /// Pseudo-absorbance `log10(1 / R)`.
+    Absorbance,
+}
+
+/// Prior on the reduced scattering spectrum `μs'(λ) = μs'(λ₀) · (λ / λ₀)^−b` used to separate
+/// absorption from scattering when only diffuse reflectance is measured.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Scattering {
+    /// Reduced scattering coefficient `μs'(λ₀)` at the reference wavelength.
+    pub coefficient: ReciprocalLength,
+    /// Reference wavelength `λ₀`.
+    pub reference: Length,
+    /// Power-law exponent `b`.
+    pub exponent: f64,
+    /// Refractive index of the medium relative to its surroundings.
+    pub refractive_index: f64,
+}
+
+impl Scattering {
+    fn at(&self, wavelength: Length) -> ReciprocalLength {
+        let ratio: f64 = (wavelength / self.reference).value;
+        self.coefficient * ratio.powf(-self.exponent)
+    }
+}
+
+/// Absorption and reduced scattering coefficients at a single wavelength.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Coefficients {
+    pub absorption: ReciprocalLength,
+    pub scattering: ReciprocalLength,
+}
+
+impl Database {
+    /// Apply `transform` to the reflectance spectra of `measurements` from `source`. Nothing is
+    /// written to disk. Values outside the domain of the transform (`R ≤ 0`) are dropped.
+    pub fn transformed(
+        &self,
+        source: Source,
+        measurements: &[u32],
+        transform: Transform,
+    ) -> Result<HashMap<u32, Spectrum>, Error> {
+        let function: fn(f64) -> f64 = match transform {
+            Transform::KubelkaMunk => |r: f64| (1.0 - r).powi(2) / (2.0 * r),
+            Transform::Absorbance => |r: f64| -r.log10(),
+        };
+        let spectra = self
+            .spectra(source, measurements)?
+            .into_iter()
+            .map(|(id, spectrum)| {
+                let spectrum = spectrum
+                    .into_iter()
+                    .filter(|(_, r)| *r > 0.0)
+                    .map(|(wavelength, r)| (wavelength, function(r)))
+                    .collect();
+                (id, spectrum)
+            })
+            .collect();
+        Ok(spectra)
+    }
+
+    /// Apply `transform` as [`Database::transformed`] and write the result to the derived table
+    /// called `name`. Returns the IDs of the processed measurements.
+    pub fn transform(
+        &self,
+        name: &str,
+        source: Source,
+        measurements: &[u32],
+        transform: Transform,
+    ) -> Result<Vec<u32>, Error> {
+        let spectra = self.transformed(source, measurements, transform)?;
+        let operation = match transform {
+            Transform::KubelkaMunk => "kubelka_munk",
+            Transform::Absorbance => "absorbance",
+        };
+        let provenance = Provenance::new(operation, &[source.name()]);
+        write(&mut self.create_derived(name, &provenance)?, spectra)
+    }
+
+    /// Estimate absorption and reduced scattering coefficients from the reflectance spectra of
+    /// `measurements`. The reduced albedo at each wavelength is found by inverting a diffusion
+    /// model of a semi-infinite medium, then split into `μa` and `μs'` using the `scattering`
+    /// prior. Wavelengths where the model cannot reproduce the measured reflectance are dropped.
+    pub fn optical_properties(
+        &self,
+        source: Source,
+        measurements: &[u32],
+        scattering: &Scattering,
+    ) -> Result<HashMap<u32, BTreeMap<u32, Coefficients>>, Error> {
+        let lookup = self.wavelengths.lookup()?;
+        self.spectra(source, measurements)?
+            .into_iter()
+            .map(|(id, spectrum)| {
+                let coefficients = spectrum
+                    .into_iter()
+                    .map(|(wavelength, r)| {
+                        let nm = *lookup
+                            .get(&wavelength)
+                            .ok_or(Error::MissingWavelength(wavelength))?;
+                        let albedo = diffusion::albedo(r, scattering.refractive_index);
+                        let reduced = scattering.at(nm);
+                        let coefficients = Coefficients {
+                            absorption: reduced * ((1.0 - albedo) / albedo),
+                            scattering: reduced,
+                        };
+                        Ok((wavelength, coefficients))
+                    })
+                    .filter(|result| {
+                        result.as_ref().map_or(true, |(_, coefficients)| {
+                            coefficients.absorption.value.is_finite()
+                        })
+                    })
+                    .collect::<Result<_, Error>>()?;
+                Ok((id, coefficients))
+            })
+            .collect()
+    }
+
+    /// Estimate coefficients as [`Database::optical_properties`] and write `μa` and `μs'` in mm⁻¹
+    /// to the derived tables called `absorption` and `scattering`.
+    pub fn estimate_optical_properties(
+        &self,
+        absorption: &str,
+        scattering: &str,
+        source: Source,
+        measurements: &[u32],
+        prior: &Scattering,
+    ) -> Result<Vec<u32>, Error> {
+        let properties = self.optical_properties(source, measurements, prior)?;
+        let provenance = |coefficient: &str| {
+            Provenance::new("optical_properties", &[source.name(), "wavelengths"])
+                .parameter("coefficient", coefficient)
+                .parameter("units", "mm^-1")
+                .parameter("model", "diffusion")
+                .parameter(
+                    "scattering_mm^-1",
+                    prior.coefficient.get::<reciprocal_millimeter>(),
+                )
+                .parameter("reference_nm", prior.reference.get::<nanometer>())
+                .parameter("exponent", prior.exponent)
+                .parameter("refractive_index", prior.refractive_index)
+        };
+        let select = |f: fn(&Coefficients) -> ReciprocalLength| {
+            properties
+                .iter()
+                .map(|(id, coefficients)| {
+                    let spectrum = coefficients
+                        .iter()
+                        .map(|(wavelength, c)| (*wavelength, f(c).get::<reciprocal_millimeter>()))
+                        .collect();
+                    (*id, spectrum)
+                })
+                .collect()
+        };
+        let tables = [
+            (absorption.to_string(), provenance("absorption")),
+            (scattering.to_string(), provenance("scattering")),
+        ];
+        let mut tables = self.create_derived_all(&tables)?;
+        write(&mut tables[0], select(|c| c.absorption))?;
+        write(&mut tables[1], select(|c| c.scattering))
+    }
+}
+
+/* ----------------------------------------------------------------------------- Private Helpers */
+
+/// Write `spectra` to `table` in ascending measurement order and commit them.
+fn write(table: &mut Derived, spectra: HashMap<u32, Spectrum>) -> Result<Vec<u32>, Error> {
+    let mut spectra: Vec<_> = spectra.into_iter().collect();
+    spectra.sort_unstable_by_key(|(id, _)| *id);
+    for (id, spectrum) in &spectra {
+        let (wavelengths, values): (Vec<u32>, Vec<f64>) = spectrum.iter().unzip();
+        table.push(*id, &wavelengths, values);
+    }
+    table.commit()?;
+    Ok(spectra.into_iter().map(|(id, _)| id).collect())
+}
+
+/* ---------------------------------------------------------------------------------- Unit Tests */
+
+#[cfg(all(
+    test,
+    feature = "x",
+    feature = "y",
+    not(feature = "z"),
+    not(feature = "a")
+))]
+mod tests {
+    use std::fs::remove_dir_all;
+
+    use uom::si::f64::Time;
+    use uom::si::length::micrometer;
+    use uom::si::time::millisecond;
+
+    use super::*;
+    use crate::Kind;
+
+    #[test]
+    fn diffusion_inversion() {
+        for albedo in [0.5, 0.9, 0.99] {
+            let r = diffusion::reflectance(albedo, 1.4);
+            assert!((diffusion::albedo(r, 1.4) - albedo).abs() < 1E-9);
+        }
+        assert!(diffusion::albedo(1.5, 1.4).is_nan());
+    }
+
+    #[test]
+    fn reflectance_transforms() {
+        const PATH: &str = "test-optical";
+        let mut db = Database::new(PATH).unwrap();
+        let wavelengths = db.wavelengths.push(vec![500.0, 600.0]).unwrap();
+        db.wavelengths.commit().unwrap();
+        let origin = Length::new::<micrometer>(0.0);
+        let integration = Time::new::<millisecond>(10.0);
+        let id = db
+            .measurements
+            .push(Kind::Sample, origin, origin, integration, &[])
+            .unwrap();
+        db.intensities.push(id, &wavelengths, vec![0.5, 0.1]);
+        db.measurements.commit().unwrap();
+        db.intensities.commit().unwrap();
+
+        db.transform("km", Source::Intensities, &[id], Transform::KubelkaMunk)
+            .unwrap();
+        let km = &db.read_derived("km").unwrap()[&id];
+        assert_eq!(km[&wavelengths[0]], 0.25);
+        assert!((km[&wavelengths[1]] - 4.05).abs() < 1E-12);
+
+        let prior = Scattering {
+            coefficient: ReciprocalLength::new::<reciprocal_millimeter>(1.0),
+            reference: Length::new::<nanometer>(500.0),
+            exponent: 1.0,
+            refractive_index: 1.4,
+        };
+        db.estimate_optical_properties("mua", "mus", Source::Intensities, &[id], &prior)
+            .unwrap();
+        let mua = &db.read_derived("mua").unwrap()[&id];
+        let mus = &db.read_derived("mus").unwrap()[&id];
+        assert!((mus[&wavelengths[1]] - 500.0 / 600.0).abs() < 1E-9);
+        assert!(mua[&wavelengths[1]] > mua[&wavelengths[0]]); // Darker means more absorbing
+        let albedo = mus[&wavelengths[0]] / (mus[&wavelengths[0]] + mua[&wavelengths[0]]);
+        assert!((diffusion::reflectance(albedo, 1.4) - 0.5).abs() < 1E-9);
+        remove_dir_all(PATH).unwrap();
+    }
+}
@@ -0,0 +1,470 @@
+/*
+Project: Optic
+GitHub: https://github.com/MillieFD/optic
+
+BSD 3-Clause License, Copyright (c) 2026, Amelia Fraser-Dale
+
+Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the conditions of the LICENSE are met.
+*/
+
+/* ----------------------------------------------------------------------------- Private Imports */
+
+use std::collections::HashSet;
+use std::fs::{DirBuilder, OpenOptions, remove_file};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock};
+
+use arrow::array::{
+    ArrayRef,
+    AsArray,
+    BooleanArray,
+    DurationMicrosecondArray,
+    Float64Array,
+    RecordBatch,
+    UInt8Array,
+};
+use arrow::datatypes::DataType::{Boolean, Duration, Float64, UInt8};
+use arrow::datatypes::TimeUnit::Microsecond;
+use arrow::datatypes::{
+    DurationMicrosecondType,
+    Field,
+    Float64Type,
+    Schema,
+    UInt8Type,
+    UInt32Type,
+};
+use uom::si::f64::{Length, Time};
+use uom::si::length::micrometer;
+use uom::si::time::microsecond;
+
+use crate::derived::{Table, list};
+use crate::spatial::index::Index;
+use crate::writer::new_stream_writer;
+use crate::{Database, Derived, Error, Kind, Reader};
+
+/* ------------------------------------------------------------------------------ Public Exports */
+
+/// Which axis a raster scan steps along first.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum Order {
+    /// Acquire each row of constant `y` before moving to the next.
+    #[default]
+    Rows,
+    /// Acquire each column of constant `x` before moving to the next.
+    Columns,
+}
+
+/// Intended raster scan, stored in the `plans` directory.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Plan {
+    /// First and last `x` positions of the raster.
+    pub x: (Length, Length),
+    /// First and last `y` positions of the raster.
+    pub y: (Length, Length),
+    /// Distance between neighbouring points along `x` and `y`. Both must be positive.
+    pub step: (Length, Length),
+    pub order: Order,
+    /// Reverse every other row (or column) so the stage never returns to the start of a line.
+    pub serpentine: bool,
+    /// Integration time of every point.
+    pub integration: Time,
+}
+
+impl Plan {
+    /// Every point of the raster in acquisition order. Lines run from the first towards the last
+    /// position in whole steps, so the last position is omitted unless it lies on a step.
+    pub fn points(&self) -> Vec<(Length, Length)> {
+        let xs = line(self.x, self.step.0);
+        let ys = line(self.y, self.step.1);
+        let (outer, inner) = match self.order {
+            Order::Rows => (&ys, &xs),
+            Order::Columns => (&xs, &ys),
+        };
+        let mut points = Vec::with_capacity(xs.len() * ys.len());
+        for (i, a) in outer.iter().enumerate() {
+            let reversed = self.serpentine && i % 2 == 1;
+            let mut line: Vec<f64> = inner.clone();
+            if reversed {
+                line.reverse();
+            }
+            for b in line {
+                let (x, y) = match self.order {
+                    Order::Rows => (b, *a),
+                    Order::Columns => (*a, b),
+                };
+                points.push((Length::new::<micrometer>(x), Length::new::<micrometer>(y)));
+            }
+        }
+        points
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        let positive = |step: Length| step.get::<micrometer>() > 0.0;
+        let finite = [self.x.0, self.x.1, self.y.0, self.y.1]
+            .iter()
+            .all(|p| p.get::<micrometer>().is_finite());
+        match positive(self.step.0) && positive(self.step.1) && finite {
+            true => Ok(()),
+            false => Err(Error::InvalidParameter(
+                "Scan plans need finite extents and positive steps".into(),
+            )),
+        }
+    }
+}
+
+impl Database {
+    fn plans_directory(&self) -> PathBuf {
+        self.path.join("plans")
+    }
+
+    fn regions_directory(&self) -> PathBuf {
+        self.path.join("regions")
+    }
+
+    /// Store `plan` under `name`. Names follow the same rules as [`Database::create_derived`].
+    /// Fails if a plan with the same name already exists.
+    pub fn store_plan(&self, name: &str, plan: &Plan) -> Result<(), Error> {
+        plan.validate()?;
+        let um = |length: Length| Arc::new(Float64Array::from(vec![length.get::<micrometer>()]));
+        let order = match plan.order {
+            Order::Rows => 0,
+            Order::Columns => 1,
+        };
+        let integration = plan.integration.get::<microsecond>() as i64;
+        let columns: Vec<ArrayRef> = vec![
+            um(plan.x.0),
+            um(plan.x.1),
+            um(plan.step.0),
+            um(plan.y.0),
+            um(plan.y.1),
+            um(plan.step.1),
+            Arc::new(UInt8Array::from(vec![order])),
+            Arc::new(BooleanArray::from(vec![plan.serpentine])),
+            Arc::new(DurationMicrosecondArray::from(vec![integration])),
+        ];
+        let batch = RecordBatch::try_new(PLAN.clone(), columns)?;
+        store(&self.plans_directory(), name, &batch)
+    }
+
+    /// List the names of every scan plan in alphabetical order.
+    pub fn plans(&self) -> Result<Vec<String>, Error> {
+        list(&self.plans_directory())
+    }
+
+    /// Read the scan plan called `name`.
+    pub fn read_plan(&self, name: &str) -> Result<Plan, Error> {
+        let path = Derived::locate(&self.plans_directory(), name)?;
+        let batch = Table(path)
+            .batches()?
+            .into_iter()
+            .find(|batch| batch.num_rows() > 0)
+            .ok_or_else(|| Error::ParseError(format!("Scan plan '{name}' is empty")))?;
+        let um = |column: usize| {
+            let value = batch.column(column).as_primitive::<Float64Type>().value(0);
+            Length::new::<micrometer>(value)
+        };
+        let order = match batch.column(6).as_primitive::<UInt8Type>().value(0) {
+            0 => Order::Rows,
+            1 => Order::Columns,
+            other => return Err(Error::ParseError(format!("Scan order {other}"))),
+        };
+        let integration = batch
+            .column(8)
+            .as_primitive::<DurationMicrosecondType>()
+            .value(0);
+        Ok(Plan {
+            x: (um(0), um(1)),
+            y: (um(3), um(4)),
+            step: (um(2), um(5)),
+            order,
+            serpentine: batch.column(7).as_boolean().value(0),
+            integration: Time::new::<microsecond>(integration as f64),
+        })
+    }
+
+    /// Delete the scan plan called `name`. Measurements acquired with it are not affected.
+    pub fn delete_plan(&self, name: &str) -> Result<(), Error> {
+        let path = Derived::locate(&self.plans_directory(), name)?;
+        remove_file(path).map_err(Error::from)
+    }
+
+    /// Points of the scan plan called `name` without a committed [`Kind::Sample`] measurement
+    /// with a committed spectrum within `tolerance` of their `x`/`y` position, in acquisition
+    /// order. After an interrupted scan, reopen the database and acquire these points to complete
+    /// it: new measurements are appended to the existing tables.
+    pub fn remaining(&self, name: &str, tolerance: Length) -> Result<Vec<(Length, Length)>, Error> {
+        let plan = self.read_plan(name)?;
+        let tolerance = tolerance.get::<micrometer>();
+        let mut spectra = HashSet::new();
+        for batch in self.intensities.reader()? {
+            let ids = batch?.column(0).as_primitive::<UInt32Type>().clone();
+            spectra.extend(ids.values().iter().copied());
+        }
+        let mut acquired = Index::new(plan.step.0.min(plan.step.1).get::<micrometer>());
+        for record in self.measurements.read()? {
+            if record.kind == Kind::Sample && spectra.contains(&record.id) {
+                let position = [record.x, record.y, Length::default()];
+                acquired.insert(record.id, position.map(|p| p.get::<micrometer>()));
+            }
+        }
+        Ok(plan
+            .points()
+            .into_iter()
+            .filter(|(x, y)| {
+                let target = [x.get::<micrometer>(), y.get::<micrometer>(), 0.0];
+                acquired.within(target, tolerance).is_empty()
+            })
+            .collect())
+    }
+
+    /// Store the region of interest with `vertices` in stage coordinates under `name`. The
+    /// polygon closes from the last vertex back to the first. Names follow the same rules as
+    /// [`Database::create_derived`]. Fails if a region with the same name already exists.
+    pub fn store_region(&self, name: &str, vertices: &[(Length, Length)]) -> Result<(), Error> {
+        if vertices.len() < 3 {
+            return Err(Error::InvalidParameter(
+                "Regions need at least three vertices".into(),
+            ));
+        }
+        let (x, y): (Vec<f64>, Vec<f64>) = vertices
+            .iter()
+            .map(|(x, y)| (x.get::<micrometer>(), y.get::<micrometer>()))
+            .unzip();
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(Float64Array::from(x)),
+            Arc::new(Float64Array::from(y)),
+        ];
+        let batch = RecordBatch::try_new(REGION.clone(), columns)?;
+        store(&self.regions_directory(), name, &batch)
+    }
+
+    /// List the names of every region of interest in alphabetical order.
+    pub fn regions(&self) -> Result<Vec<String>, Error> {
+        list(&self.regions_directory())
+    }
+
+    /// Read the vertices of the region of interest called `name`.
+    pub fn read_region(&self, name: &str) -> Result<Vec<(Length, Length)>, Error> {
+        let path = Derived::locate(&self.regions_directory(), name)?;
+        let mut vertices = Vec::new();
+        for batch in Table(path).batches()? {
+            let x = batch.column(0).as_primitive::<Float64Type>().values();
+            let y = batch.column(1).as_primitive::<Float64Type>().values();
+            x.iter()
+                .zip(y)
+                .map(|(x, y)| (Length::new::<micrometer>(*x), Length::new::<micrometer>(*y)))
+                .collect_into(&mut vertices);
+        }
+        Ok(vertices)
+    }
+
+    /// Delete the region of interest called `name`.
+    pub fn delete_region(&self, name: &str) -> Result<(), Error> {
+        let path = Derived::locate(&self.regions_directory(), name)?;
+        remove_file(path).map_err(Error::from)
+    }
+
+    /// IDs of every measurement inside the region of interest called `name`, in ascending order.
+    pub fn in_region(&self, name: &str) -> Result<Vec<u32>, Error> {
+        let vertices = self.read_region(name)?;
+        Ok(self.measurements.inside(&vertices))
+    }
+}
+
+/* ----------------------------------------------------------------------------- Private Helpers */
+
+/// Positions are rounded to whole steps within this fraction of a step.
+const TOLERANCE: f64 = 1E-6;
+
+static PLAN: LazyLock<Arc<Schema>> = LazyLock::new(|| {
+    Arc::new(Schema::new(vec![
+        Field::new("x_start", Float64, false),
+        Field::new("x_end", Float64, false),
+        Field::new("x_step", Float64, false),
+        Field::new("y_start", Float64, false),
+        Field::new("y_end", Float64, false),
+        Field::new("y_step", Float64, false),
+        Field::new("order", UInt8, false),
+        Field::new("serpentine", Boolean, false),
+        Field::new("integration", Duration(Microsecond), false),
+    ]))
+});
+
+static REGION: LazyLock<Arc<Schema>> = LazyLock::new(|| {
+    Arc::new(Schema::new(vec![
+        Field::new("x", Float64, false),
+        Field::new("y", Float64, false),
+    ]))
+});
+
+/// Positions in micrometres from `first` towards `last` in whole steps.
+fn line((first, last): (Length, Length), step: Length) -> Vec<f64> {
+    let (first, last) = (first.get::<micrometer>(), last.get::<micrometer>());
+    let step = step.get::<micrometer>().copysign(last - first);
+    let count = ((last - first) / step + TOLERANCE).floor() as usize + 1;
+    (0..count).map(|i| first + i as f64 * step).collect()
+}
+
+/// Write `batch` to a new table called `name` in `directory`.
+fn store(directory: &Path, name: &str, batch: &RecordBatch) -> Result<(), Error> {
+    let path = Derived::locate(directory, name)?;
+    DirBuilder::new().recursive(true).create(directory)?;
+    let file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&path)?;
+    let mut stream = new_stream_writer(file, &batch.schema())?;
+    stream.write(batch)?;
+    stream.finish().map_err(Error::from)
+}
+
+/* ---------------------------------------------------------------------------------- Unit Tests */
+
+#[cfg(all(
+    test,
+    feature = "x",
+    feature = "y",
+    not(feature = "z"),
+    not(feature = "a")
+))]
+mod tests {
+    use std::fs::remove_dir_all;
+
+    use uom::si::time::millisecond;
+
+    use super::*;
+
+    #[test]
+    fn plans_and_regions() {
+        const PATH: &str = "test-plan";
+        let mut db = Database::new(PATH).unwrap();
+        let um = Length::new::<micrometer>;
+        let plan = Plan {
+            x: (um(0.0), um(20.0)),
+            y: (um(10.0), um(0.0)),
+            step: (um(10.0), um(10.0)),
+            order: Order::Rows,
+            serpentine: true,
+            integration: Time::new::<millisecond>(10.0),
+        };
+        db.store_plan("raster", &plan).unwrap();
+        assert!(db.store_plan("raster", &plan).is_err());
+        assert_eq!(db.plans().unwrap(), vec!["raster"]);
+        let stored = db.read_plan("raster").unwrap();
+        assert_eq!(stored, plan);
+        let points: Vec<(f64, f64)> = stored
+            .points()
+            .into_iter()
+            .map(|(x, y)| (x.get::<micrometer>(), y.get::<micrometer>()))
+            .collect();
+        let expected = [(0, 10), (10, 10), (20, 10), (20, 0), (10, 0), (0, 0)];
+        assert_eq!(points.len(), expected.len());
+        for ((x, y), (ex, ey)) in points.iter().zip(expected) {
+            assert!((x - ex as f64).abs() < 1E-9 && (y - ey as f64).abs() < 1E-9);
+        }
+        let invalid = Plan {
+            step: (um(0.0), um(10.0)),
+            ..plan
+        };
+        assert!(db.store_plan("invalid", &invalid).is_err());
+
+        let ids: Vec<u32> = plan
+            .points()
+            .into_iter()
+            .map(|(x, y)| {
+                db.measurements
+                    .push(Kind::Sample, x, y, plan.integration, &[])
+                    .unwrap()
+            })
+            .collect();
+        db.measurements.commit().unwrap();
+        let square = [
+            (um(5.0), um(-5.0)),
+            (um(25.0), um(-5.0)),
+            (um(25.0), um(15.0)),
+            (um(5.0), um(15.0)),
+        ];
+        db.store_region("right", &square).unwrap();
+        assert_eq!(db.regions().unwrap(), vec!["right"]);
+        assert_eq!(db.read_region("right").unwrap().len(), 4);
+        assert_eq!(
+            db.in_region("right").unwrap(),
+            vec![ids[1], ids[2], ids[3], ids[4]]
+        );
+        assert!(db.store_region("line", &square[..2]).is_err());
+        db.delete_region("right").unwrap();
+        db.delete_plan("raster").unwrap();
+        assert!(db.regions().unwrap().is_empty() && db.plans().unwrap().is_empty());
+        remove_dir_all(PATH).unwrap();
+    }
+
+    #[test]
+    fn resume_scan() {
+        const PATH: &str = "test-resume";
+        let um = Length::new::<micrometer>;
+        let plan = Plan {
+            x: (um(0.0), um(30.0)),
+            y: (um(0.0), um(10.0)),
+            step: (um(10.0), um(10.0)),
+            order: Order::Rows,
+            serpentine: false,
+            integration: Time::new::<millisecond>(10.0),
+        };
+        let tolerance = um(1.0);
+        let acquire = |db: &mut Database, points: &[(Length, Length)]| {
+            let wavelengths = db.wavelengths.push(vec![500.0, 600.0]).unwrap();
+            db.wavelengths.commit().unwrap();
+            for (x, y) in points {
+                let id = db
+                    .measurements
+                    .push(Kind::Sample, *x, *y, plan.integration, &[])
+                    .unwrap();
+                db.intensities.push(id, &wavelengths, vec![id as f64; 2]);
+            }
+            db.measurements.commit().unwrap();
+            db.intensities.commit().unwrap();
+        };
+        let mut db = Database::new(PATH).unwrap();
+        db.store_plan("raster", &plan).unwrap();
+        let points = db.remaining("raster", tolerance).unwrap();
+        assert_eq!(points, plan.points());
+        acquire(&mut db, &points[..3]);
+        db.measurements
+            .push(
+                Kind::Sample,
+                points[3].0,
+                points[3].1,
+                plan.integration,
+                &[],
+            )
+            .unwrap();
+        db.measurements.commit().unwrap(); // Without a spectrum
+        db.measurements
+            .push(Kind::Sample, um(0.0), um(10.0), plan.integration, &[])
+            .unwrap(); // Never committed
+        drop(db);
+
+        // Simulate a crash part way through writing a batch
+        let measurements = PathBuf::from(PATH).join("measurements.arrow");
+        let mut file = OpenOptions::new().append(true).open(&measurements).unwrap();
+        std::io::Write::write_all(&mut file, &[0xFF, 0xFF, 0xFF, 0xFF, 0x40, 0, 0, 0, 1, 2])
+            .unwrap();
+
+        let mut db = Database::new(PATH).unwrap();
+        let remaining = db.remaining("raster", tolerance).unwrap();
+        assert_eq!(remaining, plan.points()[3..]);
+        acquire(&mut db, &remaining);
+        drop(db);
+
+        let db = Database::new(PATH).unwrap();
+        assert!(db.remaining("raster", tolerance).unwrap().is_empty());
+        let records = db.measurements.read().unwrap();
+        let ids: Vec<u32> = records.iter().map(|record| record.id).collect();
+        assert_eq!(ids, (0..9).collect::<Vec<u32>>()); // Including the one without a spectrum
+        let intensities = db.intensities.spectra(&ids).unwrap();
+        assert_eq!(intensities.len(), 8);
+        assert_eq!(db.wavelengths.lookup().unwrap().len(), 2);
+        remove_dir_all(PATH).unwrap();
+    }
+}
@@ -0,0 +1,157 @@
+/*
+Project: Optic
+GitHub: https://github.com/MillieFD/optic
+
+BSD 3-Clause License, Copyright (c) 2026, Amelia Fraser-Dale
+
+Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the conditions of the LICENSE are met.
+*/
+
+/* ----------------------------------------------------------------------------- Private Imports */
+
+use crate::Error;
+use crate::analysis::linalg::solve;
+
+/* ------------------------------------------------------------------------------ Public Exports */
+
+/// Savitzky–Golay filter evaluated at every sample. A polynomial of degree `order` is fitted by
+/// least squares to the `window` samples centred on each point (shifted inwards at the edges)
+/// using the actual wavelengths, so uneven spacing is handled. Derivatives are per nanometre.
+/// Fails unless the spectrum spans at least two distinct wavelengths.
+pub(super) fn savitzky_golay(
+    nm: &[f64],
+    values: &[f64],
+    window: usize,
+    order: usize,
+    derivative: usize,
+) -> Result<Vec<f64>, Error> {
+    let n = values.len();
+    if window > n {
+        let e = format!("window of {window} exceeds spectrum of {n} samples");
+        return Err(Error::InvalidParameter(e));
+    }
+    let spacing = match n {
+        0 | 1 => 0.0,
+        _ => (nm[n - 1] - nm[0]) / (n - 1) as f64,
+    };
+    if !spacing.is_normal() || spacing < 0.0 {
+        let e = format!("Savitzky–Golay needs increasing wavelengths, found {n} samples");
+        return Err(Error::InvalidParameter(e));
+    }
+    let scale =
+        (1..=derivative).map(|k| k as f64).product::<f64>() / spacing.powi(derivative as i32);
+    let smoothed = (0..n)
+        .map(|i| {
+            let start = i.saturating_sub(window / 2).min(n - window);
+            let range = start..start + window;
+            let t: Vec<f64> = nm[range.clone()]
+                .iter()
+                .map(|x| (x - nm[i]) / spacing)
+                .collect();
+            polyfit(&t, &values[range], order).map_or(f64::NAN, |c| c[derivative] * scale)
+        })
+        .collect();
+    Ok(smoothed)
+}
+
+/// Standard normal variate: centre on the mean and divide by the sample standard deviation of
+/// the finite values. A spectrum with fewer than two finite values or no variation becomes `NaN`.
+pub(super) fn snv(values: &mut [f64]) {
+    let finite: Vec<f64> = values.iter().copied().filter(|v| v.is_finite()).collect();
+    let n = finite.len() as f64;
+    let mean = finite.iter().sum::<f64>() / n;
+    let variance = finite.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    let deviation = variance.sqrt();
+    match finite.len() >= 2 && deviation.is_normal() {
+        true => values.iter_mut().for_each(|v| *v = (*v - mean) / deviation),
+        false => values.fill(f64::NAN),
+    }
+}
+
+/// Multiplicative scatter correction: regress `values = a + b·reference` and return
+/// `(values − a) / b`. Only samples where both are finite contribute to the fit. A spectrum
+/// whose fit is undefined, such as one with fewer than two samples or a zero slope, becomes
+/// `NaN`.
+pub(super) fn msc(values: &mut [f64], reference: &[f64]) {
+    let pairs: Vec<(f64, f64)> = reference
+        .iter()
+        .zip(values.iter())
+        .map(|(r, v)| (*r, *v))
+        .filter(|(r, v)| r.is_finite() && v.is_finite())
+        .collect();
+    let n = pairs.len() as f64;
+    let mean_r = pairs.iter().map(|(r, _)| r).sum::<f64>() / n;
+    let mean_v = pairs.iter().map(|(_, v)| v).sum::<f64>() / n;
+    let covariance: f64 = pairs.iter().map(|(r, v)| (r - mean_r) * (v - mean_v)).sum();
+    let variance: f64 = pairs.iter().map(|(r, _)| (r - mean_r).powi(2)).sum();
+    let slope = covariance / variance;
+    let intercept = mean_v - slope * mean_r;
+    match pairs.len() >= 2 && slope.is_normal() {
+        true => values
+            .iter_mut()
+            .for_each(|v| *v = (*v - intercept) / slope),
+        false => values.fill(f64::NAN),
+    }
+}
+
+/// Divide by the upper convex hull of the spectrum so that the continuum becomes `1`.
+pub(super) fn continuum_removal(nm: &[f64], values: &mut [f64]) {
+    let mut hull: Vec<(f64, f64)> = Vec::new();
+    let points = nm.iter().copied().zip(values.iter().copied());
+    for point in points.filter(|(_, v)| v.is_finite()) {
+        while let [.., a, b] = hull[..] {
+            let cross = (b.0 - a.0) * (point.1 - a.1) - (b.1 - a.1) * (point.0 - a.0);
+            match cross >= 0.0 {
+                true => hull.pop(), // `b` lies on or below the chord from `a` to `point`
+                false => break,
+            };
+        }
+        hull.push(point);
+    }
+    if hull.is_empty() {
+        return;
+    }
+    for (x, v) in nm.iter().zip(values.iter_mut()) {
+        let index = hull.partition_point(|&(hx, _)| hx < *x).min(hull.len() - 1);
+        let continuum = match index {
+            0 => hull[0].1,
+            i => {
+                let ((x0, y0), (x1, y1)) = (hull[i - 1], hull[i]);
+                y0 + (y1 - y0) * (x - x0) / (x1 - x0)
+            }
+        };
+        *v /= continuum;
+    }
+}
+
+/// Convert reflectance to pseudo-absorbance `log10(1 / R)`. Non-positive values become `NaN`.
+pub(super) fn absorbance(values: &mut [f64]) {
+    values.iter_mut().for_each(|v| {
+        *v = match *v > 0.0 {
+            true => -v.log10(),
+            false => f64::NAN,
+        }
+    });
+}
+
+/* ----------------------------------------------------------------------------- Private Helpers */
+
+/// Least-squares polynomial coefficients `c₀ + c₁t + … + cₖtᵏ` via the normal equations, or
+/// `None` if they are singular.
+fn polyfit(t: &[f64], y: &[f64], order: usize) -> Option<Vec<f64>> {
+    let size = order + 1;
+    let mut matrix = vec![vec![0.0; size]; size];
+    let mut rhs = vec![vec![0.0]; size];
+    for (ti, yi) in t.iter().zip(y) {
+        let powers: Vec<f64> = (0..size).map(|k| ti.powi(k as i32)).collect();
+        for row in 0..size {
+            for column in 0..size {
+                matrix[row][column] += powers[row] * powers[column];
+            }
+            rhs[row][0] += powers[row] * yi;
+        }
+    }
+    let solution = solve(matrix, rhs)?;
+    Some(solution.into_iter().map(|row| row[0]).collect())
+}
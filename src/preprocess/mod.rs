@@ -0,0 +1,367 @@
+/*
+Project: Optic
+GitHub: https://github.com/MillieFD/optic
+
+BSD 3-Clause License, Copyright (c) 2026, Amelia Fraser-Dale
+
+Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the conditions of the LICENSE are met.
+*/
+
+/* ----------------------------------------------------------------------------- Private Modules */
+
+mod filters;
+
+/* ----------------------------------------------------------------------------- Private Imports */
+
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+use uom::si::f64::Length;
+use uom::si::length::nanometer;
+
+use crate::intensities::Spectrum;
+use crate::{Database, Error, Provenance, Source};
+
+/* ------------------------------------------------------------------------------ Public Exports */
+
+/// A single preprocessing operation applied to every spectrum in a [`Pipeline`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Step {
+    /// Savitzky–Golay smoothing with an odd `window` of samples and polynomial `order`. A
+    /// non-zero `derivative` returns that derivative per nanometre instead of the smoothed value.
+    SavitzkyGolay {
+        window: usize,
+        order: usize,
+        derivative: usize,
+    },
+    /// Standard normal variate.
+    Snv,
+    /// Multiplicative scatter correction against the mean of the selected spectra.
+    Msc,
+    /// Divide by the upper convex hull of each spectrum.
+    ContinuumRemoval,
+    /// Pseudo-absorbance `log10(1 / R)`.
+    Absorbance,
+}
+
+/// An ordered sequence of preprocessing [`Step`]s.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Pipeline {
+    pub steps: Vec<Step>,
+}
+
+impl Pipeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append `step` to the end of the pipeline.
+    pub fn then(mut self, step: Step) -> Self {
+        self.steps.push(step);
+        self
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        self.steps.iter().try_for_each(|step| match *step {
+            Step::SavitzkyGolay {
+                window,
+                order,
+                derivative,
+            } if window.is_multiple_of(2) || window <= order || derivative > order => {
+                let e = format!("invalid Savitzky–Golay parameters in '{step}'");
+                Err(Error::InvalidParameter(e))
+            }
+            _ => Ok(()),
+        })
+    }
+}
+
+impl Database {
+    /// Read the spectra of `measurements` from `source` and apply `pipeline` in order. Nothing is
+    /// written to disk. Values that become undefined, such as the absorbance of `R ≤ 0`, are
+    /// dropped from the returned spectra.
+    pub fn preprocessed(
+        &self,
+        source: Source,
+        measurements: &[u32],
+        pipeline: &Pipeline,
+    ) -> Result<HashMap<u32, Spectrum>, Error> {
+        pipeline.validate()?;
+        let lookup = self.wavelengths.lookup()?;
+        let mut series = self
+            .spectra(source, measurements)?
+            .iter()
+            .map(|(id, spectrum)| Ok((*id, Series::new(spectrum, &lookup)?)))
+            .collect::<Result<HashMap<_, _>, Error>>()?;
+        for step in &pipeline.steps {
+            apply(step, &mut series)?;
+        }
+        let spectra = series
+            .into_iter()
+            .map(|(id, series)| {
+                let spectrum = series
+                    .ids
+                    .into_iter()
+                    .zip(series.values)
+                    .filter(|(_, value)| value.is_finite())
+                    .collect();
+                (id, spectrum)
+            })
+            .collect();
+        Ok(spectra)
+    }
+
+    /// Apply `pipeline` as [`Database::preprocessed`] and write the result to the derived table
+    /// called `name`. Returns the IDs of the processed measurements.
+    pub fn preprocess(
+        &self,
+        name: &str,
+        source: Source,
+        measurements: &[u32],
+        pipeline: &Pipeline,
+    ) -> Result<Vec<u32>, Error> {
+        let mut spectra: Vec<_> = self
+            .preprocessed(source, measurements, pipeline)?
+            .into_iter()
+            .collect();
+        spectra.sort_unstable_by_key(|(id, _)| *id);
+        let steps: Vec<String> = pipeline.steps.iter().map(Step::to_string).collect();
+        let provenance = Provenance::new("preprocess", &[source.name(), "wavelengths"])
+            .parameter("steps", steps.join(";"));
+        let mut table = self.create_derived(name, &provenance)?;
+        for (id, spectrum) in &spectra {
+            let (wavelengths, values): (Vec<u32>, Vec<f64>) = spectrum.iter().unzip();
+            table.push(*id, &wavelengths, values);
+        }
+        table.commit()?;
+        Ok(spectra.into_iter().map(|(id, _)| id).collect())
+    }
+}
+
+/* ----------------------------------------------------------------------- Trait Implementations */
+
+impl Display for Step {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            Step::SavitzkyGolay {
+                window,
+                order,
+                derivative,
+            } => write!(
+                f,
+                "savitzky_golay(window={window},order={order},derivative={derivative})"
+            ),
+            Step::Snv => write!(f, "snv"),
+            Step::Msc => write!(f, "msc"),
+            Step::ContinuumRemoval => write!(f, "continuum_removal"),
+            Step::Absorbance => write!(f, "absorbance"),
+        }
+    }
+}
+
+/* ----------------------------------------------------------------------------- Private Helpers */
+
+/// A spectrum sorted by wavelength with parallel wavelength ID, nanometre and value columns.
+struct Series {
+    ids: Vec<u32>,
+    nm: Vec<f64>,
+    values: Vec<f64>,
+}
+
+impl Series {
+    fn new(spectrum: &Spectrum, lookup: &HashMap<u32, Length>) -> Result<Self, Error> {
+        let mut rows = spectrum
+            .iter()
+            .map(|(id, value)| match lookup.get(id) {
+                Some(wl) => Ok((*id, wl.get::<nanometer>(), *value)),
+                None => Err(Error::MissingWavelength(*id)),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        rows.sort_unstable_by(|a, b| a.1.total_cmp(&b.1));
+        let mut series = Self {
+            ids: Vec::with_capacity(rows.len()),
+            nm: Vec::with_capacity(rows.len()),
+            values: Vec::with_capacity(rows.len()),
+        };
+        for (id, nm, value) in rows {
+            series.ids.push(id);
+            series.nm.push(nm);
+            series.values.push(value);
+        }
+        Ok(series)
+    }
+}
+
+fn apply(step: &Step, series: &mut HashMap<u32, Series>) -> Result<(), Error> {
+    match *step {
+        Step::SavitzkyGolay {
+            window,
+            order,
+            derivative,
+        } => series.values_mut().try_for_each(|s| {
+            s.values = filters::savitzky_golay(&s.nm, &s.values, window, order, derivative)?;
+            Ok(())
+        }),
+        Step::Snv => {
+            series
+                .values_mut()
+                .for_each(|s| filters::snv(&mut s.values));
+            Ok(())
+        }
+        Step::Msc => {
+            let reference = mean(series);
+            series.values_mut().for_each(|s| {
+                let reference: Vec<f64> = s
+                    .ids
+                    .iter()
+                    .map(|id| reference.get(id).copied().unwrap_or(f64::NAN))
+                    .collect();
+                filters::msc(&mut s.values, &reference);
+            });
+            Ok(())
+        }
+        Step::ContinuumRemoval => {
+            series
+                .values_mut()
+                .for_each(|s| filters::continuum_removal(&s.nm, &mut s.values));
+            Ok(())
+        }
+        Step::Absorbance => {
+            series
+                .values_mut()
+                .for_each(|s| filters::absorbance(&mut s.values));
+            Ok(())
+        }
+    }
+}
+
+/// Mean of the finite values at each wavelength ID across every series.
+fn mean(series: &HashMap<u32, Series>) -> HashMap<u32, f64> {
+    let mut sums: HashMap<u32, (f64, f64)> = HashMap::new();
+    series
+        .values()
+        .flat_map(|s| s.ids.iter().zip(&s.values))
+        .filter(|(_, value)| value.is_finite())
+        .for_each(|(id, value)| {
+            let (sum, count) = sums.entry(*id).or_default();
+            *sum += value;
+            *count += 1.0;
+        });
+    sums.into_iter()
+        .map(|(id, (sum, count))| (id, sum / count))
+        .collect()
+}
+
+/* ---------------------------------------------------------------------------------- Unit Tests */
+
+#[cfg(all(
+    test,
+    feature = "x",
+    feature = "y",
+    not(feature = "z"),
+    not(feature = "a")
+))]
+mod tests {
+    use std::fs::remove_dir_all;
+
+    use uom::si::f64::Time;
+    use uom::si::length::micrometer;
+    use uom::si::time::millisecond;
+
+    use super::*;
+    use crate::Kind;
+
+    #[test]
+    fn filters() {
+        let nm: Vec<f64> = (0..9).map(|i| 400.0 + 5.0 * i as f64).collect();
+        let quadratic: Vec<f64> = nm.iter().map(|x| 0.01 * x * x - 3.0 * x).collect();
+        let smooth = filters::savitzky_golay(&nm, &quadratic, 5, 2, 0).unwrap();
+        let slope = filters::savitzky_golay(&nm, &quadratic, 5, 2, 1).unwrap();
+        for i in 0..nm.len() {
+            assert!((smooth[i] - quadratic[i]).abs() < 1E-6);
+            assert!((slope[i] - (0.02 * nm[i] - 3.0)).abs() < 1E-6);
+        }
+
+        let mut values = vec![1.0, 2.0, 3.0];
+        filters::snv(&mut values);
+        assert_eq!(values, vec![-1.0, 0.0, 1.0]);
+        let mut values = vec![1.0, f64::NAN, 2.0, 3.0];
+        filters::snv(&mut values);
+        assert_eq!([values[0], values[2], values[3]], [-1.0, 0.0, 1.0]); // NaN is ignored
+        for mut values in [vec![1.0], vec![2.0, 2.0], vec![2.0, f64::NAN]] {
+            filters::snv(&mut values);
+            assert!(values.iter().all(|v| v.is_nan()));
+        }
+        assert!(filters::savitzky_golay(&[400.0], &[1.0], 1, 0, 0).is_err());
+        assert!(filters::savitzky_golay(&[400.0; 3], &[1.0; 3], 3, 1, 0).is_err());
+
+        let mut values = vec![2.0, 1.0, 3.0, 3.0];
+        filters::continuum_removal(&[400.0, 410.0, 420.0, 430.0], &mut values);
+        assert_eq!(values, vec![1.0, 0.4, 1.0, 1.0]);
+
+        let mut values = vec![2.0, 4.0, 6.0];
+        filters::msc(&mut values, &[1.0, 2.0, 3.0]);
+        assert!(
+            values
+                .iter()
+                .zip([1.0, 2.0, 3.0])
+                .all(|(v, r)| (v - r).abs() < 1E-12)
+        );
+        let mut values = vec![2.0, 2.0, 2.0];
+        filters::msc(&mut values, &[1.0, 2.0, 3.0]); // Zero slope
+        assert!(values.iter().all(|v| v.is_nan()));
+    }
+
+    #[test]
+    fn pipeline_to_derived_table() {
+        const PATH: &str = "test-preprocess";
+        let mut db = Database::new(PATH).unwrap();
+        let wavelengths = db.wavelengths.push(vec![400.0, 410.0, 420.0]).unwrap();
+        db.wavelengths.commit().unwrap();
+        let origin = Length::new::<micrometer>(0.0);
+        let integration = Time::new::<millisecond>(10.0);
+        let id = db
+            .measurements
+            .push(Kind::Sample, origin, origin, integration, &[])
+            .unwrap();
+        db.intensities.push(id, &wavelengths, vec![0.1, 1.0, 0.0]);
+        let flat = db
+            .measurements
+            .push(Kind::Sample, origin, origin, integration, &[])
+            .unwrap();
+        db.intensities.push(flat, &wavelengths, vec![0.5; 3]);
+        db.measurements.commit().unwrap();
+        db.intensities.commit().unwrap();
+
+        let invalid = Pipeline::new().then(Step::SavitzkyGolay {
+            window: 4,
+            order: 2,
+            derivative: 0,
+        });
+        assert!(
+            db.preprocess("bad", Source::Intensities, &[id], &invalid)
+                .is_err()
+        );
+
+        let pipeline = Pipeline::new().then(Step::Absorbance);
+        let ids = db
+            .preprocess("absorbance", Source::Intensities, &[id], &pipeline)
+            .unwrap();
+        assert_eq!(ids, vec![id]);
+        let spectrum = &db.read_derived("absorbance").unwrap()[&id];
+        assert_eq!(spectrum.len(), 2); // log(1 / 0) is dropped
+        assert!((spectrum[&wavelengths[0]] - 1.0).abs() < 1E-12);
+        let provenance = db.provenance("absorbance").unwrap();
+        assert_eq!(provenance.parameters["steps"], "absorbance");
+
+        let pipeline = Pipeline::new().then(Step::Absorbance).then(Step::Snv);
+        let spectra = db
+            .preprocessed(Source::Intensities, &[id, flat], &pipeline)
+            .unwrap();
+        assert_eq!(spectra[&id].len(), 2); // SNV ignores the undefined absorbance
+        assert!((spectra[&id][&wavelengths[0]] - 0.5f64.sqrt()).abs() < 1E-12);
+        assert!(spectra[&flat].is_empty()); // A flat spectrum is dropped, not an error
+        remove_dir_all(PATH).unwrap();
+    }
+}
@@ -0,0 +1,313 @@
+/*
+Project: Optic
+GitHub: https://github.com/MillieFD/optic
+
+BSD 3-Clause License, Copyright (c) 2026, Amelia Fraser-Dale
+
+Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the conditions of the LICENSE are met.
+*/
+
+/* ----------------------------------------------------------------------------- Private Modules */
+
+mod noise;
+
+/* ----------------------------------------------------------------------------- Private Imports */
+
+use std::collections::{HashMap, HashSet};
+
+use uom::si::time::second;
+
+pub(crate) use self::noise::{median, noise, running_median, spikes};
+use crate::resample::curve;
+use crate::{Database, Error, Provenance};
+
+/* ------------------------------------------------------------------------------ Public Exports */
+
+/// Reason a spectrum fails quality control.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Flag {
+    /// At least one sample reached the detector maximum.
+    Saturated,
+    /// The peak signal in counts per second is below the minimum.
+    LowSignal,
+    /// The estimated signal-to-noise ratio is below the minimum.
+    LowSnr,
+    /// At least one spike such as a cosmic ray was detected.
+    Spikes,
+}
+
+/// Limits used to flag spectra.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Thresholds {
+    /// Raw counts at which the detector saturates.
+    pub saturation: f64,
+    /// Minimum peak signal in counts per second.
+    pub signal: f64,
+    /// Minimum signal-to-noise ratio.
+    pub snr: f64,
+    /// Residuals from a 5-sample running median beyond this many robust standard deviations are
+    /// counted as spikes.
+    pub spike_sigma: f64,
+}
+
+/// Quality metrics of one raw spectrum.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Quality {
+    /// Number of samples at or above the saturation level.
+    pub saturated: usize,
+    /// Peak signal in counts per second.
+    pub signal: f64,
+    /// Signal-to-noise ratio estimated by DER_SNR.
+    pub snr: f64,
+    /// Number of detected spikes.
+    pub spikes: usize,
+    pub flags: HashSet<Flag>,
+}
+
+impl Default for Thresholds {
+    /// A 16-bit detector with no signal or SNR minimum and a spike threshold of 6σ.
+    fn default() -> Self {
+        Self {
+            saturation: u16::MAX as f64,
+            signal: 0.0,
+            snr: 0.0,
+            spike_sigma: 6.0,
+        }
+    }
+}
+
+impl Database {
+    /// Compute quality metrics from the raw `intensities` and integration times of
+    /// `measurements`. Nothing is written to disk.
+    pub fn quality(
+        &self,
+        measurements: &[u32],
+        thresholds: &Thresholds,
+    ) -> Result<HashMap<u32, Quality>, Error> {
+        let lookup = self.wavelengths.lookup()?;
+        let spectra = self.intensities.spectra(measurements)?;
+        self.measurements
+            .read()?
+            .into_iter()
+            .filter_map(|record| Some((spectra.get(&record.id)?, record)))
+            .map(|(spectrum, record)| {
+                let values: Vec<f64> = curve(spectrum, &lookup)?
+                    .into_iter()
+                    .map(|(_, value)| value)
+                    .collect();
+                let peak = values.iter().copied().fold(f64::NAN, f64::max);
+                let quality = Quality {
+                    saturated: values
+                        .iter()
+                        .filter(|v| **v >= thresholds.saturation)
+                        .count(),
+                    signal: peak / record.integration.get::<second>(),
+                    snr: noise::snr(&values),
+                    spikes: spikes(&values, WINDOW, thresholds.spike_sigma).len(),
+                    flags: HashSet::new(),
+                };
+                Ok((record.id, quality.flagged(thresholds)))
+            })
+            .collect()
+    }
+
+    /// Compute [`Database::quality`] and write it to the scalar table called `name` with columns
+    /// `saturated`, `signal`, `snr`, `spikes` and one `0`/`1` column per [`Flag`]. Returns the
+    /// IDs of the assessed measurements.
+    pub fn assess_quality(
+        &self,
+        name: &str,
+        measurements: &[u32],
+        thresholds: &Thresholds,
+    ) -> Result<Vec<u32>, Error> {
+        let mut qualities: Vec<_> = self
+            .quality(measurements, thresholds)?
+            .into_iter()
+            .collect();
+        qualities.sort_unstable_by_key(|(id, _)| *id);
+        let provenance = Provenance::new("quality", &["intensities", "measurements"])
+            .parameter("saturation", thresholds.saturation)
+            .parameter("signal", thresholds.signal)
+            .parameter("snr", thresholds.snr)
+            .parameter("spike_sigma", thresholds.spike_sigma);
+        let columns: Vec<&str> = METRICS
+            .iter()
+            .chain(FLAGS.iter().map(|(_, c)| c))
+            .copied()
+            .collect();
+        let mut table = self.create_scalars(name, &columns, &provenance)?;
+        for (id, quality) in &qualities {
+            let metrics = [
+                quality.saturated as f64,
+                quality.signal,
+                quality.snr,
+                quality.spikes as f64,
+            ];
+            let flags = FLAGS.map(|(flag, _)| quality.flags.contains(&flag) as u8 as f64);
+            table.push(*id, &[metrics.as_slice(), flags.as_slice()].concat())?;
+        }
+        table.commit()?;
+        Ok(qualities.into_iter().map(|(id, _)| id).collect())
+    }
+
+    /// Read the quality table called `name` written by [`Database::assess_quality`].
+    pub fn read_quality(&self, name: &str) -> Result<HashMap<u32, Quality>, Error> {
+        let columns = self.read_scalars(name)?;
+        let column = |name: &str| {
+            columns
+                .values
+                .get(name)
+                .ok_or_else(|| Error::InvalidName(name.to_string()))
+        };
+        let [saturated, signal, snr, spikes] = METRICS.map(column);
+        let (saturated, signal, snr, spikes) = (saturated?, signal?, snr?, spikes?);
+        let flags = FLAGS
+            .iter()
+            .map(|(flag, name)| Ok((*flag, column(name)?)))
+            .collect::<Result<Vec<_>, Error>>()?;
+        Ok(columns
+            .measurements
+            .iter()
+            .enumerate()
+            .map(|(row, id)| {
+                let quality = Quality {
+                    saturated: saturated[row] as usize,
+                    signal: signal[row],
+                    snr: snr[row],
+                    spikes: spikes[row] as usize,
+                    flags: flags
+                        .iter()
+                        .filter(|(_, values)| values[row] != 0.0)
+                        .map(|(flag, _)| *flag)
+                        .collect(),
+                };
+                (*id, quality)
+            })
+            .collect())
+    }
+
+    /// Keep the `measurements` that the quality table called `name` assessed without any of the
+    /// `rejected` flags, in their original order.
+    pub fn passing(
+        &self,
+        name: &str,
+        measurements: &[u32],
+        rejected: &[Flag],
+    ) -> Result<Vec<u32>, Error> {
+        let qualities = self.read_quality(name)?;
+        Ok(measurements
+            .iter()
+            .copied()
+            .filter(|id| {
+                qualities
+                    .get(id)
+                    .is_some_and(|quality| !rejected.iter().any(|f| quality.flags.contains(f)))
+            })
+            .collect())
+    }
+}
+
+/* ----------------------------------------------------------------------------- Private Helpers */
+
+/// Running median window used for spike detection.
+pub(crate) const WINDOW: usize = 5;
+
+const METRICS: [&str; 4] = ["saturated", "signal", "snr", "spikes"];
+
+const FLAGS: [(Flag, &str); 4] = [
+    (Flag::Saturated, "flag_saturated"),
+    (Flag::LowSignal, "flag_low_signal"),
+    (Flag::LowSnr, "flag_low_snr"),
+    (Flag::Spikes, "flag_spikes"),
+];
+
+impl Quality {
+    fn flagged(mut self, thresholds: &Thresholds) -> Self {
+        let checks = [
+            (Flag::Saturated, self.saturated > 0),
+            (
+                Flag::LowSignal,
+                self.signal.is_nan() || self.signal < thresholds.signal,
+            ),
+            (Flag::LowSnr, self.snr < thresholds.snr),
+            (Flag::Spikes, self.spikes > 0),
+        ];
+        self.flags = checks
+            .into_iter()
+            .filter(|(_, failed)| *failed)
+            .map(|(flag, _)| flag)
+            .collect();
+        self
+    }
+}
+
+/* ---------------------------------------------------------------------------------- Unit Tests */
+
+#[cfg(all(
+    test,
+    feature = "x",
+    feature = "y",
+    not(feature = "z"),
+    not(feature = "a")
+))]
+mod tests {
+    use std::fs::remove_dir_all;
+
+    use uom::si::f64::{Length, Time};
+    use uom::si::length::micrometer;
+    use uom::si::time::millisecond;
+
+    use super::*;
+    use crate::Kind;
+
+    #[test]
+    fn flag_spectra() {
+        const PATH: &str = "test-quality";
+        let mut db = Database::new(PATH).unwrap();
+        let nm: Vec<f64> = (0..40).map(|i| 400.0 + i as f64).collect();
+        let wavelengths = db.wavelengths.push(nm).unwrap();
+        db.wavelengths.commit().unwrap();
+        let zero = Length::new::<micrometer>(0.0);
+        let integration = Time::new::<millisecond>(100.0);
+        let noise = |i: usize| ((i as f64 * 12.9898).sin() * 43758.5453).fract() * 5.0;
+        let clean: Vec<f64> = (0..40).map(|i| 1000.0 + noise(i)).collect();
+        let mut saturated = clean.clone();
+        saturated[10] = 65535.0;
+        let dim: Vec<f64> = clean.iter().map(|v| v / 100.0).collect();
+        let ids: Vec<u32> = [clean, saturated, dim]
+            .into_iter()
+            .map(|values| {
+                let id = db
+                    .measurements
+                    .push(Kind::Sample, zero, zero, integration, &[])
+                    .unwrap();
+                db.intensities.push(id, &wavelengths, values);
+                id
+            })
+            .collect();
+        db.measurements.commit().unwrap();
+        db.intensities.commit().unwrap();
+
+        let thresholds = Thresholds {
+            signal: 1000.0, // Counts per second
+            ..Thresholds::default()
+        };
+        let assessed = db.assess_quality("qc", &ids, &thresholds).unwrap();
+        assert_eq!(assessed, ids);
+        let qualities = db.read_quality("qc").unwrap();
+        assert!(qualities[&ids[0]].flags.is_empty());
+        assert!((10000.0..10050.0).contains(&qualities[&ids[0]].signal));
+        let expected = HashSet::from([Flag::Saturated, Flag::Spikes]);
+        assert_eq!(qualities[&ids[1]].flags, expected);
+        assert_eq!(qualities[&ids[1]].saturated, 1);
+        assert_eq!(qualities[&ids[2]].flags, HashSet::from([Flag::LowSignal]));
+        let passing = db.passing("qc", &ids, &[Flag::Saturated]).unwrap();
+        assert_eq!(passing, vec![ids[0], ids[2]]);
+        let passing = db
+            .passing("qc", &ids, &[Flag::Saturated, Flag::LowSignal])
+            .unwrap();
+        assert_eq!(passing, vec![ids[0]]);
+        remove_dir_all(PATH).unwrap();
+    }
+}
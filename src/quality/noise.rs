@@ -0,0 +1,95 @@
+/*
+Project: Optic
+GitHub: https://github.com/MillieFD/optic
+
+BSD 3-Clause License, Copyright (c) 2026, Amelia Fraser-Dale
+
+Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the conditions of the LICENSE are met.
+*/
+
+/* ------------------------------------------------------------------------------ Public Exports */
+
+/// Signal-to-noise ratio by the DER_SNR estimator of Stoehr et al. (2008): the median signal
+/// over a robust noise estimate from second differences four samples apart. `NaN` for fewer than
+/// five samples and infinite for a noise-free spectrum.
+pub(crate) fn snr(values: &[f64]) -> f64 {
+    median(values.to_vec()) / noise(values)
+}
+
+/// Robust standard deviation of the noise from second differences four samples apart, `NaN` for
+/// fewer than five samples.
+pub(crate) fn noise(values: &[f64]) -> f64 {
+    if values.len() < 5 {
+        return f64::NAN;
+    }
+    let differences: Vec<f64> = values
+        .windows(5)
+        .map(|w| (2.0 * w[2] - w[0] - w[4]).abs())
+        .collect();
+    1.482602 / 6f64.sqrt() * median(differences)
+}
+
+/// Indices of samples that exceed a running median of `window` samples by more than `sigma`
+/// times the [`noise`]. The noise never falls below a billionth of the largest magnitude so
+/// smooth, noise-free spectra are not flagged.
+pub(crate) fn spikes(values: &[f64], window: usize, sigma: f64) -> Vec<usize> {
+    let floor = 1E-9 * values.iter().fold(0.0, |max: f64, v| max.max(v.abs()));
+    let threshold = match noise(values) {
+        noise if noise.is_nan() => return Vec::new(),
+        noise => sigma * noise.max(floor),
+    };
+    values
+        .iter()
+        .zip(running_median(values, window))
+        .enumerate()
+        .filter(|(_, (value, smooth))| *value - smooth > threshold)
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// Median of the `window` samples centred on each sample. The window shrinks symmetrically
+/// towards the ends so that it stays centred.
+pub(crate) fn running_median(values: &[f64], window: usize) -> Vec<f64> {
+    (0..values.len())
+        .map(|i| {
+            let half = (window / 2).min(i).min(values.len() - 1 - i);
+            median(values[i - half..=i + half].to_vec())
+        })
+        .collect()
+}
+
+pub(crate) fn median(mut values: Vec<f64>) -> f64 {
+    if values.is_empty() {
+        return f64::NAN;
+    }
+    values.sort_unstable_by(f64::total_cmp);
+    let middle = values.len() / 2;
+    match values.len() % 2 {
+        0 => (values[middle - 1] + values[middle]) / 2.0,
+        _ => values[middle],
+    }
+}
+
+/* ---------------------------------------------------------------------------------- Unit Tests */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn noise_estimates() {
+        let noise = |i: usize| ((i as f64 * 12.9898).sin() * 43758.5453).fract() / 2.0;
+        let smooth: Vec<f64> = (0..50)
+            .map(|i| 100.0 + (i as f64 / 5.0).sin() + noise(i))
+            .collect();
+        assert_eq!(spikes(&smooth, 5, 6.0), Vec::<usize>::new());
+        let mut spiked = smooth.clone();
+        spiked[20] += 50.0;
+        assert_eq!(spikes(&spiked, 5, 6.0), vec![20]);
+        let noisy =
+            |scale: f64| -> Vec<f64> { (0..50).map(|i| 100.0 + scale * noise(i)).collect() };
+        assert!((snr(&noisy(1.0)) / snr(&noisy(2.0)) - 2.0).abs() < 0.05);
+        assert_eq!(median(vec![3.0, 1.0, 2.0, 10.0]), 2.5);
+    }
+}
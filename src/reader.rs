@@ -0,0 +1,39 @@
+/*
+Project: Optic
+GitHub: https://github.com/MillieFD/optic
+
+BSD 3-Clause License, Copyright (c) 2026, Amelia Fraser-Dale
+
+Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the conditions of the LICENSE are met.
+*/
+
+/* ----------------------------------------------------------------------------- Private Imports */
+
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use arrow::array::RecordBatch;
+use arrow::ipc::reader::StreamReader;
+
+use crate::Error;
+
+/* ------------------------------------------------------------------------------- Pubic Exports */
+
+pub(super) trait Reader {
+    fn path(&self) -> &Path;
+
+    /// Read every committed [`RecordBatch`] from disk. Uncommitted rows are not included.
+    fn batches(&self) -> Result<Vec<RecordBatch>, Error> {
+        let batches = self.reader()?.filter_map(Result::ok).collect();
+        Ok(batches)
+    }
+
+    /// Iterate over committed [`RecordBatch`]es one at a time without holding the whole table in
+    /// memory.
+    fn reader(&self) -> Result<StreamReader<BufReader<File>>, Error> {
+        let file = File::open(self.path())?;
+        StreamReader::try_new_buffered(file, None).map_err(Error::from)
+    }
+}
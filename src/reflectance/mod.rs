@@ -0,0 +1,164 @@
+/*
+Project: Optic
+GitHub: https://github.com/MillieFD/optic
+
+BSD 3-Clause License, Copyright (c) 2026, Amelia Fraser-Dale
+
+Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the conditions of the LICENSE are met.
+*/
+
+/* ----------------------------------------------------------------------------- Private Imports */
+
+use std::collections::HashMap;
+
+use crate::intensities::Spectrum;
+use crate::measurements::Kind;
+use crate::{Database, Error, Provenance};
+
+/* ------------------------------------------------------------------------------ Public Exports */
+
+/// How each sample measurement is paired with its dark and white references.
+pub enum References {
+    /// Use the dark and white references nearest in time with the same integration time.
+    Nearest,
+    /// Use explicit `(dark, white)` measurement IDs for each sample measurement ID.
+    Linked(HashMap<u32, (u32, u32)>),
+}
+
+impl Database {
+    /// Compute the reflectance `R = (S − D) / (W − D)` of sample measurements from their dark and
+    /// white references and write it to the derived table called `name`. Only wavelengths present
+    /// in all three spectra with `W ≠ D` are written. Returns the IDs of the processed samples.
+    pub fn compute_reflectance(
+        &self,
+        name: &str,
+        references: References,
+    ) -> Result<Vec<u32>, Error> {
+        let (method, mut triples): (_, Vec<(u32, u32, u32)>) = match references {
+            References::Nearest => {
+                let records = self.measurements.read()?;
+                let triples = records
+                    .iter()
+                    .filter(|record| record.kind == Kind::Sample)
+                    .map(|sample| {
+                        let dark = sample.nearest(&records, Kind::Dark);
+                        let white = sample.nearest(&records, Kind::White);
+                        match (dark, white) {
+                            (Some(dark), Some(white)) => Ok((sample.id, dark, white)),
+                            _ => Err(Error::MissingReference(sample.id)),
+                        }
+                    })
+                    .collect::<Result<_, _>>()?;
+                ("nearest", triples)
+            }
+            References::Linked(links) => {
+                let triples = links.into_iter().map(|(s, (d, w))| (s, d, w)).collect();
+                ("linked", triples)
+            }
+        };
+        triples.sort_unstable();
+        let ids: Vec<u32> = triples.iter().flat_map(|&(s, d, w)| [s, d, w]).collect();
+        let spectra = self.intensities.spectra(&ids)?;
+        let provenance = Provenance::new("reflectance", &["measurements", "intensities"])
+            .parameter("references", method);
+        let rows = triples
+            .iter()
+            .map(|&(sample, dark, white)| {
+                let spectrum = |id: u32| spectra.get(&id).ok_or(Error::MissingReference(sample));
+                let (wavelengths, values) =
+                    reflectance(spectrum(sample)?, spectrum(dark)?, spectrum(white)?);
+                Ok((sample, wavelengths, values))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        let mut table = self.create_derived(name, &provenance)?; // Only once every row is known
+        for (sample, wavelengths, values) in rows {
+            table.push(sample, &wavelengths, values);
+        }
+        table.commit()?;
+        Ok(triples.into_iter().map(|(sample, ..)| sample).collect())
+    }
+}
+
+/* ----------------------------------------------------------------------------- Private Helpers */
+
+fn reflectance(sample: &Spectrum, dark: &Spectrum, white: &Spectrum) -> (Vec<u32>, Vec<f64>) {
+    sample
+        .iter()
+        .filter_map(|(wavelength, s)| {
+            let d = dark.get(wavelength)?;
+            let w = white.get(wavelength)?;
+            (w != d).then(|| (*wavelength, (s - d) / (w - d)))
+        })
+        .unzip()
+}
+
+/* ---------------------------------------------------------------------------------- Unit Tests */
+
+#[cfg(all(
+    test,
+    feature = "x",
+    feature = "y",
+    not(feature = "z"),
+    not(feature = "a")
+))]
+mod tests {
+    use std::fs::remove_dir_all;
+
+    use uom::si::f64::{Length, Time};
+    use uom::si::length::micrometer;
+    use uom::si::time::millisecond;
+
+    use super::*;
+
+    fn push(db: &mut Database, kind: Kind, wavelengths: &[u32], counts: Vec<f64>) -> u32 {
+        let origin = Length::new::<micrometer>(0.0);
+        let integration = Time::new::<millisecond>(10.0);
+        let id = db
+            .measurements
+            .push(kind, origin, origin, integration, &[])
+            .unwrap();
+        db.intensities.push(id, wavelengths, counts);
+        id
+    }
+
+    #[test]
+    fn nearest_references() {
+        const PATH: &str = "test-reflectance";
+        let mut db = Database::new(PATH).unwrap();
+        let wavelengths = db.wavelengths.push(vec![400.0, 500.0]).unwrap();
+        push(&mut db, Kind::Dark, &wavelengths, vec![10.0, 10.0]);
+        push(&mut db, Kind::White, &wavelengths, vec![110.0, 210.0]);
+        let sample = push(&mut db, Kind::Sample, &wavelengths, vec![60.0, 60.0]);
+        db.measurements.commit().unwrap();
+        db.intensities.commit().unwrap();
+
+        let samples = db.compute_reflectance("R", References::Nearest).unwrap();
+        assert_eq!(samples, vec![sample]);
+        let spectra = db.read_derived("R").unwrap();
+        assert_eq!(
+            spectra[&sample].values().copied().collect::<Vec<_>>(),
+            vec![0.5, 0.25]
+        );
+        assert_eq!(db.provenance("R").unwrap().operation, "reflectance");
+        remove_dir_all(PATH).unwrap();
+    }
+
+    #[test]
+    fn missing_reference() {
+        const PATH: &str = "test-reflectance-missing";
+        let mut db = Database::new(PATH).unwrap();
+        let wavelengths = db.wavelengths.push(vec![400.0]).unwrap();
+        push(&mut db, Kind::Dark, &wavelengths, vec![10.0]);
+        let sample = push(&mut db, Kind::Sample, &wavelengths, vec![60.0]);
+        db.measurements.commit().unwrap();
+        db.intensities.commit().unwrap();
+        let result = db.compute_reflectance("R", References::Nearest);
+        assert!(matches!(result, Err(Error::MissingReference(id)) if id == sample));
+        let links = HashMap::from([(sample, (0, 99))]); // White has no spectrum
+        let result = db.compute_reflectance("R", References::Linked(links));
+        assert!(matches!(result, Err(Error::MissingReference(id)) if id == sample));
+        assert!(db.derived().unwrap().is_empty()); // Nothing left behind to block a retry
+        remove_dir_all(PATH).unwrap();
+    }
+}
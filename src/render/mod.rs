@@ -0,0 +1,278 @@
+/*
+Project: Optic
+GitHub: https://github.com/MillieFD/optic
+
+BSD 3-Clause License, Copyright (c) 2026, Amelia Fraser-Dale
+
+Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the conditions of the LICENSE are met.
+*/
+
+/* ----------------------------------------------------------------------------- Private Modules */
+
+mod png;
+mod tiff;
+
+/* ----------------------------------------------------------------------------- Private Imports */
+
+use std::fs::write;
+use std::path::Path;
+
+use uom::si::f64::Length;
+use uom::si::length::{micrometer, nanometer};
+
+use self::tiff::Samples;
+use crate::bandmath::nearest;
+use crate::colour::{Viewing, gamma};
+use crate::resample::curve;
+use crate::{Database, Error, Raster, Source};
+
+/* ------------------------------------------------------------------------------ Public Exports */
+
+/// Which values are mapped to the channels of a rendered image.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Colour {
+    /// Greyscale image of the sample nearest to one wavelength.
+    Band(Length),
+    /// False-colour image with the samples nearest to three wavelengths as red, green and blue.
+    Bands([Length; 3]),
+    /// True-colour sRGB image from the CIE 1931 observer under illuminant D65, treating each
+    /// spectrum as reflectance.
+    TrueColour,
+}
+
+/// How values are mapped to the displayable range `0..=1`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Stretch {
+    /// Use values as they are. Integer formats clamp to `0..=1`.
+    None,
+    /// Map the lower and upper percentiles (`0..=100`) of each channel to `0` and `1`. True
+    /// colour images use one range across all channels to preserve hue.
+    Percentile(f64, f64),
+}
+
+/// Output image encoding.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Format {
+    /// 8-bit PNG. Pixels without a measurement are black.
+    Png,
+    /// 16-bit unsigned integer TIFF. Pixels without a measurement are black.
+    Tiff16,
+    /// 32-bit floating point TIFF. Pixels without a measurement are `NaN`.
+    Tiff32,
+}
+
+impl Database {
+    /// Render the spectra of `measurements` from `source` to an image at `path`. Pixels lie on
+    /// the grid of distinct `x` and `y` positions with `x` increasing to the right and `y`
+    /// increasing downwards. Everything runs on the CPU.
+    pub fn render<P>(
+        &self,
+        path: P,
+        source: Source,
+        measurements: &[u32],
+        colour: Colour,
+        stretch: Stretch,
+        format: Format,
+    ) -> Result<(), Error>
+    where
+        P: AsRef<Path>,
+    {
+        let lookup = self.wavelengths.lookup()?;
+        let spectra = self.spectra(source, measurements)?;
+        let mut records = self.measurements.read()?;
+        records.retain(|record| spectra.contains_key(&record.id));
+        if records.is_empty() {
+            return Err(Error::InvalidParameter(
+                "None of the measurements has a spectrum to render".into(),
+            ));
+        }
+        let xs: Vec<f64> = records.iter().map(|r| r.x.get::<micrometer>()).collect();
+        let ys: Vec<f64> = records.iter().map(|r| r.y.get::<micrometer>()).collect();
+        let pixels = records
+            .iter()
+            .map(|record| {
+                let curve = curve(&spectra[&record.id], &lookup)?;
+                let band = |wl: &Length| nearest(&curve, wl.get::<nanometer>());
+                Ok(match colour {
+                    Colour::Band(wl) => vec![band(&wl)],
+                    Colour::Bands(wls) => wls.iter().map(band).collect(),
+                    Colour::TrueColour => Viewing::default().linear_srgb(&curve).to_vec(),
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        let channels = match colour {
+            Colour::Band(_) => 1,
+            _ => 3,
+        };
+        let mut rasters: Vec<Raster> = (0..channels)
+            .map(|c| {
+                let values: Vec<f64> = pixels.iter().map(|p| p[c]).collect();
+                Raster::new(&xs, &ys, &values)
+            })
+            .collect();
+        if let Stretch::Percentile(lower, upper) = stretch {
+            match colour {
+                Colour::TrueColour => {
+                    let all: Vec<f64> = rasters.iter().flat_map(|r| r.values.clone()).collect();
+                    let range = percentiles(&all, lower, upper);
+                    rasters.iter_mut().for_each(|r| scale(&mut r.values, range));
+                }
+                _ => rasters.iter_mut().for_each(|r| {
+                    let range = percentiles(&r.values, lower, upper);
+                    scale(&mut r.values, range)
+                }),
+            }
+        }
+        if colour == Colour::TrueColour {
+            rasters.iter_mut().for_each(|r| {
+                r.values
+                    .iter_mut()
+                    .for_each(|v| *v = gamma(v.clamp(0.0, 1.0)))
+            });
+        }
+        let (width, height) = rasters
+            .first()
+            .map_or((0, 0), |raster| (raster.width, raster.height));
+        let interleaved: Vec<f64> = (0..width * height)
+            .flat_map(|pixel| rasters.iter().map(move |raster| raster.values[pixel]))
+            .collect();
+        let normalised = |v: &f64| match v.is_nan() {
+            true => 0.0,
+            false => v.clamp(0.0, 1.0),
+        };
+        let bytes = match format {
+            Format::Png => {
+                let samples: Vec<u8> = interleaved
+                    .iter()
+                    .map(|v| (normalised(v) * u8::MAX as f64).round() as u8)
+                    .collect();
+                png::encode(width, height, channels, &samples)?
+            }
+            Format::Tiff16 => {
+                let samples: Vec<u16> = interleaved
+                    .iter()
+                    .map(|v| (normalised(v) * u16::MAX as f64).round() as u16)
+                    .collect();
+                tiff::encode(width, height, channels, Samples::U16(&samples))?
+            }
+            Format::Tiff32 => {
+                let samples: Vec<f32> = interleaved.iter().map(|v| *v as f32).collect();
+                tiff::encode(width, height, channels, Samples::F32(&samples))?
+            }
+        };
+        write(path, bytes).map_err(Error::from)
+    }
+}
+
+/* ----------------------------------------------------------------------------- Private Helpers */
+
+/// Values at the `lower` and `upper` percentiles of the finite `values`.
+fn percentiles(values: &[f64], lower: f64, upper: f64) -> (f64, f64) {
+    let mut sorted: Vec<f64> = values.iter().copied().filter(|v| v.is_finite()).collect();
+    if sorted.is_empty() {
+        return (0.0, 1.0);
+    }
+    sorted.sort_unstable_by(f64::total_cmp);
+    let at = |percentile: f64| {
+        let rank = percentile.clamp(0.0, 100.0) / 100.0 * (sorted.len() - 1) as f64;
+        let (below, above) = (sorted[rank.floor() as usize], sorted[rank.ceil() as usize]);
+        below + (above - below) * rank.fract()
+    };
+    (at(lower), at(upper))
+}
+
+fn scale(values: &mut [f64], (low, high): (f64, f64)) {
+    let range = match high > low {
+        true => high - low,
+        false => 1.0, // Constant image
+    };
+    values.iter_mut().for_each(|v| *v = (*v - low) / range);
+}
+
+/* ---------------------------------------------------------------------------------- Unit Tests */
+
+#[cfg(all(
+    test,
+    feature = "x",
+    feature = "y",
+    not(feature = "z"),
+    not(feature = "a")
+))]
+mod tests {
+    use std::fs::{read, remove_dir_all};
+
+    use uom::si::f64::Time;
+    use uom::si::time::millisecond;
+
+    use super::*;
+    use crate::Kind;
+    use crate::deflate::decompress;
+
+    #[test]
+    fn render_images() {
+        const PATH: &str = "test-render";
+        let mut db = Database::new(PATH).unwrap();
+        let wavelengths = db.wavelengths.push(vec![450.0, 550.0, 650.0]).unwrap();
+        db.wavelengths.commit().unwrap();
+        let integration = Time::new::<millisecond>(10.0);
+        let ids: Vec<u32> = [(0.0, 0.0, 0.0), (10.0, 0.0, 0.5), (0.0, 10.0, 1.0)]
+            .into_iter()
+            .map(|(x, y, r)| {
+                let x = Length::new::<micrometer>(x);
+                let y = Length::new::<micrometer>(y);
+                let id = db
+                    .measurements
+                    .push(Kind::Sample, x, y, integration, &[])
+                    .unwrap();
+                db.intensities.push(id, &wavelengths, vec![r; 3]);
+                id
+            })
+            .collect();
+        db.measurements.commit().unwrap();
+        db.intensities.commit().unwrap();
+
+        let band = Colour::Band(Length::new::<nanometer>(540.0));
+        let png = db.path.join("band.png");
+        db.render(
+            &png,
+            Source::Intensities,
+            &ids,
+            band,
+            Stretch::Percentile(0.0, 100.0),
+            Format::Png,
+        )
+        .unwrap();
+        let bytes = read(&png).unwrap();
+        assert_eq!(&bytes[1..4], b"PNG");
+        let length = u32::from_be_bytes(bytes[33..37].try_into().unwrap()) as usize;
+        let scanlines = decompress(&bytes[41..41 + length]).unwrap();
+        assert_eq!(scanlines, vec![0, 0, 128, 0, 255, 0]); // Two rows of two pixels
+
+        let grey = db.path.join("grey.tif");
+        db.render(
+            &grey,
+            Source::Intensities,
+            &ids,
+            Colour::TrueColour,
+            Stretch::None,
+            Format::Tiff32,
+        )
+        .unwrap();
+        let bytes = read(&grey).unwrap();
+        assert_eq!(&bytes[0..4], &[b'I', b'I', 42, 0]);
+        let white = f32::from_le_bytes(bytes[8 + 24..8 + 28].try_into().unwrap());
+        assert!((white - 1.0).abs() < 1E-2); // A flat reflectance of 1 is white
+
+        let empty = db.render(
+            &png,
+            Source::Intensities,
+            &[],
+            band,
+            Stretch::None,
+            Format::Png,
+        );
+        assert!(matches!(empty, Err(Error::InvalidParameter(_))));
+        remove_dir_all(PATH).unwrap();
+    }
+}
@@ -0,0 +1,71 @@
+/*
+Project: Optic
+GitHub: https://github.com/MillieFD/optic
+
+BSD 3-Clause License, Copyright (c) 2026, Amelia Fraser-Dale
+
+Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the conditions of the LICENSE are met.
+*/
+
+/* ----------------------------------------------------------------------------- Private Imports */
+
+use crate::Error;
+use crate::deflate::compress;
+
+/* ------------------------------------------------------------------------------ Public Exports */
+
+/// Encode 8-bit greyscale (`channels = 1`) or RGB (`channels = 3`) pixels in row-major order.
+/// Fails for an empty image, which PNG cannot represent.
+pub(super) fn encode(
+    width: usize,
+    height: usize,
+    channels: usize,
+    pixels: &[u8],
+) -> Result<Vec<u8>, Error> {
+    if width == 0 || height == 0 {
+        return Err(Error::InvalidParameter(
+            "Images need at least one pixel".into(),
+        ));
+    }
+    let colour = match channels {
+        1 => 0, // Greyscale
+        _ => 2, // Truecolour
+    };
+    let mut header = Vec::with_capacity(13);
+    header.extend((width as u32).to_be_bytes());
+    header.extend((height as u32).to_be_bytes());
+    header.extend([8, colour, 0, 0, 0]); // Bit depth, colour type, compression, filter, interlace
+    let scanlines: Vec<u8> = pixels
+        .chunks(width * channels)
+        .flat_map(|row| [0].into_iter().chain(row.iter().copied())) // Filter type `None`
+        .collect();
+    let mut png = SIGNATURE.to_vec();
+    chunk(&mut png, b"IHDR", &header);
+    chunk(&mut png, b"IDAT", &compress(&scanlines));
+    chunk(&mut png, b"IEND", &[]);
+    Ok(png)
+}
+
+/* ----------------------------------------------------------------------------- Private Helpers */
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n'];
+
+fn chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend((data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend(kind);
+    png.extend(data);
+    let crc = crc32(&png[start..]);
+    png.extend(crc.to_be_bytes());
+}
+
+/// CRC-32 (ISO 3309) as required by every PNG chunk.
+fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(u32::MAX, |crc, byte| {
+        (0..8).fold(crc ^ *byte as u32, |crc, _| match crc & 1 {
+            1 => (crc >> 1) ^ 0xEDB8_8320,
+            _ => crc >> 1,
+        })
+    })
+}
@@ -0,0 +1,89 @@
+/*
+Project: Optic
+GitHub: https://github.com/MillieFD/optic
+
+BSD 3-Clause License, Copyright (c) 2026, Amelia Fraser-Dale
+
+Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the conditions of the LICENSE are met.
+*/
+
+/* ----------------------------------------------------------------------------- Private Imports */
+
+use crate::Error;
+
+/* ------------------------------------------------------------------------------ Public Exports */
+
+/// Sample encoding of an uncompressed baseline TIFF.
+#[derive(Copy, Clone, Debug)]
+pub(super) enum Samples<'a> {
+    U16(&'a [u16]),
+    F32(&'a [f32]),
+}
+
+/// Encode little-endian greyscale (`channels = 1`) or RGB (`channels = 3`) pixels in row-major
+/// order as a single uncompressed strip. Fails for an empty image, which TIFF cannot represent.
+pub(super) fn encode(
+    width: usize,
+    height: usize,
+    channels: usize,
+    samples: Samples,
+) -> Result<Vec<u8>, Error> {
+    if width == 0 || height == 0 {
+        return Err(Error::InvalidParameter(
+            "Images need at least one pixel".into(),
+        ));
+    }
+    let (bits, format, data): (u16, u16, Vec<u8>) = match samples {
+        Samples::U16(values) => (16, 1, values.iter().flat_map(|v| v.to_le_bytes()).collect()),
+        Samples::F32(values) => (32, 3, values.iter().flat_map(|v| v.to_le_bytes()).collect()),
+    };
+    let mut tiff = vec![b'I', b'I', 42, 0, 0, 0, 0, 0];
+    tiff.extend(&data);
+    let per_sample = |tiff: &mut Vec<u8>, value: u16| match channels {
+        1 => value as u32,
+        _ => {
+            let offset = tiff.len() as u32;
+            (0..channels).for_each(|_| tiff.extend(value.to_le_bytes()));
+            offset
+        }
+    };
+    let bits = per_sample(&mut tiff, bits);
+    let format = per_sample(&mut tiff, format);
+    if tiff.len() % 2 == 1 {
+        tiff.push(0); // The IFD must start on a word boundary
+    }
+    let ifd = tiff.len() as u32;
+    tiff[4..8].copy_from_slice(&ifd.to_le_bytes());
+    let photometric = match channels {
+        1 => 1, // BlackIsZero
+        _ => 2, // RGB
+    };
+    let entries: [(u16, u16, u32, u32); 11] = [
+        (256, LONG, 1, width as u32),
+        (257, LONG, 1, height as u32),
+        (258, SHORT, channels as u32, bits),
+        (259, SHORT, 1, 1), // No compression
+        (262, SHORT, 1, photometric),
+        (273, LONG, 1, 8), // Strip offset immediately after the header
+        (277, SHORT, 1, channels as u32),
+        (278, LONG, 1, height as u32),
+        (279, LONG, 1, data.len() as u32),
+        (284, SHORT, 1, 1), // Chunky planar configuration
+        (339, SHORT, channels as u32, format),
+    ];
+    tiff.extend((entries.len() as u16).to_le_bytes());
+    for (tag, kind, count, value) in entries {
+        tiff.extend(tag.to_le_bytes());
+        tiff.extend(kind.to_le_bytes());
+        tiff.extend(count.to_le_bytes());
+        tiff.extend(value.to_le_bytes()); // Values shorter than 4 bytes are left-justified
+    }
+    tiff.extend(0u32.to_le_bytes()); // No further IFDs
+    Ok(tiff)
+}
+
+/* ----------------------------------------------------------------------------- Private Helpers */
+
+const SHORT: u16 = 3;
+const LONG: u16 = 4;
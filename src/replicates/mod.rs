@@ -0,0 +1,286 @@
+/*
+Project: Optic
+GitHub: https://github.com/MillieFD/optic
+
+BSD 3-Clause License, Copyright (c) 2026, Amelia Fraser-Dale
+
+Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the conditions of the LICENSE are met.
+*/
+
+/* ----------------------------------------------------------------------------- Private Imports */
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use uom::si::f64::Length;
+use uom::si::length::micrometer;
+
+use crate::scalars::position;
+use crate::{Database, Error, Provenance, Source};
+
+/* ------------------------------------------------------------------------------ Public Exports */
+
+/// How measurements are grouped into replicates.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Grouping {
+    /// Measurements whose enabled position axes all lie within this tolerance of the first
+    /// measurement of a group. Every measurement is grouped together when no position feature is
+    /// enabled. The tolerance must be positive and finite.
+    Position(Length),
+    /// Measurements with the same key. Measurements without a key are left out.
+    Key(HashMap<u32, String>),
+}
+
+impl Database {
+    /// Group `measurements` into replicates. Each group is sorted by ID and the groups are sorted
+    /// by their first ID. Unknown measurement IDs are ignored.
+    pub fn replicates(
+        &self,
+        measurements: &[u32],
+        grouping: &Grouping,
+    ) -> Result<Vec<Vec<u32>>, Error> {
+        let wanted: HashSet<&u32> = measurements.iter().collect();
+        let mut records = self.measurements.read()?;
+        records.retain(|record| wanted.contains(&record.id));
+        records.sort_unstable_by_key(|record| record.id);
+        let mut groups: Vec<Vec<u32>> = match grouping {
+            Grouping::Key(keys) => {
+                let mut groups: BTreeMap<&str, Vec<u32>> = BTreeMap::new();
+                for record in &records {
+                    if let Some(key) = keys.get(&record.id) {
+                        groups.entry(key).or_default().push(record.id);
+                    }
+                }
+                groups.into_values().collect()
+            }
+            Grouping::Position(tolerance) => {
+                let tolerance = tolerance.get::<micrometer>();
+                if !tolerance.is_finite() || tolerance <= 0.0 {
+                    return Err(Error::InvalidParameter(
+                        "Tolerance must be positive and finite".into(),
+                    ));
+                }
+                let cell = |p: &[f64]| -> Vec<i64> {
+                    p.iter().map(|v| (v / tolerance).floor() as i64).collect()
+                };
+                let mut anchors: Vec<Vec<f64>> = Vec::new();
+                let mut groups: Vec<Vec<u32>> = Vec::new();
+                let mut cells: HashMap<Vec<i64>, Vec<usize>> = HashMap::new();
+                for record in &records {
+                    let p = position(Some(record));
+                    let home = cell(&p);
+                    let within = |group: &usize| {
+                        anchors[*group]
+                            .iter()
+                            .zip(&p)
+                            .all(|(a, b)| (a - b).abs() <= tolerance)
+                    };
+                    let found = neighbours(&home)
+                        .filter_map(|c| cells.get(&c))
+                        .flatten()
+                        .copied()
+                        .filter(within)
+                        .min();
+                    match found {
+                        Some(group) => groups[group].push(record.id),
+                        None => {
+                            cells.entry(home).or_default().push(groups.len());
+                            anchors.push(p);
+                            groups.push(vec![record.id]);
+                        }
+                    }
+                }
+                groups
+            }
+        };
+        groups.sort_unstable_by_key(|group| group[0]);
+        Ok(groups)
+    }
+
+    /// Average the spectra of replicate `measurements` from `source` per wavelength ID. Values
+    /// further than `clip` standard deviations from the mean are rejected repeatedly until none
+    /// remain. The mean, sample standard deviation and count of the kept values are written to
+    /// the derived tables `name`, `{name}_std` and `{name}_count`, keyed by the first measurement
+    /// ID of each group. Returns the groups as [`Database::replicates`].
+    pub fn average_replicates(
+        &self,
+        name: &str,
+        source: Source,
+        measurements: &[u32],
+        grouping: &Grouping,
+        clip: Option<f64>,
+    ) -> Result<Vec<Vec<u32>>, Error> {
+        if clip.is_some_and(|sigma| sigma.is_nan() || sigma <= 0.0) {
+            return Err(Error::InvalidParameter("Clip must be positive".into()));
+        }
+        let groups = self.replicates(measurements, grouping)?;
+        let spectra = self.spectra(source, measurements)?;
+        let provenance = Provenance::new("average_replicates", &[source.name(), "measurements"]);
+        let provenance = match grouping {
+            Grouping::Position(tolerance) => provenance
+                .parameter("grouping", "position")
+                .parameter("tolerance_um", tolerance.get::<micrometer>()),
+            Grouping::Key(_) => provenance.parameter("grouping", "key"),
+        };
+        let provenance = match clip {
+            Some(sigma) => provenance.parameter("clip_sigma", sigma),
+            None => provenance,
+        };
+        let names = [
+            name.to_string(),
+            format!("{name}_std"),
+            format!("{name}_count"),
+        ];
+        let tables: Vec<(String, Provenance)> = names
+            .into_iter()
+            .map(|table| {
+                let provenance = provenance.clone().parameter("statistic", &table);
+                (table, provenance)
+            })
+            .collect();
+        let mut tables = self.create_derived_all(&tables)?;
+        for group in &groups {
+            let mut samples: BTreeMap<u32, Vec<f64>> = BTreeMap::new();
+            for spectrum in group.iter().filter_map(|id| spectra.get(id)) {
+                for (wavelength, value) in spectrum {
+                    samples.entry(*wavelength).or_default().push(*value);
+                }
+            }
+            let wavelengths: Vec<u32> = samples.keys().copied().collect();
+            let statistics: Vec<[f64; 3]> = samples
+                .into_values()
+                .map(|values| {
+                    let kept = clipped(values, clip);
+                    let (mean, std) = moments(&kept);
+                    [mean, std, kept.len() as f64]
+                })
+                .collect();
+            for (index, table) in tables.iter_mut().enumerate() {
+                table.push(
+                    group[0],
+                    &wavelengths,
+                    statistics.iter().map(|s| s[index]).collect(),
+                );
+            }
+        }
+        tables.iter_mut().try_for_each(|table| table.commit())?;
+        Ok(groups)
+    }
+}
+
+/* ----------------------------------------------------------------------------- Private Helpers */
+
+/// Every grid cell adjacent to or equal to `cell`.
+fn neighbours(cell: &[i64]) -> impl Iterator<Item = Vec<i64>> + '_ {
+    (0..3usize.pow(cell.len() as u32)).map(move |mut index| {
+        cell.iter()
+            .map(|c| {
+                let offset = (index % 3) as i64 - 1;
+                index /= 3;
+                c.saturating_add(offset)
+            })
+            .collect()
+    })
+}
+
+/// Mean and sample standard deviation, which is zero for a single value.
+pub(crate) fn moments(values: &[f64]) -> (f64, f64) {
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let variance = match values.len() {
+        0 | 1 => 0.0,
+        _ => values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0),
+    };
+    (mean, variance.sqrt())
+}
+
+/// Repeatedly reject values further than `clip` standard deviations from the mean.
+fn clipped(mut values: Vec<f64>, clip: Option<f64>) -> Vec<f64> {
+    let Some(sigma) = clip else {
+        return values;
+    };
+    loop {
+        let (mean, std) = moments(&values);
+        let before = values.len();
+        values.retain(|v| (v - mean).abs() <= sigma * std);
+        if values.len() == before || values.len() < 2 {
+            return values;
+        }
+    }
+}
+
+/* ---------------------------------------------------------------------------------- Unit Tests */
+
+#[cfg(all(
+    test,
+    feature = "x",
+    feature = "y",
+    not(feature = "z"),
+    not(feature = "a")
+))]
+mod tests {
+    use std::fs::remove_dir_all;
+
+    use uom::si::f64::Time;
+    use uom::si::time::millisecond;
+
+    use super::*;
+    use crate::Kind;
+
+    #[test]
+    fn average_with_clipping() {
+        const PATH: &str = "test-replicates";
+        let mut db = Database::new(PATH).unwrap();
+        let wavelengths = db.wavelengths.push(vec![500.0, 600.0]).unwrap();
+        db.wavelengths.commit().unwrap();
+        let integration = Time::new::<millisecond>(10.0);
+        let ids: Vec<u32> = [
+            (0.0, [1.0, 10.0]),
+            (0.1, [1.0, 10.0]),
+            (0.2, [1.0, 10.0]),
+            (0.1, [1.0, 10.0]),
+            (0.0, [1.0, 10.0]),
+            (-0.1, [1.0, 10.0]),
+            (0.0, [1.0, 1000.0]), // Cosmic ray
+            (50.0, [2.0, 20.0]),
+            (50.4, [4.0, 40.0]),
+        ]
+        .into_iter()
+        .map(|(x, values)| {
+            let x = Length::new::<micrometer>(x);
+            let y = Length::new::<micrometer>(0.0);
+            let id = db
+                .measurements
+                .push(Kind::Sample, x, y, integration, &[])
+                .unwrap();
+            db.intensities.push(id, &wavelengths, values.to_vec());
+            id
+        })
+        .collect();
+        db.measurements.commit().unwrap();
+        db.intensities.commit().unwrap();
+
+        let grouping = Grouping::Position(Length::new::<micrometer>(0.5));
+        let groups = db
+            .average_replicates("mean", Source::Intensities, &ids, &grouping, Some(2.0))
+            .unwrap();
+        assert_eq!(groups, vec![ids[..7].to_vec(), ids[7..].to_vec()]);
+        let mean = db.read_derived("mean").unwrap();
+        let std = db.read_derived("mean_std").unwrap();
+        let count = db.read_derived("mean_count").unwrap();
+        assert_eq!(mean[&ids[0]][&wavelengths[1]], 10.0); // Spike rejected
+        assert_eq!(count[&ids[0]][&wavelengths[1]], 6.0);
+        assert_eq!(count[&ids[0]][&wavelengths[0]], 7.0);
+        assert_eq!(mean[&ids[7]][&wavelengths[0]], 3.0);
+        assert!((std[&ids[7]][&wavelengths[0]] - 2f64.sqrt()).abs() < 1E-12);
+
+        let keys = HashMap::from([(ids[0], "a".to_string()), (ids[8], "a".to_string())]);
+        let groups = db.replicates(&ids, &Grouping::Key(keys)).unwrap();
+        assert_eq!(groups, vec![vec![ids[0], ids[8]]]);
+        for tolerance in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            let grouping = Grouping::Position(Length::new::<micrometer>(tolerance));
+            assert!(db.replicates(&ids, &grouping).is_err());
+        }
+        remove_dir_all(PATH).unwrap();
+    }
+}
@@ -0,0 +1,269 @@
+/*
+Project: Optic
+GitHub: https://github.com/MillieFD/optic
+
+BSD 3-Clause License, Copyright (c) 2026, Amelia Fraser-Dale
+
+Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the conditions of the LICENSE are met.
+*/
+
+/* ----------------------------------------------------------------------------- Private Modules */
+
+mod spline;
+
+/* ----------------------------------------------------------------------------- Private Imports */
+
+use std::collections::HashMap;
+use std::f64::consts::LN_2;
+
+use uom::si::f64::Length;
+use uom::si::length::nanometer;
+
+use self::spline::Spline;
+use crate::intensities::Spectrum;
+use crate::{Database, Error, Provenance, Source};
+
+/* ------------------------------------------------------------------------------ Public Exports */
+
+/// How source samples are mapped onto each target wavelength.
+#[derive(Copy, Clone, Debug)]
+pub enum Interpolation {
+    /// Straight line between the two neighbouring source samples.
+    Linear,
+    /// Natural cubic spline through every source sample.
+    Spline,
+    /// Gaussian bandpass with the given full width at half maximum, centred on each target.
+    Bandpass(Length),
+}
+
+/// Spectra resampled onto a common wavelength grid. `values[row][column]` holds measurement
+/// `measurements[row]` at wavelength `grid[column]`, or `NaN` outside the measured range.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Matrix {
+    pub measurements: Vec<u32>,
+    pub grid: Vec<Length>,
+    pub values: Vec<Vec<f64>>,
+}
+
+impl Database {
+    /// Interpolate the spectra of `measurements` from `source` onto `grid`. Nothing is written to
+    /// disk. Rows are sorted by measurement ID and measurements without a spectrum are omitted.
+    pub fn resample(
+        &self,
+        source: Source,
+        measurements: &[u32],
+        grid: &[Length],
+        interpolation: Interpolation,
+    ) -> Result<Matrix, Error> {
+        let lookup = self.wavelengths.lookup()?;
+        let mut spectra: Vec<_> = self.spectra(source, measurements)?.into_iter().collect();
+        spectra.sort_unstable_by_key(|(id, _)| *id);
+        let targets: Vec<f64> = grid.iter().map(|wl| wl.get::<nanometer>()).collect();
+        let (measurements, values) = spectra
+            .iter()
+            .map(|(id, spectrum)| {
+                let curve = curve(spectrum, &lookup)?;
+                Ok((*id, interpolate(&curve, &targets, interpolation)))
+            })
+            .collect::<Result<_, Error>>()?;
+        Ok(Matrix {
+            measurements,
+            grid: grid.to_vec(),
+            values,
+        })
+    }
+
+    /// Resample as [`Database::resample`] and write the result to the derived table called
+    /// `name`. The grid is registered in the `wavelengths` table and its IDs are returned.
+    pub fn resample_into(
+        &mut self,
+        name: &str,
+        source: Source,
+        measurements: &[u32],
+        grid: &[Length],
+        interpolation: Interpolation,
+    ) -> Result<Vec<u32>, Error> {
+        let matrix = self.resample(source, measurements, grid, interpolation)?;
+        let provenance = Provenance::new("resample", &[source.name(), "wavelengths"]);
+        let provenance = match interpolation {
+            Interpolation::Linear => provenance.parameter("interpolation", "linear"),
+            Interpolation::Spline => provenance.parameter("interpolation", "spline"),
+            Interpolation::Bandpass(fwhm) => provenance
+                .parameter("interpolation", "bandpass")
+                .parameter("fwhm_nm", fwhm.get::<nanometer>()),
+        };
+        let mut table = self.create_derived(name, &provenance)?; // Before registering the grid
+        let nms = grid.iter().map(|wl| wl.get::<nanometer>()).collect();
+        let ids = self.wavelengths.push(nms)?;
+        self.wavelengths.commit()?;
+        for (id, row) in matrix.measurements.iter().zip(matrix.values) {
+            let (wavelengths, values): (Vec<u32>, Vec<f64>) = ids
+                .iter()
+                .copied()
+                .zip(row)
+                .filter(|(_, value)| value.is_finite())
+                .unzip();
+            table.push(*id, &wavelengths, values);
+        }
+        table.commit()?;
+        Ok(ids)
+    }
+}
+
+/// Convert a spectrum keyed by wavelength ID into `(nm, value)` pairs sorted by wavelength.
+pub(crate) fn curve(
+    spectrum: &Spectrum,
+    lookup: &HashMap<u32, Length>,
+) -> Result<Vec<(f64, f64)>, Error> {
+    let mut curve = spectrum
+        .iter()
+        .map(|(id, value)| match lookup.get(id) {
+            Some(wl) => Ok((wl.get::<nanometer>(), *value)),
+            None => Err(Error::MissingWavelength(*id)),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    curve.sort_unstable_by(|a, b| a.0.total_cmp(&b.0));
+    Ok(curve)
+}
+
+/* ----------------------------------------------------------------------------- Private Helpers */
+
+fn interpolate(curve: &[(f64, f64)], targets: &[f64], interpolation: Interpolation) -> Vec<f64> {
+    match interpolation {
+        Interpolation::Linear => targets.iter().map(|&x| linear(curve, x)).collect(),
+        Interpolation::Spline => {
+            let spline = Spline::new(curve);
+            targets.iter().map(|&x| spline.at(x)).collect()
+        }
+        Interpolation::Bandpass(fwhm) => {
+            let sigma = fwhm.get::<nanometer>() / (2.0 * (2.0 * LN_2).sqrt());
+            targets.iter().map(|&x| bandpass(curve, x, sigma)).collect()
+        }
+    }
+}
+
+pub(crate) fn linear(curve: &[(f64, f64)], x: f64) -> f64 {
+    let index = curve.partition_point(|&(xi, _)| xi < x);
+    match (index.checked_sub(1).map(|i| curve[i]), curve.get(index)) {
+        (_, Some(&(x1, y1))) if x1 == x => y1,
+        (Some((x0, y0)), Some(&(x1, y1))) => y0 + (y1 - y0) * (x - x0) / (x1 - x0),
+        _ => f64::NAN,
+    }
+}
+
+/// Gaussian-weighted mean of the samples within three standard deviations of `x`. Each sample is
+/// weighted by the mean spacing to its neighbours to allow for uneven sampling.
+fn bandpass(curve: &[(f64, f64)], x: f64, sigma: f64) -> f64 {
+    match (curve.first(), curve.last()) {
+        (Some(first), Some(last)) if (first.0..=last.0).contains(&x) => {}
+        _ => return f64::NAN,
+    }
+    if sigma <= 0.0 {
+        return linear(curve, x);
+    }
+    let (sum, weights) = (0..curve.len())
+        .filter(|&i| (curve[i].0 - x).abs() <= 3.0 * sigma)
+        .map(|i| {
+            let lower = curve[i.saturating_sub(1)].0;
+            let upper = curve[(i + 1).min(curve.len() - 1)].0;
+            let neighbours = (i > 0) as u8 + (i + 1 < curve.len()) as u8;
+            let width = (upper - lower).max(f64::EPSILON) / f64::from(neighbours.max(1));
+            let weight = (-0.5 * ((curve[i].0 - x) / sigma).powi(2)).exp() * width;
+            (curve[i].1 * weight, weight)
+        })
+        .fold((0.0, 0.0), |(s, w), (value, weight)| {
+            (s + value, w + weight)
+        });
+    match weights > 0.0 {
+        true => sum / weights,
+        false => linear(curve, x), // Bandpass narrower than the sample spacing
+    }
+}
+
+/* ---------------------------------------------------------------------------------- Unit Tests */
+
+#[cfg(all(
+    test,
+    feature = "x",
+    feature = "y",
+    not(feature = "z"),
+    not(feature = "a")
+))]
+mod tests {
+    use std::fs::remove_dir_all;
+
+    use uom::si::f64::Time;
+    use uom::si::length::micrometer;
+    use uom::si::time::millisecond;
+
+    use super::*;
+    use crate::Kind;
+
+    #[test]
+    fn resample_onto_grid() {
+        const PATH: &str = "test-resample";
+        let mut db = Database::new(PATH).unwrap();
+        let wavelengths = db
+            .wavelengths
+            .push(vec![400.0, 410.0, 420.0, 430.0])
+            .unwrap();
+        db.wavelengths.commit().unwrap();
+        let origin = Length::new::<micrometer>(0.0);
+        let integration = Time::new::<millisecond>(10.0);
+        let id = db
+            .measurements
+            .push(Kind::Sample, origin, origin, integration, &[])
+            .unwrap();
+        db.intensities
+            .push(id, &wavelengths, vec![4.0, 4.1, 4.2, 4.3]);
+        db.measurements.commit().unwrap();
+        db.intensities.commit().unwrap();
+
+        let grid: Vec<Length> = [415.0, 425.0, 450.0]
+            .into_iter()
+            .map(Length::new::<nanometer>)
+            .collect();
+        for interpolation in [
+            Interpolation::Linear,
+            Interpolation::Spline,
+            Interpolation::Bandpass(Length::new::<nanometer>(5.0)),
+        ] {
+            let matrix = db
+                .resample(Source::Intensities, &[id], &grid, interpolation)
+                .unwrap();
+            assert_eq!(matrix.measurements, vec![id]);
+            assert!((matrix.values[0][0] - 4.15).abs() < 1E-2);
+            assert!((matrix.values[0][1] - 4.25).abs() < 1E-2);
+            assert!(matrix.values[0][2].is_nan()); // Outside the measured range
+        }
+
+        let ids = db
+            .resample_into(
+                "grid",
+                Source::Intensities,
+                &[id],
+                &grid,
+                Interpolation::Linear,
+            )
+            .unwrap();
+        assert_eq!(ids.len(), 3);
+        let spectrum = &db.read_derived("grid").unwrap()[&id];
+        assert_eq!(spectrum.len(), 2);
+        assert!((spectrum[&ids[1]] - 4.25).abs() < 1E-9);
+        assert_eq!(db.wavelengths.lookup().unwrap().len(), 7);
+        let other = [Length::new::<nanometer>(999.0)];
+        for name in ["grid", "../escape"] {
+            let result = db.resample_into(
+                name,
+                Source::Intensities,
+                &[id],
+                &other,
+                Interpolation::Linear,
+            );
+            assert!(result.is_err());
+        }
+        assert_eq!(db.wavelengths.lookup().unwrap().len(), 7); // Grid not registered
+        remove_dir_all(PATH).unwrap();
+    }
+}
@@ -0,0 +1,61 @@
+/*
+Project: Optic
+GitHub: https://github.com/MillieFD/optic
+
+BSD 3-Clause License, Copyright (c) 2026, Amelia Fraser-Dale
+
+Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the conditions of the LICENSE are met.
+*/
+
+/* ------------------------------------------------------------------------------ Public Exports */
+
+/// Natural cubic spline through a set of `(x, y)` samples sorted by `x`.
+pub(super) struct Spline<'a> {
+    points: &'a [(f64, f64)],
+    /// Second derivative at each sample.
+    curvature: Vec<f64>,
+}
+
+impl<'a> Spline<'a> {
+    pub(super) fn new(points: &'a [(f64, f64)]) -> Self {
+        let n = points.len();
+        let mut curvature = vec![0.0; n];
+        if n < 3 {
+            return Self { points, curvature };
+        }
+        // Thomas algorithm for the tridiagonal system with zero curvature at both ends
+        let mut diagonal = vec![1.0; n];
+        let mut rhs = vec![0.0; n];
+        let mut upper = vec![0.0; n];
+        for i in 1..n - 1 {
+            let (h0, h1) = (points[i].0 - points[i - 1].0, points[i + 1].0 - points[i].0);
+            let slope0 = (points[i].1 - points[i - 1].1) / h0;
+            let slope1 = (points[i + 1].1 - points[i].1) / h1;
+            diagonal[i] = 2.0 * (h0 + h1) - h0 * upper[i - 1];
+            upper[i] = h1 / diagonal[i];
+            rhs[i] = (6.0 * (slope1 - slope0) - h0 * rhs[i - 1]) / diagonal[i];
+        }
+        for i in (1..n - 1).rev() {
+            curvature[i] = rhs[i] - upper[i] * curvature[i + 1];
+        }
+        Self { points, curvature }
+    }
+
+    /// Evaluate the spline at `x`, returning `NaN` outside the sampled range.
+    pub(super) fn at(&self, x: f64) -> f64 {
+        let points = self.points;
+        let index = points.partition_point(|&(xi, _)| xi < x);
+        if index < points.len() && points[index].0 == x {
+            return points[index].1;
+        }
+        if index == 0 || index == points.len() {
+            return f64::NAN;
+        }
+        let ((x0, y0), (x1, y1)) = (points[index - 1], points[index]);
+        let (m0, m1) = (self.curvature[index - 1], self.curvature[index]);
+        let h = x1 - x0;
+        let (a, b) = ((x1 - x) / h, (x - x0) / h);
+        a * y0 + b * y1 + ((a.powi(3) - a) * m0 + (b.powi(3) - b) * m1) * h * h / 6.0
+    }
+}
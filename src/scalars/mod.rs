@@ -0,0 +1,339 @@
+/*
+Project: Optic
+GitHub: https://github.com/MillieFD/optic
+
+BSD 3-Clause License, Copyright (c) 2026, Amelia Fraser-Dale
+
+Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the conditions of the LICENSE are met.
+*/
+
+/* ----------------------------------------------------------------------------- Private Imports */
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{DirBuilder, File, OpenOptions, remove_file};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, AsArray, Float64Builder, RecordBatch, UInt32Builder};
+use arrow::datatypes::DataType::{Float64, UInt32};
+use arrow::datatypes::{Field, Float64Type, Schema, UInt32Type};
+use arrow::ipc::writer::StreamWriter;
+#[cfg(any(feature = "x", feature = "y", feature = "z", feature = "a"))]
+use uom::si::length::micrometer;
+
+use crate::derived::{Table, list};
+use crate::writer::new_stream_writer;
+use crate::{Database, Derived, Error, Measurement, Provenance, Reader};
+
+/* ------------------------------------------------------------------------------ Public Exports */
+
+/// A per-measurement table of named scalar values, such as PCA scores or band indices, stored in
+/// the `scalars` directory. Each row also carries the position of its measurement so that any
+/// value column can be rendered as an image without joining on the `measurements` table.
+pub struct Scalars {
+    stream: StreamWriter<File>,
+    schema: Arc<Schema>,
+    records: HashMap<u32, Measurement>,
+    measurement: UInt32Builder,
+    positions: Vec<Float64Builder>,
+    values: Vec<Float64Builder>,
+    pub name: String,
+    pub path: PathBuf,
+}
+
+/// Committed contents of a scalar table. Every column holds one entry per row. Position columns
+/// are in micrometres as in the `measurements` table, or `NaN` for unknown measurements.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Columns {
+    pub measurements: Vec<u32>,
+    pub values: BTreeMap<String, Vec<f64>>,
+    pub provenance: Provenance,
+}
+
+/// Image of one scalar column on the grid of distinct `x` and `y` positions in micrometres.
+/// Pixel `(column, row)` is stored at `values[row * width + column]` with `y` increasing by row.
+/// Pixels without a measurement are `NaN`.
+#[cfg(all(feature = "x", feature = "y"))]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Raster {
+    pub width: usize,
+    pub height: usize,
+    pub x: Vec<f64>,
+    pub y: Vec<f64>,
+    pub values: Vec<f64>,
+}
+
+#[cfg(all(feature = "x", feature = "y"))]
+impl Columns {
+    /// Arrange `column` into a [`Raster`]. Later rows overwrite earlier rows at the same position.
+    pub fn raster(&self, column: &str) -> Result<Raster, Error> {
+        let get = |name: &str| {
+            self.values
+                .get(name)
+                .ok_or_else(|| Error::InvalidName(name.to_string()))
+        };
+        Ok(Raster::new(get("x")?, get("y")?, get(column)?))
+    }
+}
+
+#[cfg(all(feature = "x", feature = "y"))]
+impl Raster {
+    /// Arrange `values` on the grid of distinct `xs` and `ys` positions in micrometres.
+    pub(crate) fn new(xs: &[f64], ys: &[f64], values: &[f64]) -> Self {
+        const TOLERANCE: f64 = 1E-6; // Micrometres
+        let axis = |positions: &[f64]| {
+            let mut axis: Vec<f64> = positions
+                .iter()
+                .copied()
+                .filter(|p| p.is_finite())
+                .collect();
+            axis.sort_unstable_by(f64::total_cmp);
+            axis.dedup_by(|a, b| (*a - *b).abs() < TOLERANCE);
+            axis
+        };
+        let (x, y) = (axis(xs), axis(ys));
+        let index = |axis: &[f64], p: f64| {
+            let i = axis.partition_point(|a| *a < p - TOLERANCE);
+            (i < axis.len()).then_some(i)
+        };
+        let mut raster = Raster {
+            width: x.len(),
+            height: y.len(),
+            values: vec![f64::NAN; x.len() * y.len()],
+            x,
+            y,
+        };
+        for ((px, py), value) in xs.iter().zip(ys).zip(values) {
+            if let (Some(column), Some(row)) = (index(&raster.x, *px), index(&raster.y, *py)) {
+                raster.values[row * raster.width + column] = *value;
+            }
+        }
+        raster
+    }
+}
+
+impl Scalars {
+    /// Append a row for `measurement` with one value per column in the order they were declared.
+    /// Fails without appending anything if the number of values differs from the number of
+    /// columns.
+    pub fn push(&mut self, measurement: u32, values: &[f64]) -> Result<(), Error> {
+        if values.len() != self.values.len() {
+            return Err(Error::InvalidParameter(format!(
+                "Expected {} values but got {}",
+                self.values.len(),
+                values.len()
+            )));
+        }
+        self.measurement.append_value(measurement);
+        let record = self.records.get(&measurement);
+        self.positions
+            .iter_mut()
+            .zip(position(record))
+            .for_each(|(builder, value)| builder.append_value(value));
+        self.values
+            .iter_mut()
+            .zip(values)
+            .for_each(|(builder, value)| builder.append_value(*value));
+        Ok(())
+    }
+
+    pub fn commit(&mut self) -> Result<(), Error> {
+        let columns: Vec<ArrayRef> = [&mut self.measurement]
+            .into_iter()
+            .map(|builder| Arc::new(builder.finish()) as ArrayRef)
+            .chain(
+                self.positions
+                    .iter_mut()
+                    .chain(self.values.iter_mut())
+                    .map(|builder| Arc::new(builder.finish()) as ArrayRef),
+            )
+            .collect();
+        let batch = RecordBatch::try_new(self.schema.clone(), columns)?;
+        self.stream.write(&batch).map_err(Error::from)
+    }
+}
+
+impl Database {
+    fn scalars_directory(&self) -> PathBuf {
+        self.path.join("scalars")
+    }
+
+    /// Create an empty scalar table called `name` with the given value `columns`. Names follow the
+    /// same rules as [`Database::create_derived`]. Column names must be unique and may not shadow
+    /// the position columns.
+    pub fn create_scalars(
+        &self,
+        name: &str,
+        columns: &[&str],
+        provenance: &Provenance,
+    ) -> Result<Scalars, Error> {
+        let directory = self.scalars_directory();
+        let path = Derived::locate(&directory, name)?;
+        let reserved = |(index, column): &(usize, &&str)| {
+            **column == "measurement"
+                || POSITIONS.contains(column)
+                || columns[..*index].contains(column)
+        };
+        if let Some((_, column)) = columns.iter().enumerate().find(reserved) {
+            return Err(Error::InvalidName(column.to_string()));
+        }
+        DirBuilder::new().recursive(true).create(&directory)?;
+        let file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)?;
+        let fields: Vec<Field> = [Field::new("measurement", UInt32, false)]
+            .into_iter()
+            .chain(POSITIONS.iter().map(|p| Field::new(*p, Float64, false)))
+            .chain(columns.iter().map(|c| Field::new(*c, Float64, false)))
+            .collect();
+        let schema = Arc::new(Schema::new(fields).with_metadata(provenance.metadata()));
+        let records = self
+            .measurements
+            .read()?
+            .into_iter()
+            .map(|record| (record.id, record))
+            .collect();
+        Ok(Scalars {
+            stream: new_stream_writer(file, &schema)?,
+            schema,
+            records,
+            measurement: UInt32Builder::new(),
+            positions: POSITIONS.iter().map(|_| Float64Builder::new()).collect(),
+            values: columns.iter().map(|_| Float64Builder::new()).collect(),
+            name: name.to_string(),
+            path,
+        })
+    }
+
+    /// List the names of every scalar table in alphabetical order.
+    pub fn scalars(&self) -> Result<Vec<String>, Error> {
+        list(&self.scalars_directory())
+    }
+
+    /// Read every committed row of the scalar table called `name`.
+    pub fn read_scalars(&self, name: &str) -> Result<Columns, Error> {
+        let path = Derived::locate(&self.scalars_directory(), name)?;
+        let reader = Table(path).reader()?;
+        let provenance = Provenance::from(reader.schema().metadata());
+        let names: Vec<String> = reader
+            .schema()
+            .fields()
+            .iter()
+            .skip(1)
+            .map(|field| field.name().clone())
+            .collect();
+        let mut columns = Columns {
+            provenance,
+            ..Columns::default()
+        };
+        for batch in reader.filter_map(Result::ok) {
+            let measurements = batch.column(0).as_primitive::<UInt32Type>().values();
+            columns.measurements.extend(measurements.iter());
+            for (index, name) in names.iter().enumerate() {
+                let values = batch.column(index + 1).as_primitive::<Float64Type>();
+                columns
+                    .values
+                    .entry(name.clone())
+                    .or_default()
+                    .extend(values.values().iter());
+            }
+        }
+        Ok(columns)
+    }
+
+    /// Delete the scalar table called `name`. Raw data is never affected.
+    pub fn delete_scalars(&self, name: &str) -> Result<(), Error> {
+        let path = Derived::locate(&self.scalars_directory(), name)?;
+        remove_file(path).map_err(Error::from)
+    }
+}
+
+/* ----------------------------------------------------------------------------- Private Helpers */
+
+const POSITIONS: &[&str] = &[
+    #[cfg(feature = "x")]
+    "x",
+    #[cfg(feature = "y")]
+    "y",
+    #[cfg(feature = "z")]
+    "z",
+    #[cfg(feature = "a")]
+    "a",
+];
+
+#[allow(unused_variables)] // `record` is unused when every position feature is disabled
+pub(crate) fn position(record: Option<&Measurement>) -> Vec<f64> {
+    vec![
+        #[cfg(feature = "x")]
+        record.map_or(f64::NAN, |r| r.x.get::<micrometer>()),
+        #[cfg(feature = "y")]
+        record.map_or(f64::NAN, |r| r.y.get::<micrometer>()),
+        #[cfg(feature = "z")]
+        record.map_or(f64::NAN, |r| r.z.get::<micrometer>()),
+        #[cfg(feature = "a")]
+        record.map_or(f64::NAN, |r| r.a.get::<micrometer>()),
+    ]
+}
+
+/* ----------------------------------------------------------------------- Trait Implementations */
+
+impl Reader for Scalars {
+    fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+/* ---------------------------------------------------------------------------------- Unit Tests */
+
+#[cfg(all(
+    test,
+    feature = "x",
+    feature = "y",
+    not(feature = "z"),
+    not(feature = "a")
+))]
+mod tests {
+    use std::fs::remove_dir_all;
+
+    use uom::si::f64::{Length, Time};
+    use uom::si::time::millisecond;
+
+    use super::*;
+    use crate::Kind;
+
+    #[test]
+    fn scalars_lifecycle() {
+        const PATH: &str = "test-scalars";
+        let mut db = Database::new(PATH).unwrap();
+        let x = Length::new::<micrometer>(5.0);
+        let y = Length::new::<micrometer>(7.0);
+        let integration = Time::new::<millisecond>(10.0);
+        let id = db
+            .measurements
+            .push(Kind::Sample, x, y, integration, &[])
+            .unwrap();
+        db.measurements.commit().unwrap();
+
+        let provenance = Provenance::new("test", &["intensities"]);
+        assert!(db.create_scalars("bad", &["x"], &provenance).is_err()); // Shadows a position
+        let mut table = db.create_scalars("index", &["ndvi"], &provenance).unwrap();
+        assert!(table.push(id, &[0.5, 1.0]).is_err()); // One value per column
+        table.push(id, &[0.5]).unwrap();
+        table.commit().unwrap();
+
+        assert_eq!(db.scalars().unwrap(), vec!["index"]);
+        let columns = db.read_scalars("index").unwrap();
+        assert_eq!(columns.measurements, vec![id]);
+        assert_eq!(columns.values["ndvi"], vec![0.5]);
+        assert!((columns.values["x"][0] - 5.0).abs() < 1E-9);
+        assert_eq!(columns.provenance, provenance);
+        let raster = columns.raster("ndvi").unwrap();
+        assert_eq!((raster.width, raster.height), (1, 1));
+        assert_eq!(raster.values, vec![0.5]);
+        db.delete_scalars("index").unwrap();
+        assert!(db.scalars().unwrap().is_empty());
+        remove_dir_all(PATH).unwrap();
+    }
+}
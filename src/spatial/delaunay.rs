@@ -0,0 +1,134 @@
+/*
+Project: Optic
+GitHub: https://github.com/MillieFD/optic
+
+BSD 3-Clause License, Copyright (c) 2026, Amelia Fraser-Dale
+
+Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the conditions of the LICENSE are met.
+*/
+
+/* ----------------------------------------------------------------------------- Private Imports */
+
+use std::collections::HashMap;
+
+/* ------------------------------------------------------------------------------ Public Exports */
+
+/// Delaunay triangulation of distinct `points` by the Bowyer–Watson algorithm. Returns the
+/// vertex indices of each triangle. Collinear inputs give no triangles.
+pub(super) fn triangulate(points: &[[f64; 2]]) -> Vec<[usize; 3]> {
+    let n = points.len();
+    if n < 3 {
+        return Vec::new();
+    }
+    let (mut lower, mut upper) = ([f64::MAX; 2], [f64::MIN; 2]);
+    for p in points {
+        lower = [lower[0].min(p[0]), lower[1].min(p[1])];
+        upper = [upper[0].max(p[0]), upper[1].max(p[1])];
+    }
+    let span = (upper[0] - lower[0]).max(upper[1] - lower[1]).max(1.0);
+    let centre = [(lower[0] + upper[0]) / 2.0, (lower[1] + upper[1]) / 2.0];
+    let mut vertices = points.to_vec();
+    vertices.extend([
+        [centre[0] - 20.0 * span, centre[1] - span],
+        [centre[0] + 20.0 * span, centre[1] - span],
+        [centre[0], centre[1] + 20.0 * span],
+    ]);
+    let mut triangles: Vec<Triangle> = vec![Triangle::new(&vertices, [n, n + 1, n + 2])];
+    for (index, point) in points.iter().enumerate() {
+        let (bad, good): (Vec<Triangle>, Vec<Triangle>) = triangles
+            .into_iter()
+            .partition(|triangle| triangle.encloses(*point));
+        triangles = good;
+        let mut edges: HashMap<(usize, usize), usize> = HashMap::new();
+        for triangle in &bad {
+            for edge in triangle.edges() {
+                *edges
+                    .entry((edge.0.min(edge.1), edge.0.max(edge.1)))
+                    .or_default() += 1;
+            }
+        }
+        for triangle in &bad {
+            for (a, b) in triangle.edges() {
+                if edges[&(a.min(b), a.max(b))] == 1 {
+                    triangles.push(Triangle::new(&vertices, [a, b, index]));
+                }
+            }
+        }
+    }
+    triangles
+        .into_iter()
+        .filter(|triangle| triangle.vertices.iter().all(|v| *v < n))
+        .filter(|triangle| triangle.radius2.is_finite())
+        .map(|triangle| triangle.vertices)
+        .collect()
+}
+
+/// Barycentric weights of `point` in the triangle with corners `a`, `b` and `c`, or `None` if it
+/// lies outside.
+pub(super) fn barycentric(point: [f64; 2], [a, b, c]: [[f64; 2]; 3]) -> Option<[f64; 3]> {
+    const TOLERANCE: f64 = 1E-12;
+    let area = (b[0] - a[0]) * (c[1] - a[1]) - (c[0] - a[0]) * (b[1] - a[1]);
+    if area == 0.0 {
+        return None;
+    }
+    let wb = ((point[0] - a[0]) * (c[1] - a[1]) - (c[0] - a[0]) * (point[1] - a[1])) / area;
+    let wc = ((b[0] - a[0]) * (point[1] - a[1]) - (point[0] - a[0]) * (b[1] - a[1])) / area;
+    let weights = [1.0 - wb - wc, wb, wc];
+    weights.iter().all(|w| *w >= -TOLERANCE).then_some(weights)
+}
+
+/* ----------------------------------------------------------------------------- Private Helpers */
+
+struct Triangle {
+    vertices: [usize; 3],
+    centre: [f64; 2],
+    radius2: f64,
+}
+
+impl Triangle {
+    fn new(points: &[[f64; 2]], vertices: [usize; 3]) -> Self {
+        let [a, b, c] = vertices.map(|v| points[v]);
+        let d = 2.0 * (a[0] * (b[1] - c[1]) + b[0] * (c[1] - a[1]) + c[0] * (a[1] - b[1]));
+        let norm = |p: [f64; 2]| p[0] * p[0] + p[1] * p[1];
+        let centre = [
+            (norm(a) * (b[1] - c[1]) + norm(b) * (c[1] - a[1]) + norm(c) * (a[1] - b[1])) / d,
+            (norm(a) * (c[0] - b[0]) + norm(b) * (a[0] - c[0]) + norm(c) * (b[0] - a[0])) / d,
+        ];
+        let radius2 = (a[0] - centre[0]).powi(2) + (a[1] - centre[1]).powi(2);
+        Self {
+            vertices,
+            centre,
+            radius2,
+        }
+    }
+
+    fn encloses(&self, point: [f64; 2]) -> bool {
+        (point[0] - self.centre[0]).powi(2) + (point[1] - self.centre[1]).powi(2) < self.radius2
+    }
+
+    fn edges(&self) -> [(usize, usize); 3] {
+        let [a, b, c] = self.vertices;
+        [(a, b), (b, c), (c, a)]
+    }
+}
+
+/* ---------------------------------------------------------------------------------- Unit Tests */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn triangulate_square() {
+        let square = [[0.0, 0.0], [1.0, 0.0], [0.0, 1.0], [1.0, 1.0], [0.5, 0.5]];
+        let triangles = triangulate(&square);
+        assert_eq!(triangles.len(), 4);
+        let weights = triangles
+            .iter()
+            .find_map(|t| barycentric([0.25, 0.5], t.map(|v| square[v])))
+            .unwrap();
+        assert!((weights.iter().sum::<f64>() - 1.0).abs() < 1E-12);
+        assert!(triangulate(&[[0.0, 0.0], [1.0, 0.0], [2.0, 0.0]]).is_empty());
+    }
+}
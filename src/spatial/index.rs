@@ -0,0 +1,257 @@
+/*
+Project: Optic
+GitHub: https://github.com/MillieFD/optic
+
+BSD 3-Clause License, Copyright (c) 2026, Amelia Fraser-Dale
+
+Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the conditions of the LICENSE are met.
+*/
+
+/* ----------------------------------------------------------------------------- Private Imports */
+
+use std::collections::{HashMap, HashSet};
+
+/* ------------------------------------------------------------------------------ Public Exports */
+
+/// Uniform grid of square `x`/`y` cells over identified points for neighbour queries. Points carry
+/// an optional third coordinate that is included in distances but not in the cells, so planar
+/// scans pay nothing for it. The cell size adapts to the point density as points are inserted.
+pub(crate) struct Index {
+    cell: f64,
+    cells: HashMap<(i64, i64), Vec<usize>>,
+    points: Vec<(u32, [f64; 3])>,
+    ids: HashSet<u32>,
+    /// Lowest and highest occupied cell keys on each axis.
+    bounds: [(i64, i64); 2],
+    /// Number of points when the cells were last sized. They are resized whenever it doubles.
+    sized: usize,
+}
+
+impl Index {
+    /// Create an empty index with cells of side `cell`.
+    pub(crate) fn new(cell: f64) -> Self {
+        Self {
+            cell: cell.max(f64::MIN_POSITIVE),
+            cells: HashMap::new(),
+            points: Vec::new(),
+            ids: HashSet::new(),
+            bounds: [(i64::MAX, i64::MIN); 2],
+            sized: 0,
+        }
+    }
+
+    /// Insert the point `id` unless it is already indexed.
+    pub(crate) fn insert(&mut self, id: u32, point: [f64; 3]) {
+        if !self.ids.insert(id) {
+            return;
+        }
+        self.points.push((id, point));
+        if self.points.len() >= 2 * self.sized {
+            self.resize();
+        } else {
+            self.place(self.points.len() - 1);
+        }
+    }
+
+    /// IDs and distances of the `count` points nearest to `target`, nearest first.
+    pub(crate) fn nearest(&self, target: [f64; 3], count: usize) -> Vec<(u32, f64)> {
+        if self.points.is_empty() || count == 0 {
+            return Vec::new();
+        }
+        let (cx, cy) = self.key(target);
+        let [(x0, x1), (y0, y1)] = self.bounds;
+        // Rings closer than the occupied cells are empty
+        let start = [x0 - cx, cx - x1, y0 - cy, cy - y1, 0]
+            .into_iter()
+            .max()
+            .unwrap_or(0);
+        let extent = [cx - x0, x1 - cx, cy - y0, y1 - cy]
+            .into_iter()
+            .max()
+            .unwrap_or(0);
+        let mut found: Vec<(u32, f64)> = Vec::new();
+        for ring in start..=extent {
+            // Every point outside the searched rings is at least this far away
+            let reach = (ring as f64 - 1.0).max(0.0) * self.cell;
+            if found.len() >= count && found[count - 1].1 <= reach {
+                break;
+            }
+            for key in ring_keys(cx, cy, ring) {
+                for &i in self.cells.get(&key).into_iter().flatten() {
+                    let (id, point) = self.points[i];
+                    found.push((id, distance(point, target)));
+                }
+            }
+            found.sort_unstable_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+        }
+        found.truncate(count);
+        found
+    }
+
+    /// IDs and distances of every point within `radius` of `target`, nearest first.
+    pub(crate) fn within(&self, target: [f64; 3], radius: f64) -> Vec<(u32, f64)> {
+        let mut found: Vec<(u32, f64)> = self
+            .candidates(
+                [target[0] - radius, target[1] - radius],
+                [target[0] + radius, target[1] + radius],
+            )
+            .map(|(id, point)| (id, distance(point, target)))
+            .filter(|(_, distance)| *distance <= radius)
+            .collect();
+        found.sort_unstable_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+        found
+    }
+
+    /// IDs of every point whose `x`/`y` position lies inside `polygon`, in ascending order.
+    pub(crate) fn inside(&self, polygon: &[[f64; 2]]) -> Vec<u32> {
+        if polygon.len() < 3 {
+            return Vec::new();
+        }
+        let (mut lower, mut upper) = ([f64::MAX; 2], [f64::MIN; 2]);
+        for p in polygon {
+            lower = [lower[0].min(p[0]), lower[1].min(p[1])];
+            upper = [upper[0].max(p[0]), upper[1].max(p[1])];
+        }
+        let mut found: Vec<u32> = self
+            .candidates(lower, upper)
+            .filter(|(_, point)| contains(polygon, [point[0], point[1]]))
+            .map(|(id, _)| id)
+            .collect();
+        found.sort_unstable();
+        found
+    }
+
+    /// Points in every cell overlapping the `x`/`y` box from `lower` to `upper`.
+    fn candidates(
+        &self,
+        lower: [f64; 2],
+        upper: [f64; 2],
+    ) -> impl Iterator<Item = (u32, [f64; 3])> + '_ {
+        let (l, u) = (
+            self.key([lower[0], lower[1], 0.0]),
+            self.key([upper[0], upper[1], 0.0]),
+        );
+        let [(x0, x1), (y0, y1)] = self.bounds;
+        let (l, u) = ((l.0.max(x0), l.1.max(y0)), (u.0.min(x1), u.1.min(y1)));
+        (l.0..=u.0)
+            .flat_map(move |x| (l.1..=u.1).map(move |y| (x, y)))
+            .filter_map(|key| self.cells.get(&key))
+            .flatten()
+            .map(|&i| self.points[i])
+    }
+
+    fn key(&self, [x, y, _]: [f64; 3]) -> (i64, i64) {
+        (
+            (x / self.cell).floor() as i64,
+            (y / self.cell).floor() as i64,
+        )
+    }
+
+    fn place(&mut self, i: usize) {
+        let key = self.key(self.points[i].1);
+        for (bound, k) in self.bounds.iter_mut().zip([key.0, key.1]) {
+            *bound = (bound.0.min(k), bound.1.max(k));
+        }
+        self.cells.entry(key).or_default().push(i);
+    }
+
+    /// Size cells to hold about one point each over the occupied area, or along the longest side
+    /// for scans that lie on a line, and rebuild them.
+    fn resize(&mut self) {
+        let (mut lower, mut upper) = ([f64::MAX; 2], [f64::MIN; 2]);
+        for (_, p) in &self.points {
+            lower = [lower[0].min(p[0]), lower[1].min(p[1])];
+            upper = [upper[0].max(p[0]), upper[1].max(p[1])];
+        }
+        let [width, height] = [upper[0] - lower[0], upper[1] - lower[1]];
+        let count = self.points.len() as f64;
+        let side = (width * height / count)
+            .sqrt()
+            .max(width.max(height) / count);
+        if side.is_normal() {
+            self.cell = side;
+        }
+        self.cells.clear();
+        self.bounds = [(i64::MAX, i64::MIN); 2];
+        (0..self.points.len()).for_each(|i| self.place(i));
+        self.sized = self.points.len();
+    }
+}
+
+/* ----------------------------------------------------------------------------- Private Helpers */
+
+fn distance(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| (a - b).powi(2))
+        .sum::<f64>()
+        .sqrt()
+}
+
+/// Keys of the cells on the square ring at Chebyshev distance `ring` from `(x, y)`.
+fn ring_keys(x: i64, y: i64, ring: i64) -> Vec<(i64, i64)> {
+    if ring == 0 {
+        return vec![(x, y)];
+    }
+    let rows = (-ring..=ring).flat_map(|dx| [(x + dx, y - ring), (x + dx, y + ring)]);
+    let columns = (1 - ring..ring).flat_map(|dy| [(x - ring, y + dy), (x + ring, y + dy)]);
+    rows.chain(columns).collect()
+}
+
+/// Whether `point` lies inside `polygon` by the even-odd rule.
+fn contains(polygon: &[[f64; 2]], [x, y]: [f64; 2]) -> bool {
+    let mut inside = false;
+    let mut previous = polygon[polygon.len() - 1];
+    for current in polygon {
+        if (current[1] > y) != (previous[1] > y) {
+            let crossing = current[0]
+                + (y - current[1]) * (previous[0] - current[0]) / (previous[1] - current[1]);
+            if x < crossing {
+                inside = !inside;
+            }
+        }
+        previous = *current;
+    }
+    inside
+}
+
+/* ---------------------------------------------------------------------------------- Unit Tests */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn neighbour_queries() {
+        let mut index = Index::new(1.0);
+        let points: Vec<[f64; 3]> = (0..200)
+            .map(|i| [(i % 20) as f64 * 3.0, (i / 20) as f64 * 3.0, 0.0])
+            .collect();
+        points
+            .iter()
+            .enumerate()
+            .for_each(|(i, p)| index.insert(i as u32, *p));
+        index.insert(0, [100.0, 100.0, 0.0]); // Already indexed
+        assert_eq!(index.points.len(), 200);
+        let brute = |target: [f64; 3]| {
+            let mut all: Vec<(u32, f64)> = points
+                .iter()
+                .enumerate()
+                .map(|(i, p)| (i as u32, distance(*p, target)))
+                .collect();
+            all.sort_unstable_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+            all
+        };
+        for target in [[10.2, 7.9, 0.0], [-50.0, 200.0, 0.0], [30.0, 13.0, 4.0]] {
+            assert_eq!(index.nearest(target, 5), brute(target)[..5].to_vec());
+            let expected: Vec<(u32, f64)> = brute(target)
+                .into_iter()
+                .filter(|(_, d)| *d <= 7.0)
+                .collect();
+            assert_eq!(index.within(target, 7.0), expected);
+        }
+        let triangle = [[-1.0, -1.0], [8.0, -1.0], [-1.0, 8.0]];
+        assert_eq!(index.inside(&triangle), vec![0, 1, 2, 20, 21, 40]);
+    }
+}
@@ -0,0 +1,347 @@
+/*
+Project: Optic
+GitHub: https://github.com/MillieFD/optic
+
+BSD 3-Clause License, Copyright (c) 2026, Amelia Fraser-Dale
+
+Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the conditions of the LICENSE are met.
+*/
+
+/* ----------------------------------------------------------------------------- Private Modules */
+
+mod delaunay;
+pub(crate) mod index;
+
+/* ----------------------------------------------------------------------------- Private Imports */
+
+use std::collections::BTreeSet;
+
+use uom::si::f64::Length;
+use uom::si::length::micrometer;
+
+use self::index::Index;
+use crate::{Database, Error, Raster, Source};
+
+/* ------------------------------------------------------------------------------ Public Exports */
+
+/// How spectra at irregular `x`/`y` positions are interpolated onto a regular grid.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Gridding {
+    /// Use the spectrum of the nearest measurement.
+    Nearest,
+    /// Weight every measurement within `radius` by the inverse distance raised to `power`.
+    /// Pixels without a measurement in range are `NaN`.
+    InverseDistance { power: f64, radius: Length },
+    /// Interpolate linearly inside the Delaunay triangulation of the measurements. Pixels outside
+    /// their convex hull are `NaN`.
+    Linear,
+}
+
+/// Spectra on a regular grid covering the measured positions.
+///
+/// Values are stored band-sequentially: pixel `(column, row)` of band `b` is at
+/// `values[(b * height + row) * width + column]`, with `x` increasing by column and `y` by row.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Cube {
+    pub width: usize,
+    pub height: usize,
+    /// Column positions in micrometres.
+    pub x: Vec<f64>,
+    /// Row positions in micrometres.
+    pub y: Vec<f64>,
+    /// Wavelength ID of each band.
+    pub wavelengths: Vec<u32>,
+    pub values: Vec<f64>,
+    /// Distance from each pixel to the nearest measurement in micrometres.
+    pub distance: Vec<f64>,
+}
+
+impl Cube {
+    /// Image of band `band`.
+    pub fn band(&self, band: usize) -> Raster {
+        let size = self.width * self.height;
+        Raster {
+            width: self.width,
+            height: self.height,
+            x: self.x.clone(),
+            y: self.y.clone(),
+            values: self.values[band * size..(band + 1) * size].to_vec(),
+        }
+    }
+
+    /// Whether each pixel lies within `limit` of a measurement.
+    pub fn mask(&self, limit: Length) -> Vec<bool> {
+        let limit = limit.get::<micrometer>();
+        self.distance.iter().map(|d| *d <= limit).collect()
+    }
+}
+
+impl Database {
+    /// Interpolate the spectra of `measurements` from `source` onto a grid with square pixels of
+    /// side `spacing` spanning their `x`/`y` extent. Only wavelengths present in every spectrum
+    /// are gridded. Measurements at the same position are averaged first. Fails if the cube
+    /// would hold more than 2²⁸ values.
+    pub fn grid(
+        &self,
+        source: Source,
+        measurements: &[u32],
+        spacing: Length,
+        method: Gridding,
+    ) -> Result<Cube, Error> {
+        let spacing = spacing.get::<micrometer>();
+        if spacing.is_nan() || spacing <= 0.0 {
+            return Err(Error::InvalidParameter("Spacing must be positive".into()));
+        }
+        let spectra = self.spectra(source, measurements)?;
+        let mut records = self.measurements.read()?;
+        records.retain(|record| spectra.contains_key(&record.id));
+        records.sort_unstable_by_key(|record| record.id);
+        let wavelengths: Vec<u32> = records
+            .iter()
+            .map(|record| {
+                spectra[&record.id]
+                    .keys()
+                    .copied()
+                    .collect::<BTreeSet<u32>>()
+            })
+            .reduce(|common, keys| &common & &keys)
+            .unwrap_or_default()
+            .into_iter()
+            .collect();
+        let bounds = |axis: usize| {
+            records.iter().fold((f64::MAX, f64::MIN), |(l, u), record| {
+                let p = [record.x, record.y][axis].get::<micrometer>();
+                (l.min(p), u.max(p))
+            })
+        };
+        let count = |(lower, upper): (f64, f64)| match records.is_empty() {
+            true => 0,
+            false => (((upper - lower) / spacing + TOLERANCE).floor() as usize).saturating_add(1),
+        };
+        let (width, height, bands) = (count(bounds(0)), count(bounds(1)), wavelengths.len());
+        if width
+            .checked_mul(height)
+            .and_then(|size| size.checked_mul(bands))
+            .is_none_or(|values| values > MAX_VALUES)
+        {
+            let e = format!("{width} × {height} pixels of {bands} bands exceed the grid limit");
+            return Err(Error::InvalidParameter(e));
+        }
+        // Average measurements at the same position
+        let mut samples: Vec<([f64; 2], Vec<f64>, usize)> = Vec::new();
+        let mut positions = Index::new(spacing);
+        for record in &records {
+            let point = [record.x.get::<micrometer>(), record.y.get::<micrometer>()];
+            let values = wavelengths.iter().map(|w| spectra[&record.id][w]);
+            match positions.nearest([point[0], point[1], 0.0], 1).first() {
+                Some(&(i, distance)) if distance < TOLERANCE => {
+                    let i = i as usize;
+                    samples[i]
+                        .1
+                        .iter_mut()
+                        .zip(values)
+                        .for_each(|(s, v)| *s += v);
+                    samples[i].2 += 1;
+                }
+                _ => {
+                    positions.insert(samples.len() as u32, [point[0], point[1], 0.0]);
+                    samples.push((point, values.collect(), 1));
+                }
+            }
+        }
+        let points: Vec<[f64; 2]> = samples.iter().map(|(p, ..)| *p).collect();
+        let values: Vec<Vec<f64>> = samples
+            .into_iter()
+            .map(|(_, sum, count)| sum.into_iter().map(|s| s / count as f64).collect())
+            .collect();
+        let axis = |axis: usize, count: usize| -> Vec<f64> {
+            let (lower, _) = bounds(axis);
+            (0..count).map(|i| lower + i as f64 * spacing).collect()
+        };
+        let (x, y) = (axis(0, width), axis(1, height));
+        let size = width * height;
+        let mut cube = vec![f64::NAN; size * bands];
+        let mut set = |pixel: usize, weights: &[(usize, f64)]| {
+            let total: f64 = weights.iter().map(|(_, w)| w).sum();
+            for (band, value) in cube.iter_mut().skip(pixel).step_by(size).enumerate() {
+                *value = weights
+                    .iter()
+                    .map(|(i, w)| values[*i][band] * w)
+                    .sum::<f64>()
+                    / total;
+            }
+        };
+        let mut distances = vec![f64::NAN; size];
+        for (row, y) in y.iter().enumerate() {
+            for (column, x) in x.iter().enumerate() {
+                let pixel = row * width + column;
+                let Some(&(nearest, distance)) = positions.nearest([*x, *y, 0.0], 1).first() else {
+                    continue;
+                };
+                let nearest = nearest as usize;
+                distances[pixel] = distance;
+                match method {
+                    Gridding::Nearest => set(pixel, &[(nearest, 1.0)]),
+                    Gridding::InverseDistance { .. } if distance < TOLERANCE => {
+                        set(pixel, &[(nearest, 1.0)])
+                    }
+                    Gridding::InverseDistance { power, radius } => {
+                        let weights: Vec<(usize, f64)> = positions
+                            .within([*x, *y, 0.0], radius.get::<micrometer>())
+                            .into_iter()
+                            .map(|(i, d)| (i as usize, d.powf(-power)))
+                            .collect();
+                        if !weights.is_empty() {
+                            set(pixel, &weights);
+                        }
+                    }
+                    Gridding::Linear => {}
+                }
+            }
+        }
+        if method == Gridding::Linear {
+            let range = |axis: &[f64], lower: f64, upper: f64| {
+                axis.partition_point(|a| *a < lower - TOLERANCE)
+                    ..axis.partition_point(|a| *a <= upper + TOLERANCE)
+            };
+            for triangle in delaunay::triangulate(&points) {
+                let corners = triangle.map(|v| points[v]);
+                let [xs, ys] = [0, 1].map(|axis| corners.map(|c| c[axis]));
+                let bounds = |v: [f64; 3]| {
+                    (
+                        v.into_iter().fold(f64::MAX, f64::min),
+                        v.into_iter().fold(f64::MIN, f64::max),
+                    )
+                };
+                let ((x0, x1), (y0, y1)) = (bounds(xs), bounds(ys));
+                for row in range(&y, y0, y1) {
+                    for column in range(&x, x0, x1) {
+                        if let Some(weights) = delaunay::barycentric([x[column], y[row]], corners) {
+                            let weights: Vec<(usize, f64)> =
+                                triangle.into_iter().zip(weights).collect();
+                            set(row * width + column, &weights);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(Cube {
+            width,
+            height,
+            x,
+            y,
+            wavelengths,
+            values: cube,
+            distance: distances,
+        })
+    }
+}
+
+/* ----------------------------------------------------------------------------- Private Helpers */
+
+/// Positions closer than this many micrometres are treated as equal.
+const TOLERANCE: f64 = 1E-6;
+
+/// Largest number of values in a gridded cube, 2 GiB of `f64`.
+const MAX_VALUES: usize = 1 << 28;
+
+/* ---------------------------------------------------------------------------------- Unit Tests */
+
+#[cfg(all(
+    test,
+    feature = "x",
+    feature = "y",
+    not(feature = "z"),
+    not(feature = "a")
+))]
+mod tests {
+    use std::fs::remove_dir_all;
+
+    use uom::si::f64::Time;
+    use uom::si::time::millisecond;
+
+    use super::*;
+    use crate::Kind;
+
+    #[test]
+    fn grid_irregular_scan() {
+        const PATH: &str = "test-spatial";
+        let mut db = Database::new(PATH).unwrap();
+        let wavelengths = db.wavelengths.push(vec![500.0, 600.0]).unwrap();
+        db.wavelengths.commit().unwrap();
+        let integration = Time::new::<millisecond>(10.0);
+        let plane = |x: f64, y: f64| x + 2.0 * y;
+        let positions = [
+            (0.0, 0.0),
+            (4.0, 0.0),
+            (0.0, 4.0),
+            (4.0, 4.0),
+            (1.3, 2.6),
+            (4.0, 4.0),
+        ];
+        let ids: Vec<u32> = positions
+            .into_iter()
+            .map(|(x, y)| {
+                let id = db
+                    .measurements
+                    .push(
+                        Kind::Sample,
+                        Length::new::<micrometer>(x),
+                        Length::new::<micrometer>(y),
+                        integration,
+                        &[],
+                    )
+                    .unwrap();
+                db.intensities
+                    .push(id, &wavelengths, vec![plane(x, y), 1.0]);
+                id
+            })
+            .collect();
+        db.measurements.commit().unwrap();
+        db.intensities.commit().unwrap();
+        let spacing = Length::new::<micrometer>(1.0);
+
+        let linear = db
+            .grid(Source::Intensities, &ids, spacing, Gridding::Linear)
+            .unwrap();
+        assert_eq!((linear.width, linear.height), (5, 5));
+        assert_eq!(linear.wavelengths, wavelengths);
+        let band = linear.band(0);
+        for (row, y) in band.y.iter().enumerate() {
+            for (column, x) in band.x.iter().enumerate() {
+                let value = band.values[row * band.width + column];
+                assert!((value - plane(*x, *y)).abs() < 1E-9);
+            }
+        }
+        assert!(linear.band(1).values.iter().all(|v| (v - 1.0).abs() < 1E-9));
+
+        let nearest = db
+            .grid(Source::Intensities, &ids, spacing, Gridding::Nearest)
+            .unwrap();
+        assert_eq!(nearest.values[24], plane(4.0, 4.0)); // Duplicates are averaged
+        assert_eq!(nearest.values[2 * 5 + 1], plane(1.3, 2.6));
+        assert!((nearest.distance[2 * 5 + 1] - 0.3f64.hypot(0.6)).abs() < 1E-9);
+        let mask = nearest.mask(Length::new::<micrometer>(1.0));
+        assert!(mask[0] && !mask[2]); // (2, 0) is 2 µm from (0, 0) and (4, 0)
+
+        let idw = Gridding::InverseDistance {
+            power: 2.0,
+            radius: Length::new::<micrometer>(1.5),
+        };
+        let idw = db.grid(Source::Intensities, &ids, spacing, idw).unwrap();
+        assert_eq!(idw.values[0], 0.0);
+        assert!(idw.values[2].is_nan()); // (2, 0) is beyond the radius
+        let (near, far) = (plane(1.3, 2.6), plane(0.0, 4.0));
+        let (wn, wf) = (1.0 / 0.3f64.hypot(0.4).powi(2), 1.0 / 2.0);
+        let expected = (near * wn + far * wf) / (wn + wf);
+        assert!((idw.values[3 * 5 + 1] - expected).abs() < 1E-9);
+
+        for spacing in [1E-6, f64::MIN_POSITIVE] {
+            let spacing = Length::new::<micrometer>(spacing);
+            let grid = db.grid(Source::Intensities, &ids, spacing, Gridding::Nearest);
+            assert!(matches!(grid, Err(Error::InvalidParameter(_)))); // Too many pixels
+        }
+        remove_dir_all(PATH).unwrap();
+    }
+}
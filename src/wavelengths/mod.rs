@@ -15,23 +15,22 @@ mod record;
 
 /* ----------------------------------------------------------------------------- Private Imports */
 
-use std::fs::{File, OpenOptions};
+use std::collections::HashMap;
+use std::fs::File;
 use std::ops::Sub;
 use std::path::{Path, PathBuf};
-use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::{Arc, LazyLock};
 
 use arrow::array::{AsArray, RecordBatch};
 use arrow::datatypes::DataType::{Float64, UInt32};
 use arrow::datatypes::{Field, Float64Type, Schema, UInt32Type};
-use arrow::ipc::reader::StreamReader;
 use arrow::ipc::writer::StreamWriter;
 use uom::si::f64::Length;
 use uom::si::length::nanometer;
 
 use self::builder::Builder;
 use self::record::Record;
-use crate::{Error, Writer};
+use crate::{Error, Reader, Writer};
 
 /* ------------------------------------------------------------------------------ Public Exports */
 
@@ -52,11 +51,10 @@ impl Wavelengths {
             .try_into()
     }
 
-    fn read(&self) -> Vec<Record> {
-        let file = File::open(&self.path).expect("Unable to open 'wavelengths' file");
-        StreamReader::try_new(file, None)
-            .expect("Unable to read 'wavelengths' file")
-            .filter_map(Result::ok)
+    fn read(&self) -> Result<Vec<Record>, Error> {
+        let records = self
+            .batches()?
+            .iter()
             .fold(Vec::new(), |mut records, batch| {
                 let nms = batch
                     .column_by_name("nm")
@@ -77,29 +75,43 @@ impl Wavelengths {
                     .map(Record::from)
                     .collect_into(&mut records)
                     .to_owned()
-            })
+            });
+        Ok(records)
+    }
+
+    /// Read every committed wavelength keyed by wavelength ID.
+    pub fn lookup(&self) -> Result<HashMap<u32, Length>, Error> {
+        let records = self.read()?;
+        Ok(records
+            .into_iter()
+            .map(|record| (record.id, record.nm))
+            .collect())
     }
 
     pub fn push(&mut self, wavelengths: Vec<f64>) -> Result<Vec<u32>, Error> {
-        const TOLERANCE: f64 = 1E-12;
-        let mut records = self.read();
+        const TOLERANCE_NM: f64 = 1E-3;
+        let mut records = self.read()?;
         records.sort_unstable(); // In-place sort does not allocate
-        let next = AtomicU32::new(records.last().map_or(0, |record| record.id + 1));
-        let ids = wavelengths
+        let mut next = records
             .iter()
-            .map(|wl| Length::new::<nanometer>(*wl))
-            .scan(records.iter(), |iter, wl| {
-                loop {
-                    match iter.next() {
-                        Some(record) if record.nm.sub(wl).abs().value < TOLERANCE => {
-                            break Some(record.id);
-                        }
-                        Some(record) if record.nm.sub(wl).value > TOLERANCE => continue,
-                        _ => {
-                            let id = next.fetch_add(1, Ordering::Relaxed);
-                            self.builder.push(id, wl);
-                            break Some(id);
-                        }
+            .map(|record| record.id + 1)
+            .max()
+            .unwrap_or(0);
+        let ids = wavelengths
+            .into_iter()
+            .map(Length::new::<nanometer>)
+            .map(|wl| {
+                let index = records
+                    .partition_point(|record| record.nm.sub(wl).get::<nanometer>() < -TOLERANCE_NM);
+                match records.get(index) {
+                    Some(record) if record.nm.sub(wl).abs().get::<nanometer>() < TOLERANCE_NM => {
+                        record.id
+                    }
+                    _ => {
+                        let id = next;
+                        next += 1;
+                        self.builder.push(id, wl);
+                        id
                     }
                 }
             })
@@ -110,7 +122,7 @@ impl Wavelengths {
     pub fn commit(&mut self) -> Result<(), Error> {
         let columns = self.builder.columns();
         let batch = RecordBatch::try_new(Self::schema(), columns)?;
-        self.stream.write(&batch).map_err(Error::from)
+        self.write(&batch)
     }
 }
 
@@ -124,20 +136,24 @@ impl Writer for Wavelengths {
         ];
         Schema::new(fields).into()
     });
+
+    fn stream(&mut self) -> &mut StreamWriter<File> {
+        &mut self.stream
+    }
+}
+
+impl Reader for Wavelengths {
+    fn path(&self) -> &Path {
+        &self.path
+    }
 }
 
 impl TryFrom<PathBuf> for Wavelengths {
     type Error = Error;
 
     fn try_from(path: PathBuf) -> Result<Self, Self::Error> {
-        let file = OpenOptions::new()
-            .read(false)
-            .write(true)
-            .create(true)
-            .truncate(false)
-            .open(&path)?;
         Ok(Self {
-            stream: Self::new_stream_writer(file)?,
+            stream: Self::open_stream_writer(&path)?,
             builder: Builder::new(),
             path,
         })
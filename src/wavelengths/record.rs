@@ -46,7 +46,7 @@ impl PartialEq<Self> for Record {
 
 impl PartialOrd<Self> for Record {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.nm.partial_cmp(&other.nm)
+        Some(self.cmp(other))
     }
 }
 
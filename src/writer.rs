@@ -10,33 +10,115 @@ modification, are permitted provided that the conditions of the LICENSE are met.
 
 /* ----------------------------------------------------------------------------- Private Imports */
 
-use std::fs::File;
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
 use std::sync::{Arc, LazyLock};
 
+use arrow::array::RecordBatch;
 use arrow::datatypes::Schema;
 use arrow::error::ArrowError;
-use arrow::ipc::CompressionType;
+use arrow::ipc::reader::StreamReader;
 use arrow::ipc::writer::{IpcWriteOptions, StreamWriter};
+use arrow::ipc::{CompressionType, root_as_message};
+
+use crate::Error;
 
 /* ------------------------------------------------------------------------------- Pubic Exports */
 
+#[allow(
+    clippy::declare_interior_mutable_const,
+    clippy::borrow_interior_mutable_const
+)]
 pub(super) trait Writer {
     const SCHEMA: LazyLock<Arc<Schema>>;
 
+    fn stream(&mut self) -> &mut StreamWriter<File>;
+
     fn schema() -> Arc<Schema> {
         Self::SCHEMA.clone() // Inexpensive Arc Clone
     }
 
-    fn ipc_write_options() -> IpcWriteOptions {
-        let compression = Some(CompressionType::ZSTD);
-        IpcWriteOptions::default()
-            .try_with_compression(compression)
-            .unwrap()
+    fn open_stream_writer(path: &Path) -> Result<StreamWriter<File>, Error> {
+        open_stream_writer(path, &Self::schema())
     }
 
-    fn new_stream_writer(file: File) -> Result<StreamWriter<File>, ArrowError> {
-        let options = Self::ipc_write_options();
-        let stream = StreamWriter::try_new_with_options(file, &Self::SCHEMA, options)?;
-        Ok(stream)
+    fn write(&mut self, batch: &RecordBatch) -> Result<(), Error> {
+        self.stream().write(batch).map_err(Error::from)
     }
 }
+
+/// Create a compressed IPC stream writer for a table whose schema is only known at runtime.
+pub(super) fn new_stream_writer(
+    file: File,
+    schema: &Schema,
+) -> Result<StreamWriter<File>, ArrowError> {
+    let compression = Some(CompressionType::ZSTD);
+    let options = IpcWriteOptions::default()
+        .try_with_compression(compression)
+        .unwrap();
+    StreamWriter::try_new_with_options(file, schema, options)
+}
+
+/// Open the table at `path` to append batches after those already committed, creating it if it
+/// does not exist. Anything after the last complete message, such as an end-of-stream marker or a
+/// batch cut short by a crash, is discarded. Fails if the table was written with another schema.
+pub(super) fn open_stream_writer(
+    path: &Path,
+    schema: &Schema,
+) -> Result<StreamWriter<File>, Error> {
+    let mut file = OpenOptions::new()
+        .read(true)
+        .append(true)
+        .create(true)
+        .open(path)?;
+    let end = complete(&mut file)?;
+    if end > 0 {
+        let reader = StreamReader::try_new(File::open(path)?, None)?;
+        if reader.schema().fields() != schema.fields() {
+            let path = path.display();
+            return Err(Error::ParseError(format!(
+                "'{path}' has a different schema"
+            )));
+        }
+    }
+    file.set_len(end)?;
+    let mut stream = new_stream_writer(file, schema)?;
+    if end > 0 {
+        stream.get_mut().set_len(end)?; // Drop the repeated schema message
+    }
+    Ok(stream)
+}
+
+/* ----------------------------------------------------------------------------- Private Helpers */
+
+/// Length in bytes of the complete messages at the start of an IPC stream `file`.
+fn complete(file: &mut File) -> Result<u64, Error> {
+    let size = file.metadata()?.len();
+    let mut reader = BufReader::new(file);
+    reader.seek(SeekFrom::Start(0))?;
+    let mut end = 0;
+    loop {
+        let mut prefix = [0; 8];
+        if reader.read_exact(&mut prefix).is_err() || prefix[..4] != CONTINUATION {
+            break;
+        }
+        let length = u32::from_le_bytes(prefix[4..].try_into().unwrap()) as u64;
+        let mut metadata = vec![0; length as usize];
+        if length == 0 || end + 8 + length > size || reader.read_exact(&mut metadata).is_err() {
+            break; // End-of-stream marker or truncated metadata
+        }
+        let Ok(message) = root_as_message(&metadata) else {
+            break;
+        };
+        let next = end + 8 + length + message.bodyLength() as u64;
+        if next > size {
+            break; // Truncated body
+        }
+        reader.seek(SeekFrom::Start(next))?;
+        end = next;
+    }
+    Ok(end)
+}
+
+const CONTINUATION: [u8; 4] = [0xFF; 4];